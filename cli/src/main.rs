@@ -1,18 +1,18 @@
 use anyhow::Result;
-use grok_core::{AgentFactory, EventBus, Session};
-use tracing::info;
+use grok_core::session::resolve_dir;
+use grok_core::{AgentFactory, Config, EventBus, Session};
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize tracing - only log to stderr and filter out less important messages
-    tracing_subscriber::fmt()
-        .with_max_level(tracing::Level::WARN)
-        .with_writer(std::io::stderr)
-        .init();
+    // Initialize tracing - stderr only, WARN unless GROK_LOG / RUST_LOG says otherwise
+    grok_tui::utils::logging::init();
     
-    // For now, just launch the TUI
-    // In the future, this could parse command line arguments
-    // and decide whether to run in TUI mode, headless mode, etc.
+    // For now, just launch the TUI. The only flag is `--cwd <path>`, which points
+    // tools at another directory; later this could choose headless mode, etc.
+    let workspace_root = grok_tui::utils::args::cwd_flag(std::env::args().skip(1))
+        .map_err(anyhow::Error::msg)?
+        .map(|path| resolve_dir(&std::env::current_dir()?, &path).map_err(anyhow::Error::msg))
+        .transpose()?;
     
     // Create event bus for communication
     let event_bus = EventBus::new();
@@ -21,12 +21,28 @@ async fn main() -> Result<()> {
     // Load environment variables
     let _ = dotenvy::dotenv();
 
+    // Project settings from .grok/config.toml; env vars still take precedence
+    let project_dir = match &workspace_root {
+        Some(root) => root.clone(),
+        None => std::env::current_dir()?,
+    };
+    let config = match Config::load(&project_dir) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Warning: ignoring project config: {}", e);
+            Config::default()
+        }
+    };
+
     // Create OpenRouter agent (requires OPENROUTER_API_KEY)
-    let agent = AgentFactory::create_openrouter_from_env(event_sender.clone())
+    let agent = AgentFactory::create_openrouter_with_config(event_sender.clone(), &config)
         .map_err(|e| anyhow::anyhow!("Failed to create agent: {}. Make sure OPENROUTER_API_KEY is set.", e))?;
     
     // Create session
-    let session = Session::new(agent, event_sender.clone());
+    let mut session = Session::new(agent, event_sender.clone());
+    if let Some(root) = workspace_root {
+        session = session.with_workspace_root(root);
+    }
     
     // Create and run the TUI application
     let mut app = grok_tui::App::new(session, event_bus.into_receiver());
@@ -7,14 +7,41 @@
 //! If one provider returns a non-200 response, the agent automatically tries the next one
 //! until all providers are exhausted.
 
-use crate::agent::{Agent, AgentError, AgentInfo, AgentResponse, ResponseMetadata};
+use crate::agent::prompt::SystemPrompt;
+use crate::agent::{Agent, AgentError, AgentInfo, AgentResponse, ImageAttachment, ResponseMetadata, SubmitOptions, ToolChoice};
+use crate::config::{Config, ToolSettings};
 use crate::events::{AppEvent, EventSender, ToolName, TokenUsage};
 use crate::session::ChatMessage;
+use crate::tools::executors::floor_char_boundary;
 use crate::tools::{ToolExecutor, ToolRegistry};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use std::time::Instant;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Rate-limit cooldowns at or below this are waited out automatically (once)
+const AUTO_RETRY_MAX_SECS: u64 = 5;
+
+/// An identical tool call (same tool, same arguments) runs at most this many times per submission
+const MAX_IDENTICAL_TOOL_CALLS: usize = 3;
+
+/// Substrings of model ids known to accept image input
+const VISION_MODEL_MARKERS: &[&str] = &[
+    "grok-4", "grok-2-vision", "vision", "gpt-4o", "gpt-4.1", "claude-3", "claude-sonnet-4", "claude-opus-4",
+    "gemini", "pixtral", "llava", "qwen-vl", "qwen2.5-vl",
+];
+
+/// Default for `GROK_TRANSCRIPT_RESULT_MAX_BYTES`: the most of one tool result kept in the conversation
+pub(crate) const DEFAULT_TRANSCRIPT_RESULT_BYTES: usize = 64 * 1024;
+
+/// Service a [`ModelConfig`] sends its requests to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Provider {
+    OpenRouter,
+    VercelGateway,
+}
 
 #[derive(Debug, Clone)]
 pub struct ModelConfig {
@@ -22,13 +49,131 @@ pub struct ModelConfig {
     pub api_key: String,
     pub model: String,
     pub name: String,
+    pub provider: Provider,
+}
+
+/// Optional sampling controls forwarded to the model.
+/// Unset fields are omitted from the request so provider defaults apply.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SamplingConfig {
+    pub temperature: Option<f64>,
+    pub top_p: Option<f64>,
+    /// Only honoured by providers that support deterministic sampling
+    pub seed: Option<u64>,
+}
+
+impl SamplingConfig {
+    /// Read GROK_TEMPERATURE, GROK_TOP_P and GROK_SEED; missing or unparsable values are ignored
+    pub fn from_env() -> Self {
+        Self::from_vars(|key| std::env::var(key).ok())
+    }
+
+    /// Build from the variables `var` looks up by name, as [`SamplingConfig::from_env`] does
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> Self {
+        fn parse<T: std::str::FromStr>(value: Option<String>) -> Option<T> {
+            value.and_then(|v| v.trim().parse::<T>().ok())
+        }
+        Self {
+            temperature: parse(var("GROK_TEMPERATURE")),
+            top_p: parse(var("GROK_TOP_P")),
+            seed: parse(var("GROK_SEED")),
+        }
+    }
+
+    fn apply(&self, body: &mut Value) {
+        if let Some(temperature) = self.temperature {
+            body["temperature"] = json!(temperature);
+        }
+        if let Some(top_p) = self.top_p {
+            body["top_p"] = json!(top_p);
+        }
+        if let Some(seed) = self.seed {
+            body["seed"] = json!(seed);
+        }
+    }
+}
+
+/// Default `X-Title` sent to OpenRouter when `OPENROUTER_TITLE` is unset
+pub const DEFAULT_APP_TITLE: &str = "Grok Code";
+
+/// App attribution headers OpenRouter uses to credit usage on its dashboards
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attribution {
+    /// Sent as `HTTP-Referer`; usually the app's site or repository URL
+    pub referer: Option<String>,
+    /// Sent as `X-Title`
+    pub title: String,
+}
+
+impl Default for Attribution {
+    fn default() -> Self {
+        Self { referer: None, title: DEFAULT_APP_TITLE.to_string() }
+    }
+}
+
+impl Attribution {
+    /// Read OPENROUTER_REFERER and OPENROUTER_TITLE; blank values are ignored
+    pub fn from_env() -> Self {
+        let var = |key: &str| {
+            std::env::var(key)
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        Self {
+            referer: var("OPENROUTER_REFERER"),
+            title: var("OPENROUTER_TITLE").unwrap_or_else(|| DEFAULT_APP_TITLE.to_string()),
+        }
+    }
+
+    fn apply(&self, req: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        let req = req.header("X-Title", &self.title);
+        match &self.referer {
+            Some(referer) => req.header("HTTP-Referer", referer),
+            None => req,
+        }
+    }
+}
+
+/// Counts identical tool calls within one submission so a model stuck repeating
+/// the same call is told to stop instead of burning every remaining turn
+#[derive(Debug, Default)]
+struct LoopGuard {
+    seen: HashMap<(ToolName, String), usize>,
+}
+
+impl LoopGuard {
+    /// Record a call; once it has already run `MAX_IDENTICAL_TOOL_CALLS` times,
+    /// returns the warning to send back in place of running it again
+    fn check(&mut self, tool: &ToolName, args: &Value) -> Option<Value> {
+        let count = self.seen.entry((tool.clone(), args.to_string())).or_insert(0);
+        *count += 1;
+        (*count > MAX_IDENTICAL_TOOL_CALLS).then(|| {
+            json!({
+                "error": format!(
+                    "Loop detected: {} has already been called {} times with these exact arguments and was not run again. \
+                     Stop repeating this call; change the arguments or try a different approach.",
+                    tool.wire_name(),
+                    MAX_IDENTICAL_TOOL_CALLS
+                ),
+                "tool": tool.wire_name(),
+                "args": args
+            })
+        })
+    }
 }
 
 pub struct MultiModelAgent {
     info: AgentInfo,
     model_configs: Vec<ModelConfig>,
     event_sender: EventSender,
-    tools: ToolRegistry,
+    tools: Arc<ToolRegistry>,
+    sampling: SamplingConfig,
+    attribution: Attribution,
+    max_transcript_result_bytes: usize,
+    /// Applied to each turn's `ToolExecutor`; the defaults leave it reading the environment
+    tool_settings: ToolSettings,
+    system_prompt: RwLock<String>,
 }
 
 impl MultiModelAgent {
@@ -37,6 +182,9 @@ impl MultiModelAgent {
         model: String,
         event_sender: EventSender,
     ) -> anyhow::Result<Self> {
+        // Keys pasted into .env often carry stray whitespace
+        let api_key = api_key.trim().to_string();
+
         // Build model configurations with fallback support
         let mut model_configs = Vec::new();
         
@@ -46,6 +194,7 @@ impl MultiModelAgent {
             api_key: api_key.clone(),
             model: model.clone(),
             name: "OpenRouter".to_string(),
+            provider: Provider::OpenRouter,
         });
         
         // Vercel AI Gateway config (if available)
@@ -53,9 +202,10 @@ impl MultiModelAgent {
             if let Ok(vercel_model) = std::env::var("VERCEL_AI_GATEWAY_MODEL") {
                 model_configs.push(ModelConfig {
                     base_url: "https://ai-gateway.vercel.sh/v1/chat/completions".to_string(),
-                    api_key: vercel_api_key,
-                    model: vercel_model,
+                    api_key: vercel_api_key.trim().to_string(),
+                    model: vercel_model.trim().to_string(),
                     name: "Vercel AI Gateway".to_string(),
+                    provider: Provider::VercelGateway,
                 });
             }
         }
@@ -67,8 +217,20 @@ impl MultiModelAgent {
                 api_key,
                 model,
                 name: "OpenRouter Fallback".to_string(),
+                provider: Provider::OpenRouter,
             });
         }
+
+        // A config without a key can only fail with 401, so skip it up front
+        model_configs.retain(|config| !config.api_key.is_empty());
+        if model_configs.is_empty() {
+            return Err(AgentError::Configuration(
+                "OPENROUTER_API_KEY is empty. Set it to a key from https://openrouter.ai/keys \
+                 (or configure VERCEL_AI_GATEWAY_API_KEY and VERCEL_AI_GATEWAY_MODEL)"
+                    .to_string(),
+            )
+            .into());
+        }
         
         Ok(Self {
             info: AgentInfo {
@@ -78,28 +240,71 @@ impl MultiModelAgent {
             },
             model_configs,
             event_sender,
-            tools: ToolRegistry::new(),
+            tools: ToolRegistry::shared(),
+            sampling: SamplingConfig::from_env(),
+            attribution: Attribution::from_env(),
+            max_transcript_result_bytes: std::env::var("GROK_TRANSCRIPT_RESULT_MAX_BYTES")
+                .ok()
+                .and_then(|v| v.trim().parse().ok())
+                .unwrap_or(DEFAULT_TRANSCRIPT_RESULT_BYTES),
+            tool_settings: ToolSettings::default(),
+            system_prompt: RwLock::new(SystemPrompt::load(&std::env::current_dir().unwrap_or_default()).text),
         })
     }
 
-    fn tool_name_from_string(&self, name: &str) -> Option<ToolName> {
-        match name {
-            "fs.read" => Some(ToolName::FsRead),
-            "fs.search" => Some(ToolName::FsSearch),
-            "fs.write" => Some(ToolName::FsWrite),
-            "fs.apply_patch" => Some(ToolName::FsApplyPatch),
-            "fs.set_file" => Some(ToolName::FsSetFile),
-            "fs.replace_once" => Some(ToolName::FsReplaceOnce),
-            "fs.insert_before" => Some(ToolName::FsInsertBefore),
-            "fs.insert_after" => Some(ToolName::FsInsertAfter),
-            "fs.delete_file" => Some(ToolName::FsDeleteFile),
-            "fs.rename_file" => Some(ToolName::FsRenameFile),
-            "fs.find" => Some(ToolName::FsFind),
-            "shell.exec" => Some(ToolName::ShellExec),
-            "code.symbols" => Some(ToolName::CodeSymbols),
-            "large_context_fetch" => Some(ToolName::LargeContextFetch),
-            _ => None,
+    /// Build an agent whose model, sampling and tool settings come from `config`, with env
+    /// vars taking precedence
+    pub fn from_config(api_key: String, config: &Config, event_sender: EventSender) -> anyhow::Result<Self> {
+        Ok(Self::new(api_key, config.resolved_model(), event_sender)?
+            .with_sampling(config.resolved_sampling())
+            .with_tool_settings(config.resolved_tools()))
+    }
+
+    /// Settings applied to the tool executor of every turn
+    pub fn with_tool_settings(mut self, settings: ToolSettings) -> Self {
+        self.tool_settings = settings;
+        self
+    }
+
+    /// Override the sampling parameters read from the environment
+    pub fn with_sampling(mut self, sampling: SamplingConfig) -> Self {
+        self.sampling = sampling;
+        self
+    }
+
+    /// Override the OpenRouter attribution headers read from the environment
+    pub fn with_attribution(mut self, attribution: Attribution) -> Self {
+        self.attribution = attribution;
+        self
+    }
+
+    /// The outgoing user message; with attachments its content becomes an array of parts
+    fn user_message(&self, text: &str, attachments: &[ImageAttachment]) -> Value {
+        if attachments.is_empty() {
+            return json!({ "role": "user", "content": text });
         }
+        let mut parts = vec![json!({ "type": "text", "text": text })];
+        parts.extend(attachments.iter().map(|image| {
+            json!({ "type": "image_url", "image_url": { "url": image.data_uri() } })
+        }));
+        json!({ "role": "user", "content": parts })
+    }
+
+    /// Cap how much of each tool result is appended to the conversation
+    pub fn with_max_transcript_result_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_transcript_result_bytes = max_bytes;
+        self
+    }
+
+    /// Tool message for the transcript. The executor already caps each result, but
+    /// several of them add up over a turn, so each one is held to its own budget here.
+    fn tool_result_message(&self, call_id: &str, result: &Value) -> Value {
+        let content = serde_json::to_string(result).unwrap_or_else(|_| "{}".to_string());
+        json!({
+            "role": "tool",
+            "tool_call_id": call_id,
+            "content": cap_transcript_content(content, self.max_transcript_result_bytes)
+        })
     }
 
     fn tool_specs_for_openai(&self) -> Vec<Value> {
@@ -107,22 +312,7 @@ impl MultiModelAgent {
             .get_all_specs()
             .into_iter()
             .map(|spec| {
-                let name = match spec.name {
-                    ToolName::FsRead => "fs.read",
-                    ToolName::FsSearch => "fs.search",
-                    ToolName::FsWrite => "fs.write",
-                    ToolName::FsApplyPatch => "fs.apply_patch",
-                    ToolName::FsSetFile => "fs.set_file",
-                    ToolName::FsReplaceOnce => "fs.replace_once",
-                    ToolName::FsInsertBefore => "fs.insert_before",
-                    ToolName::FsInsertAfter => "fs.insert_after",
-                    ToolName::FsDeleteFile => "fs.delete_file",
-                    ToolName::FsRenameFile => "fs.rename_file",
-                    ToolName::FsFind => "fs.find",
-                    ToolName::ShellExec => "shell.exec",
-                    ToolName::CodeSymbols => "code.symbols",
-                    ToolName::LargeContextFetch => "large_context_fetch",
-                };
+                let name = spec.name.wire_name();
                 json!({
                     "type": "function",
                     "function": {
@@ -135,13 +325,37 @@ impl MultiModelAgent {
             .collect()
     }
 
+    fn build_request_body(&self, messages: &[Value], tools: &[Value], tool_choice: &ToolChoice) -> Value {
+        let mut body = json!({
+            "model": self.model_configs[0].model, // Will be updated in http_post for each config
+            "messages": messages,
+            "tools": tools,
+            "tool_choice": tool_choice.to_json()
+        });
+        self.sampling.apply(&mut body);
+        body
+    }
+
     fn get_system_prompt(&self) -> String {
-        include_str!("../prompts/system_prompt.md").to_string()
+        self.system_prompt.read().map(|prompt| prompt.clone()).unwrap_or_default()
+    }
+
+    /// System prompt, history, and the current user message that open a submission
+    fn seed_messages(&self, message: &str, history: &[ChatMessage], options: &SubmitOptions) -> Vec<Value> {
+        let mut messages = vec![json!({
+            "role": "system",
+            "content": self.get_system_prompt()
+        })];
+        messages.extend(self.convert_history(history));
+        messages.push(self.user_message(message, &options.attachments));
+        messages
     }
 
     fn convert_history(&self, history: &[ChatMessage]) -> Vec<Value> {
         history
             .iter()
+            // File previews are for the user only
+            .filter(|m| m.kind != crate::session::MessageKind::FilePreview)
             .map(|m| {
                 let role = match m.role {
                     crate::session::MessageRole::User => "user",
@@ -188,8 +402,22 @@ impl MultiModelAgent {
     }
 
     async fn http_post(&self, body: &Value) -> Result<ChatCompletionResponse, AgentError> {
+        match self.http_post_once(body).await {
+            Err(AgentError::RateLimited(secs)) if secs <= AUTO_RETRY_MAX_SECS => {
+                let _ = self.event_sender.send(AppEvent::Notice(format!("All providers rate limited, retrying in {}s...", secs)));
+                tokio::time::sleep(Duration::from_secs(secs)).await;
+                self.http_post_once(body).await
+            }
+            result => result,
+        }
+    }
+
+    async fn http_post_once(&self, body: &Value) -> Result<ChatCompletionResponse, AgentError> {
         let client = reqwest::Client::new();
         let mut last_error = None;
+        // Soonest cooldown, kept only while every provider has answered with 429
+        let mut all_rate_limited = true;
+        let mut soonest_retry: Option<u64> = None;
         
         // Try each model config until one succeeds
         for (i, config) in self.model_configs.iter().enumerate() {
@@ -199,22 +427,23 @@ impl MultiModelAgent {
                 *model_obj = json!(config.model);
             }
             
-            let req = client
+            let mut req = client
                 .post(&config.base_url)
                 .bearer_auth(&config.api_key)
                 .header("Content-Type", "application/json");
+            if config.provider == Provider::OpenRouter {
+                req = self.attribution.apply(req);
+            }
 
             let resp = match req.json(&request_body).send().await {
                 Ok(resp) => resp,
                 Err(e) => {
                     let error_msg = format!("{} request error: {}", config.name, e);
                     last_error = Some(error_msg.clone());
+                    all_rate_limited = false;
                     
                     // Log the error but continue to next config
-                    let _ = self.event_sender.send(AppEvent::Error { 
-                        id: None, 
-                        message: format!("Failed to connect to {}, trying next provider...", config.name)
-                    });
+                    let _ = self.event_sender.send(AppEvent::Notice(format!("Failed to connect to {}, trying next provider...", config.name)));
                     continue;
                 }
             };
@@ -224,35 +453,48 @@ impl MultiModelAgent {
                     Ok(parsed) => {
                         // Success! Log which provider was used
                         if i > 0 {
-                            let _ = self.event_sender.send(AppEvent::Error { 
-                                id: None, 
-                                message: format!("Successfully using {} after {} failed attempts", config.name, i)
-                            });
+                            let _ = self.event_sender.send(AppEvent::Notice(format!("Successfully using {} after {} failed attempts", config.name, i)));
                         }
                         return Ok(parsed);
                     }
                     Err(e) => {
                         let error_msg = format!("{} decode error: {}", config.name, e);
                         last_error = Some(error_msg);
+                        all_rate_limited = false;
                         continue;
                     }
                 }
             } else {
                 let status = resp.status();
+                if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+                    if let Some(secs) = parse_retry_after(resp.headers(), SystemTime::now()) {
+                        soonest_retry = Some(soonest_retry.map_or(secs, |s| s.min(secs)));
+                    }
+                } else {
+                    all_rate_limited = false;
+                }
                 let text = resp.text().await.unwrap_or_default();
+
+                // The history itself is the problem, so other providers won't fare better
+                if let Some(err) = classify_error_response(status, &text) {
+                    return Err(err);
+                }
+
                 let error_msg = format!("{} HTTP {}: {}", config.name, status, text);
                 last_error = Some(error_msg.clone());
                 
                 // Log non-success status but continue to next config
-                let _ = self.event_sender.send(AppEvent::Error { 
-                    id: None, 
-                    message: format!("{} returned {}, trying next provider...", config.name, status)
-                });
+                let _ = self.event_sender.send(AppEvent::Notice(format!("{} returned {}, trying next provider...", config.name, status)));
                 continue;
             }
         }
         
         // All configs failed
+        if all_rate_limited {
+            if let Some(secs) = soonest_retry {
+                return Err(AgentError::RateLimited(secs));
+            }
+        }
         Err(AgentError::Network(
             last_error.unwrap_or_else(|| "All model providers failed".to_string())
         ))
@@ -265,42 +507,43 @@ impl Agent for MultiModelAgent {
         &self,
         message: String,
         history: Vec<ChatMessage>,
+        options: SubmitOptions,
     ) -> Result<AgentResponse, AgentError> {
         let start = Instant::now();
 
-        // Seed with system prompt, history, and current user message
-        let mut messages = vec![json!({
-            "role": "system",
-            "content": self.get_system_prompt()
-        })];
-        messages.extend(self.convert_history(&history));
-        messages.push(json!({ "role": "user", "content": message }));
+        let mut messages = self.seed_messages(&message, &history, &options);
 
         let tools = self.tool_specs_for_openai();
         let mut turns = 0usize;
         let mut final_text = String::new();
         let mut token_usage: Option<TokenUsage> = None;
+        // Model named by the latest response; providers may substitute another model
+        let mut model: Option<String>;
+        let mut loop_guard = LoopGuard::default();
 
         loop {
             turns += 1;
 
-            let body = json!({
-                "model": self.model_configs[0].model, // Will be updated in http_post for each config
-                "messages": messages,
-                "tools": tools,
-                "tool_choice": "auto"
-            });
+            // A forced tool only applies to the first turn; afterwards let the model answer
+            let tool_choice = match &options.tool_choice {
+                ToolChoice::Function(_) if turns > 1 => ToolChoice::Auto,
+                choice => choice.clone(),
+            };
+            let body = self.build_request_body(&messages, &tools, &tool_choice);
 
             // First turn event
             if turns == 1 { let _ = self.event_sender.send(AppEvent::ChatCreated); }
 
             let resp = self.http_post(&body).await?;
+            model = Some(resp.model.clone()).filter(|m| !m.is_empty());
 
+            // Every turn is billed, so usage adds up over the whole exchange
             if let Some(usage) = resp.usage.clone() {
+                let so_far = token_usage.unwrap_or(TokenUsage { input_tokens: 0, output_tokens: 0, total_tokens: 0 });
                 token_usage = Some(TokenUsage {
-                    input_tokens: usage.prompt_tokens as u32,
-                    output_tokens: usage.completion_tokens as u32,
-                    total_tokens: usage.total_tokens as u32,
+                    input_tokens: so_far.input_tokens.saturating_add(usage.prompt_tokens as u32),
+                    output_tokens: so_far.output_tokens.saturating_add(usage.completion_tokens as u32),
+                    total_tokens: so_far.total_tokens.saturating_add(usage.total_tokens as u32),
                 });
             }
 
@@ -318,12 +561,16 @@ impl Agent for MultiModelAgent {
                         "tool_calls": tool_calls
                     }));
 
+                    // 1MB limit unless GROK_TOOL_MAX_OUTPUT_SIZE (or the project config) says otherwise
                     let executor = ToolExecutor::new(self.event_sender.clone())
-                        .with_max_output_size(1024 * 1024); // 1MB limit, can be overridden by GROK_TOOL_MAX_OUTPUT_SIZE env var
+                        .with_tool_settings(&self.tool_settings)
+                        .with_root(options.workspace_root.clone())
+                        .with_read_only(options.read_only)
+                        .with_patch_approval(options.patch_approval);
                     
                     for call in tool_calls {
                         let name = call.function.name;
-                        let tool_name = self.tool_name_from_string(&name)
+                        let tool_name = ToolName::from_wire_name(&name)
                             .ok_or_else(|| AgentError::Processing(format!("unknown tool: {}", name)))?;
                         let args: Value = serde_json::from_str(&call.function.arguments)
                             .map_err(|e| AgentError::Processing(format!("invalid tool args: {}", e)))?;
@@ -333,6 +580,15 @@ impl Agent for MultiModelAgent {
                             continue;
                         }
 
+                        if let Some(warning) = loop_guard.check(&tool_name, &args) {
+                            let _ = self.event_sender.send(AppEvent::Error {
+                                id: Some(call.id.clone()),
+                                message: format!("Skipped repeated {} call", tool_name.wire_name()),
+                            });
+                            messages.push(self.tool_result_message(&call.id, &warning));
+                            continue;
+                        }
+
                         // Execute tool and get result
                         let tool_result = match executor.execute_tool_with_result(call.id.clone(), tool_name.clone(), args.clone()).await {
                             Ok(result) => result,
@@ -347,11 +603,7 @@ impl Agent for MultiModelAgent {
                         };
 
                         // Add tool result to conversation following OpenRouter format
-                        messages.push(json!({
-                            "role": "tool",
-                            "tool_call_id": call.id,
-                            "content": serde_json::to_string(&tool_result).unwrap_or_else(|_| "{}".to_string())
-                        }));
+                        messages.push(self.tool_result_message(&call.id, &tool_result));
                     }
                     
                     // Continue loop for next assistant turn
@@ -381,20 +633,92 @@ impl Agent for MultiModelAgent {
         Ok(AgentResponse {
             content: final_text,
             metadata: ResponseMetadata::new()
-                .with_processing_time(start.elapsed()),
+                .with_processing_time(start.elapsed())
+                .with_tokens_used(token_usage.map(|usage| usage.total_tokens))
+                .with_model(model),
         })
     }
 
     fn info(&self) -> AgentInfo {
         self.info.clone()
     }
+
+    fn supports_vision(&self) -> bool {
+        let model = self.model_configs[0].model.to_ascii_lowercase();
+        VISION_MODEL_MARKERS.iter().any(|marker| model.contains(marker))
+    }
+
+    fn reload_prompt(&self, workspace_root: &std::path::Path) -> Result<String, String> {
+        let prompt = SystemPrompt::load(workspace_root);
+        let mut current = self.system_prompt.write().map_err(|_| "System prompt lock poisoned".to_string())?;
+        *current = prompt.text.clone();
+        Ok(prompt.describe())
+    }
+}
+
+/// Cut `content` to at most `max_bytes` (on a char boundary), ending with a marker
+/// that tells the model how much was dropped
+fn cap_transcript_content(content: String, max_bytes: usize) -> String {
+    if content.len() <= max_bytes {
+        return content;
+    }
+    let marker = |omitted: usize| format!("\n[tool result truncated: {} of {} bytes omitted]", omitted, content.len());
+    // The marker's own length depends on the omitted count; size it for the worst case
+    let keep = floor_char_boundary(&content, max_bytes.saturating_sub(marker(content.len()).len()));
+    format!("{}{}", &content[..keep], marker(content.len() - keep))
+}
+
+/// Seconds until a rate-limited provider accepts requests again.
+/// Reads `Retry-After` (delay in seconds) and falls back to `X-RateLimit-Reset`,
+/// which providers send as a unix timestamp in seconds or milliseconds.
+fn parse_retry_after(headers: &reqwest::header::HeaderMap, now: SystemTime) -> Option<u64> {
+    let header_u64 = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<f64>().ok())
+            .filter(|v| v.is_finite() && *v >= 0.0)
+            .map(|v| v.ceil() as u64)
+    };
+
+    if let Some(secs) = header_u64("retry-after") {
+        return Some(secs);
+    }
+
+    let reset = header_u64("x-ratelimit-reset")?;
+    let now_secs = now.duration_since(SystemTime::UNIX_EPOCH).ok()?.as_secs();
+    let reset_secs = if reset > 1_000_000_000_000 { reset.div_ceil(1000) } else { reset };
+    if reset_secs > 1_000_000_000 {
+        Some(reset_secs.saturating_sub(now_secs))
+    } else {
+        // Small values are relative delays rather than timestamps
+        Some(reset_secs)
+    }
+}
+
+/// Map a non-success response to a specific error when retrying with another provider is pointless
+fn classify_error_response(status: reqwest::StatusCode, body: &str) -> Option<AgentError> {
+    const CONTEXT_PATTERNS: [&str; 5] = [
+        "context length",
+        "context_length",
+        "context window",
+        "maximum context",
+        "too many tokens",
+    ];
+
+    let is_client_error = status == reqwest::StatusCode::BAD_REQUEST
+        || status == reqwest::StatusCode::PAYLOAD_TOO_LARGE;
+    let lower = body.to_lowercase();
+    if is_client_error && CONTEXT_PATTERNS.iter().any(|p| lower.contains(p)) {
+        return Some(AgentError::ContextTooLarge(format!("HTTP {}: {}", status, body)));
+    }
+    None
 }
 
 #[derive(Debug, Clone, Deserialize)]
 struct ChatCompletionResponse {
     #[allow(dead_code)]
     id: String,
-    #[allow(dead_code)]
     model: String,
     #[serde(default)]
     usage: Option<TokenUsageResponse>,
@@ -433,4 +757,495 @@ struct ToolCall {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct FunctionCall { name: String, arguments: String }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::EventBus;
+
+    fn test_agent() -> MultiModelAgent {
+        let bus = EventBus::new();
+        MultiModelAgent::new("test-key".to_string(), "test-model".to_string(), bus.sender())
+            .expect("agent should build")
+    }
 
+    /// Answer one HTTP request with `status` and a JSON `body`; the handle yields the
+    /// request head, lowercased
+    async fn serve_once(status: &'static str, body: Value) -> (String, tokio::task::JoinHandle<String>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/chat/completions", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                let n = socket.read(&mut buf).await.unwrap();
+                if n == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..n]);
+            }
+            let body = body.to_string();
+            let response = format!(
+                "HTTP/1.1 {}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            let head = String::from_utf8_lossy(&request).to_lowercase();
+            head.split("\r\n\r\n").next().unwrap_or_default().to_string()
+        });
+        (url, server)
+    }
+
+    /// Answer consecutive requests with `bodies` in order, all `200 OK`
+    async fn serve_sequence(bodies: Vec<Value>) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/chat/completions", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            for body in bodies {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0u8; 4096];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    if n == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buf[..n]);
+                }
+                let body = body.to_string();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        url
+    }
+
+    /// Agent whose only provider is the mock at `url`
+    fn mock_agent(url: String) -> MultiModelAgent {
+        let mut agent = test_agent();
+        agent.model_configs.truncate(1);
+        agent.model_configs[0].base_url = url;
+        agent
+    }
+
+    #[tokio::test]
+    async fn test_openrouter_requests_carry_attribution_headers() {
+        let (url, server) = serve_once("400 Bad Request", json!({})).await;
+        let agent = mock_agent(url).with_attribution(Attribution {
+            referer: Some("https://example.com/grok".to_string()),
+            title: "Grok Test".to_string(),
+        });
+        let _ = agent.http_post_once(&json!({ "model": "test-model" })).await;
+
+        let request = server.await.unwrap();
+        assert!(request.contains("x-title: grok test"), "{}", request);
+        assert!(request.contains("http-referer: https://example.com/grok"), "{}", request);
+    }
+
+    #[tokio::test]
+    async fn test_other_providers_skip_attribution_headers() {
+        let (url, server) = serve_once("400 Bad Request", json!({})).await;
+        let mut agent = mock_agent(url);
+        agent.model_configs[0].provider = Provider::VercelGateway;
+        let _ = agent.http_post_once(&json!({ "model": "test-model" })).await;
+
+        let request = server.await.unwrap();
+        assert!(!request.contains("x-title"), "{}", request);
+    }
+
+    #[tokio::test]
+    async fn test_response_usage_reaches_metadata() {
+        let (url, _server) = serve_once("200 OK", json!({
+            "id": "gen-2",
+            "model": "test-model",
+            "usage": { "prompt_tokens": 120, "completion_tokens": 30, "total_tokens": 150 },
+            "choices": [{ "finish_reason": "stop", "message": { "role": "assistant", "content": "ok" } }]
+        })).await;
+        let agent = mock_agent(url);
+
+        let response = agent.submit("hi".to_string(), Vec::new(), SubmitOptions::default()).await.unwrap();
+        assert_eq!(response.metadata.tokens_used, Some(150));
+    }
+
+    #[tokio::test]
+    async fn test_usage_adds_up_across_tool_turns() {
+        let url = serve_sequence(vec![
+            json!({
+                "id": "gen-1",
+                "model": "test-model",
+                "usage": { "prompt_tokens": 100, "completion_tokens": 20, "total_tokens": 120 },
+                "choices": [{ "finish_reason": "tool_calls", "message": { "role": "assistant", "content": null, "tool_calls": [{
+                    "id": "call_1",
+                    "type": "function",
+                    "function": { "name": "fs.read", "arguments": "{}" }
+                }] } }]
+            }),
+            json!({
+                "id": "gen-2",
+                "model": "test-model",
+                "usage": { "prompt_tokens": 130, "completion_tokens": 10, "total_tokens": 140 },
+                "choices": [{ "finish_reason": "stop", "message": { "role": "assistant", "content": "done" } }]
+            }),
+        ]).await;
+        let agent = mock_agent(url);
+
+        let response = agent.submit("hi".to_string(), Vec::new(), SubmitOptions::default()).await.unwrap();
+        assert_eq!(response.content, "done");
+        assert_eq!(response.metadata.tokens_used, Some(260));
+    }
+
+    #[tokio::test]
+    async fn test_response_model_reaches_metadata() {
+        let (url, _server) = serve_once("200 OK", json!({
+            "id": "gen-1",
+            "model": "x-ai/grok-4-substitute",
+            "choices": [{ "finish_reason": "stop", "message": { "role": "assistant", "content": "hello" } }]
+        })).await;
+        let agent = mock_agent(url);
+
+        let response = agent.submit("hi".to_string(), Vec::new(), SubmitOptions::default()).await.unwrap();
+        assert_eq!(response.content, "hello");
+        assert_eq!(response.metadata.model.as_deref(), Some("x-ai/grok-4-substitute"));
+    }
+
+    #[test]
+    fn test_attribution_defaults_title() {
+        let attribution = Attribution::default();
+        assert_eq!(attribution.title, "Grok Code");
+        assert!(attribution.referer.is_none());
+    }
+
+    #[test]
+    fn test_empty_api_key_is_rejected() {
+        let bus = EventBus::new();
+        let err = MultiModelAgent::new("   ".to_string(), "test-model".to_string(), bus.sender())
+            .err()
+            .expect("an agent without any key should not build");
+        let err = err.downcast::<AgentError>().expect("should be an AgentError");
+        assert!(matches!(&err, AgentError::Configuration(msg) if msg.contains("OPENROUTER_API_KEY is empty")), "{}", err);
+    }
+
+    #[test]
+    fn test_api_key_is_trimmed() {
+        let bus = EventBus::new();
+        let agent = MultiModelAgent::new(" sk-test \n".to_string(), "test-model".to_string(), bus.sender()).unwrap();
+        assert_eq!(agent.model_configs[0].api_key, "sk-test");
+    }
+
+    #[test]
+    fn test_convert_history_formats_tool_messages() {
+        use crate::session::{MessageKind, MessageRole, ToolMessageInfo, ToolStatus};
+
+        let agent = test_agent();
+        let message = |role, content: &str, tool_info| ChatMessage {
+            role,
+            content: content.to_string(),
+            timestamp_secs: 0,
+            tool_info,
+            pinned: false,
+            kind: MessageKind::Text,
+        };
+        let tool_info = ToolMessageInfo {
+            id: "call_1".to_string(),
+            tool: ToolName::ShellExec,
+            summary: "Executing: ls".to_string(),
+            args: Some(json!({ "command": ["ls"] })),
+            start_time: SystemTime::UNIX_EPOCH,
+            status: ToolStatus::Completed,
+            stdout: "Cargo.toml\n".to_string(),
+            stderr: String::new(),
+            result: None,
+            result_bytes: None,
+        };
+        let preview = ChatMessage {
+            kind: MessageKind::FilePreview,
+            ..message(MessageRole::System, "Preview: src/main.rs:1 (lines 1-1 of 1)", None)
+        };
+        let history = vec![
+            message(MessageRole::User, "list files", None),
+            preview,
+            message(MessageRole::Tool, "Executing: ls", Some(tool_info)),
+            message(MessageRole::Tool, "orphaned", None),
+        ];
+
+        let converted = agent.convert_history(&history);
+        assert_eq!(converted[0], json!({ "role": "user", "content": "list files" }));
+        assert_eq!(converted[1]["role"], "tool");
+        assert_eq!(converted[1]["tool_call_id"], "call_1");
+        let payload: Value = serde_json::from_str(converted[1]["content"].as_str().unwrap()).unwrap();
+        assert_eq!(payload, json!({ "stdout": "Cargo.toml\n", "stderr": "" }));
+        // Without tool info there is no call id to attach
+        assert_eq!(converted[2], json!({ "role": "tool", "content": "orphaned" }));
+        // The preview never reaches the model
+        assert_eq!(converted.len(), 3);
+    }
+
+    #[test]
+    fn test_reload_prompt_updates_next_seed() {
+        let agent = test_agent();
+        let root = tempfile::tempdir().unwrap();
+        std::fs::create_dir(root.path().join(".grok")).unwrap();
+        let override_path = root.path().join(crate::agent::prompt::SYSTEM_PROMPT_OVERRIDE);
+        std::fs::write(&override_path, "You are the first prompt.").unwrap();
+        assert_eq!(agent.reload_prompt(root.path()).unwrap(), ".grok/system_prompt.md");
+
+        std::fs::write(&override_path, "You are the edited prompt.").unwrap();
+        std::fs::write(root.path().join("AGENTS.md"), "Run cargo fmt before committing.").unwrap();
+        // The file change alone doesn't reach the agent until it reloads
+        let seed = agent.seed_messages("hi", &[], &SubmitOptions::default());
+        assert_eq!(seed[0]["content"], "You are the first prompt.");
+
+        assert_eq!(agent.reload_prompt(root.path()).unwrap(), ".grok/system_prompt.md + AGENTS.md");
+        let seed = agent.seed_messages("hi", &[], &SubmitOptions::default());
+        let system = seed[0]["content"].as_str().unwrap();
+        assert!(system.starts_with("You are the edited prompt."));
+        assert!(system.ends_with("Run cargo fmt before committing."));
+        assert_eq!(seed.last().unwrap()["content"], "hi");
+
+        // Removing the override falls back to the built-in prompt
+        std::fs::remove_file(&override_path).unwrap();
+        std::fs::remove_file(root.path().join("AGENTS.md")).unwrap();
+        assert_eq!(agent.reload_prompt(root.path()).unwrap(), "built-in prompt");
+        let seed = agent.seed_messages("hi", &[], &SubmitOptions::default());
+        assert_eq!(seed[0]["content"], crate::agent::prompt::DEFAULT_SYSTEM_PROMPT);
+    }
+
+    #[test]
+    fn test_session_workspace_root_loads_its_prompt() {
+        let agent = std::sync::Arc::new(test_agent());
+        let root = tempfile::tempdir().unwrap();
+        std::fs::create_dir(root.path().join(".grok")).unwrap();
+        std::fs::write(root.path().join(crate::agent::prompt::SYSTEM_PROMPT_OVERRIDE), "You are the --cwd prompt.").unwrap();
+
+        let _session = crate::session::Session::new(agent.clone(), EventBus::new().sender())
+            .with_workspace_root(root.path().to_path_buf());
+        let seed = agent.seed_messages("hi", &[], &SubmitOptions::default());
+        assert_eq!(seed[0]["content"], "You are the --cwd prompt.");
+    }
+
+    #[test]
+    fn test_session_cd_loads_the_new_roots_agents_md() {
+        let agent = std::sync::Arc::new(test_agent());
+        let first = tempfile::tempdir().unwrap();
+        let second = tempfile::tempdir().unwrap();
+        std::fs::write(first.path().join("AGENTS.md"), "Use tabs.").unwrap();
+        std::fs::write(second.path().join("AGENTS.md"), "Use spaces.").unwrap();
+
+        let mut session = crate::session::Session::new(agent.clone(), EventBus::new().sender())
+            .with_workspace_root(first.path().to_path_buf());
+        let seed = agent.seed_messages("hi", &[], &SubmitOptions::default());
+        assert!(seed[0]["content"].as_str().unwrap().ends_with("Use tabs."));
+
+        // `/cd` goes through set_workspace_root
+        session.set_workspace_root(&second.path().to_string_lossy()).unwrap();
+        let seed = agent.seed_messages("hi", &[], &SubmitOptions::default());
+        assert!(seed[0]["content"].as_str().unwrap().ends_with("Use spaces."));
+    }
+
+    #[test]
+    fn test_user_message_includes_image_parts() {
+        let agent = test_agent();
+        let image = ImageAttachment {
+            name: "shot.png".to_string(),
+            mime_type: "image/png".to_string(),
+            data: "iVBORw0KGgo=".to_string(),
+        };
+
+        let message = agent.user_message("what is this?", std::slice::from_ref(&image));
+        assert_eq!(message["role"], "user");
+        let parts = message["content"].as_array().unwrap();
+        assert_eq!(parts[0], json!({ "type": "text", "text": "what is this?" }));
+        assert_eq!(parts[1]["type"], "image_url");
+        assert_eq!(parts[1]["image_url"]["url"], "data:image/png;base64,iVBORw0KGgo=");
+
+        // Without attachments the content stays a plain string
+        assert_eq!(agent.user_message("hi", &[])["content"], "hi");
+    }
+
+    #[test]
+    fn test_vision_support_follows_model() {
+        let bus = EventBus::new();
+        let vision = MultiModelAgent::new("k".to_string(), "openai/gpt-4o-mini".to_string(), bus.sender()).unwrap();
+        assert!(vision.supports_vision());
+        assert!(!test_agent().supports_vision());
+    }
+
+    #[test]
+    fn test_repeated_identical_tool_call_is_broken() {
+        let agent = test_agent();
+        let mut guard = LoopGuard::default();
+        let args = json!({ "path": "missing.rs" });
+
+        for _ in 0..MAX_IDENTICAL_TOOL_CALLS {
+            assert!(guard.check(&ToolName::FsRead, &args).is_none());
+        }
+        // A different call is tracked separately
+        assert!(guard.check(&ToolName::FsRead, &json!({ "path": "other.rs" })).is_none());
+
+        let warning = guard.check(&ToolName::FsRead, &args).expect("repeat should be flagged");
+        let message = agent.tool_result_message("call-4", &warning);
+        assert_eq!(message["role"], "tool");
+        assert_eq!(message["tool_call_id"], "call-4");
+        assert!(message["content"].as_str().unwrap().contains("Loop detected: fs.read"));
+    }
+
+    #[test]
+    fn test_oversized_tool_results_are_capped_in_transcript() {
+        let agent = test_agent().with_max_transcript_result_bytes(1024);
+        let huge = json!({ "contents": "é".repeat(50_000) });
+
+        let messages: Vec<Value> = (0..10)
+            .map(|i| agent.tool_result_message(&format!("call-{}", i), &huge))
+            .collect();
+
+        for message in &messages {
+            let content = message["content"].as_str().unwrap();
+            assert!(content.len() <= 1024, "content was {} bytes", content.len());
+            assert!(content.contains("[tool result truncated:"));
+        }
+        let total: usize = messages.iter().map(|m| m["content"].as_str().unwrap().len()).sum();
+        assert!(total <= 10 * 1024);
+
+        // Small results pass through untouched
+        let small = agent.tool_result_message("call-small", &json!({ "ok": true }));
+        assert_eq!(small["content"], "{\"ok\":true}");
+    }
+
+    #[test]
+    fn test_config_values_reach_agent() {
+        let config = Config::parse(
+            "[agent]\nmodel = \"project-model\"\ntemperature = 0.4\nseed = 9\n[tools]\nexcludes = [\"vendor/\"]\n",
+        )
+        .unwrap();
+        let bus = EventBus::new();
+        let agent = MultiModelAgent::from_config("test-key".to_string(), &config, bus.sender())
+            .expect("agent should build");
+        let body = agent.build_request_body(&[], &[], &ToolChoice::Auto);
+
+        assert_eq!(body["model"], "project-model");
+        assert_eq!(body["temperature"], json!(0.4));
+        assert_eq!(body["seed"], json!(9));
+        assert_eq!(agent.tool_settings.excludes, ["vendor/"]);
+    }
+
+    #[test]
+    fn test_request_body_omits_sampling_when_unset() {
+        let agent = test_agent().with_sampling(SamplingConfig::default());
+        let body = agent.build_request_body(&[], &[], &ToolChoice::Auto);
+
+        assert!(body.get("temperature").is_none());
+        assert!(body.get("top_p").is_none());
+        assert!(body.get("seed").is_none());
+        assert_eq!(body["tool_choice"], "auto");
+    }
+
+    #[test]
+    fn test_request_body_includes_configured_sampling() {
+        let agent = test_agent().with_sampling(SamplingConfig {
+            temperature: Some(0.2),
+            top_p: Some(0.9),
+            seed: None,
+        });
+        let body = agent.build_request_body(&[], &[], &ToolChoice::Auto);
+
+        assert_eq!(body["temperature"], json!(0.2));
+        assert_eq!(body["top_p"], json!(0.9));
+    }
+
+    #[test]
+    fn test_request_body_includes_only_configured_field() {
+        let agent = test_agent().with_sampling(SamplingConfig {
+            temperature: None,
+            top_p: Some(0.5),
+            seed: None,
+        });
+        let body = agent.build_request_body(&[], &[], &ToolChoice::Auto);
+
+        assert!(body.get("temperature").is_none());
+        assert_eq!(body["top_p"], json!(0.5));
+    }
+
+    #[test]
+    fn test_request_body_includes_seed_when_configured() {
+        let agent = test_agent().with_sampling(SamplingConfig {
+            seed: Some(42),
+            ..Default::default()
+        });
+        let body = agent.build_request_body(&[], &[], &ToolChoice::Auto);
+
+        assert_eq!(body["seed"], json!(42));
+        assert!(body.get("temperature").is_none());
+    }
+
+    #[test]
+    fn test_sampling_from_vars_reads_seed() {
+        let with_seed = SamplingConfig::from_vars(|key| (key == "GROK_SEED").then(|| " 1234 ".to_string()));
+        let bad_seed = SamplingConfig::from_vars(|key| (key == "GROK_SEED").then(|| "abc".to_string()));
+        let without_seed = SamplingConfig::from_vars(|_| None);
+
+        assert_eq!(with_seed.seed, Some(1234));
+        assert_eq!(bad_seed.seed, None);
+        assert_eq!(without_seed, SamplingConfig::default());
+    }
+
+    #[test]
+    fn test_request_body_carries_tool_choice() {
+        let agent = test_agent();
+
+        let none = agent.build_request_body(&[], &[], &ToolChoice::None);
+        assert_eq!(none["tool_choice"], "none");
+
+        let forced = agent.build_request_body(&[], &[], &ToolChoice::Function("fs.read".to_string()));
+        assert_eq!(forced["tool_choice"]["type"], "function");
+        assert_eq!(forced["tool_choice"]["function"]["name"], "fs.read");
+    }
+
+    #[test]
+    fn test_context_length_error_is_classified() {
+        let body = r#"{"error":{"message":"This model's maximum context length is 128000 tokens","code":400}}"#;
+        let err = classify_error_response(reqwest::StatusCode::BAD_REQUEST, body);
+        assert!(matches!(err, Some(AgentError::ContextTooLarge(_))));
+    }
+
+    #[test]
+    fn test_other_errors_are_not_classified() {
+        let bad_request = classify_error_response(reqwest::StatusCode::BAD_REQUEST, r#"{"error":"invalid model"}"#);
+        assert!(bad_request.is_none());
+
+        let server_error = classify_error_response(reqwest::StatusCode::INTERNAL_SERVER_ERROR, "context length exceeded");
+        assert!(server_error.is_none());
+    }
+
+    #[test]
+    fn test_parse_retry_after_seconds() {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert("retry-after", "2".parse().unwrap());
+
+        assert_eq!(parse_retry_after(&headers, SystemTime::now()), Some(2));
+    }
+
+    #[test]
+    fn test_parse_ratelimit_reset_timestamp() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert("x-ratelimit-reset", "1700000010000".parse().unwrap());
+
+        assert_eq!(parse_retry_after(&headers, now), Some(10));
+    }
+
+    #[test]
+    fn test_parse_retry_after_missing() {
+        let headers = reqwest::header::HeaderMap::new();
+        assert_eq!(parse_retry_after(&headers, SystemTime::now()), None);
+    }
+}
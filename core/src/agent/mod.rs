@@ -4,6 +4,7 @@ use std::time::{Duration, SystemTime};
 use thiserror::Error;
 
 pub mod agent_logic;
+pub mod prompt;
 
 /// Main agent trait that all agent implementations must satisfy
 #[async_trait]
@@ -13,10 +14,129 @@ pub trait Agent: Send + Sync {
         &self,
         message: String,
         history: Vec<crate::session::ChatMessage>,
+        options: SubmitOptions,
     ) -> Result<AgentResponse, AgentError>;
     
     /// Get agent information
     fn info(&self) -> AgentInfo;
+
+    /// Whether the configured model accepts image content parts
+    fn supports_vision(&self) -> bool {
+        false
+    }
+
+    /// Re-read the system prompt files under `workspace_root` for later submissions,
+    /// describing what was loaded
+    fn reload_prompt(&self, _workspace_root: &std::path::Path) -> Result<String, String> {
+        Err("This agent does not support reloading its prompt".to_string())
+    }
+}
+
+/// How the model may use tools for a submission
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum ToolChoice {
+    /// Let the model decide (provider default)
+    #[default]
+    Auto,
+    /// Answer without calling any tools
+    None,
+    /// Force a call to the named tool (wire name, e.g. "fs.read")
+    Function(String),
+}
+
+impl ToolChoice {
+    /// Encode as the OpenAI-compatible `tool_choice` value
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            ToolChoice::Auto => serde_json::json!("auto"),
+            ToolChoice::None => serde_json::json!("none"),
+            ToolChoice::Function(name) => serde_json::json!({
+                "type": "function",
+                "function": { "name": name }
+            }),
+        }
+    }
+}
+
+/// Per-submission overrides passed to `Agent::submit`
+#[derive(Debug, Clone, Default)]
+pub struct SubmitOptions {
+    pub tool_choice: ToolChoice,
+    /// Directory tools resolve relative paths against; `None` uses the process working directory
+    pub workspace_root: Option<std::path::PathBuf>,
+    /// Images sent alongside the user message as `image_url` content parts
+    pub attachments: Vec<ImageAttachment>,
+    /// Refuse side-effecting tools for this submission
+    pub read_only: bool,
+    /// Ask the user to approve `fs.apply_patch` ops (via `AppEvent::ApprovalRequest`) before writing
+    pub patch_approval: bool,
+}
+
+/// An image read from disk for a multimodal prompt
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageAttachment {
+    /// File name, for display
+    pub name: String,
+    pub mime_type: String,
+    /// Base64-encoded file contents
+    pub data: String,
+}
+
+impl ImageAttachment {
+    /// Read an image file; only formats vision models commonly accept are allowed
+    pub fn from_file(path: &std::path::Path) -> Result<Self, String> {
+        use base64::Engine;
+
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase())
+            .unwrap_or_default();
+        let mime_type = match ext.as_str() {
+            "png" => "image/png",
+            "jpg" | "jpeg" => "image/jpeg",
+            "gif" => "image/gif",
+            "webp" => "image/webp",
+            _ => return Err(format!("Unsupported image type: {} (use png, jpg, gif or webp)", path.display())),
+        };
+        let bytes = std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+        Ok(Self {
+            name: path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_else(|| path.display().to_string()),
+            mime_type: mime_type.to_string(),
+            data: base64::engine::general_purpose::STANDARD.encode(bytes),
+        })
+    }
+
+    /// `data:` URI for an OpenAI-style `image_url` part
+    pub fn data_uri(&self) -> String {
+        format!("data:{};base64,{}", self.mime_type, self.data)
+    }
+}
+
+impl SubmitOptions {
+    pub fn with_tool_choice(mut self, tool_choice: ToolChoice) -> Self {
+        self.tool_choice = tool_choice;
+        self
+    }
+
+    pub fn with_workspace_root(mut self, root: Option<std::path::PathBuf>) -> Self {
+        self.workspace_root = root;
+        self
+    }
+
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    pub fn with_patch_approval(mut self, patch_approval: bool) -> Self {
+        self.patch_approval = patch_approval;
+        self
+    }
 }
 
 /// Response from an agent
@@ -49,6 +169,24 @@ impl ResponseMetadata {
         self.processing_time = Some(duration);
         self
     }
+
+    /// Total tokens the submission consumed, as reported by the provider
+    pub fn with_tokens_used(mut self, tokens: Option<u32>) -> Self {
+        self.tokens_used = tokens;
+        self
+    }
+
+    /// The model the provider reports having used, which may differ from the one requested
+    pub fn with_model(mut self, model: Option<String>) -> Self {
+        self.model = model;
+        self
+    }
+}
+
+impl Default for ResponseMetadata {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 /// Information about an agent
@@ -73,6 +211,12 @@ pub enum AgentError {
     
     #[error("Agent unavailable: {0}")]
     Unavailable(String),
+
+    #[error("Context too large: {0}")]
+    ContextTooLarge(String),
+
+    #[error("Rate limited, retry in {0}s")]
+    RateLimited(u64),
 }
 
 /// Factory for creating different types of agents
@@ -83,15 +227,24 @@ impl AgentFactory {
     /// Required: OPENROUTER_API_KEY
     /// Optional: OPENROUTER_MODEL (default: "x-ai/grok-4-fast:free")
     /// Optional fallback: VERCEL_AI_GATEWAY_API_KEY, VERCEL_AI_GATEWAY_MODEL
+    /// Optional sampling: GROK_TEMPERATURE, GROK_TOP_P, GROK_SEED
     pub fn create_openrouter_from_env(
         event_sender: crate::events::EventSender,
+    ) -> Result<std::sync::Arc<dyn Agent>, AgentError> {
+        Self::create_openrouter_with_config(event_sender, &crate::config::Config::default())
+    }
+
+    /// Like [`AgentFactory::create_openrouter_from_env`], but model and sampling fall
+    /// back to the project config when the corresponding env vars are unset
+    pub fn create_openrouter_with_config(
+        event_sender: crate::events::EventSender,
+        config: &crate::config::Config,
     ) -> Result<std::sync::Arc<dyn Agent>, AgentError> {
         let api_key = std::env::var("OPENROUTER_API_KEY")
             .map_err(|_| AgentError::Configuration("Missing OPENROUTER_API_KEY".to_string()))?;
-        let model = std::env::var("OPENROUTER_MODEL").unwrap_or_else(|_| "x-ai/grok-4-fast:free".to_string());
 
-        let agent = agent_logic::MultiModelAgent::new(api_key, model, event_sender)
-            .map_err(|e| AgentError::Configuration(format!("{}", e)))?;
+        let agent = agent_logic::MultiModelAgent::from_config(api_key, config, event_sender)
+            .map_err(|e| e.downcast::<AgentError>().unwrap_or_else(|e| AgentError::Configuration(e.to_string())))?;
         Ok(std::sync::Arc::new(agent))
     }
 }
@@ -0,0 +1,63 @@
+//! System prompt assembly from the built-in prompt and per-project files
+//!
+//! A non-empty `.grok/system_prompt.md` in the workspace replaces the built-in prompt, and an
+//! `AGENTS.md` next to it is appended as project context. Files are read when the agent is
+//! built and again on `/reload`, so edits made during a session take effect without a restart.
+
+use std::path::Path;
+
+/// Built-in prompt used when the workspace has no override
+pub const DEFAULT_SYSTEM_PROMPT: &str = include_str!("../prompts/system_prompt.md");
+
+/// Workspace-relative file that replaces the built-in prompt
+pub const SYSTEM_PROMPT_OVERRIDE: &str = ".grok/system_prompt.md";
+
+/// Workspace-relative file appended to the prompt as project context
+pub const CONTEXT_FILE: &str = "AGENTS.md";
+
+/// The assembled prompt and which files went into it
+#[derive(Debug, Clone, PartialEq)]
+pub struct SystemPrompt {
+    pub text: String,
+    /// Workspace files that were read, in the order they were applied
+    pub sources: Vec<&'static str>,
+}
+
+impl SystemPrompt {
+    /// Build the prompt for `root`; missing or empty files are skipped
+    pub fn load(root: &Path) -> Self {
+        let read = |name: &str| {
+            std::fs::read_to_string(root.join(name))
+                .ok()
+                .filter(|text| !text.trim().is_empty())
+        };
+
+        let mut sources = Vec::new();
+        let mut text = match read(SYSTEM_PROMPT_OVERRIDE) {
+            Some(custom) => {
+                sources.push(SYSTEM_PROMPT_OVERRIDE);
+                custom
+            }
+            None => DEFAULT_SYSTEM_PROMPT.to_string(),
+        };
+        if let Some(context) = read(CONTEXT_FILE) {
+            sources.push(CONTEXT_FILE);
+            text = format!("{}\n\n# Project context ({})\n\n{}", text.trim_end(), CONTEXT_FILE, context.trim());
+        }
+        Self { text, sources }
+    }
+
+    /// One line naming what the prompt was built from, for `/reload`
+    pub fn describe(&self) -> String {
+        let base = if self.sources.contains(&SYSTEM_PROMPT_OVERRIDE) {
+            SYSTEM_PROMPT_OVERRIDE
+        } else {
+            "built-in prompt"
+        };
+        if self.sources.contains(&CONTEXT_FILE) {
+            format!("{} + {}", base, CONTEXT_FILE)
+        } else {
+            base.to_string()
+        }
+    }
+}
@@ -0,0 +1,379 @@
+//! Per-project configuration loaded from `.grok/config.toml`
+//!
+//! Every setting is optional. Environment variables always win over file values,
+//! so an existing env-based setup keeps behaving the same when a config file appears.
+//!
+//! ```toml
+//! [agent]
+//! model = "x-ai/grok-4-fast:free"
+//! temperature = 0.2
+//! top_p = 0.9
+//! seed = 42
+//!
+//! [tools]
+//! max_output_size = 524288
+//! search_skip_exts = ["lock", "svg"]
+//! excludes = ["vendor/", "*.min.js"]
+//! ```
+//!
+//! Only this flat subset of TOML is understood: `[section]` headers and
+//! `key = value` pairs whose values are strings, numbers, booleans or
+//! single-line arrays of strings.
+
+use crate::agent::agent_logic::SamplingConfig;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Location of the config file relative to the workspace root
+pub const CONFIG_FILE: &str = ".grok/config.toml";
+
+/// The model used when neither the config file nor OPENROUTER_MODEL names one
+pub const DEFAULT_MODEL: &str = "x-ai/grok-4-fast:free";
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Config {
+    pub agent: AgentSettings,
+    pub tools: ToolSettings,
+}
+
+/// `[agent]` section
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AgentSettings {
+    pub model: Option<String>,
+    pub temperature: Option<f64>,
+    pub top_p: Option<f64>,
+    pub seed: Option<u64>,
+}
+
+/// `[tools]` section
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ToolSettings {
+    pub max_output_size: Option<usize>,
+    pub search_skip_exts: Vec<String>,
+    /// Gitignore-style patterns, relative to the workspace root, that `fs.search` and `fs.find` skip
+    pub excludes: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TomlValue {
+    String(String),
+    Integer(i64),
+    Float(f64),
+    Bool(bool),
+    Array(Vec<String>),
+}
+
+impl Config {
+    /// Load `<root>/.grok/config.toml`. A missing file yields the defaults.
+    pub fn load(root: &Path) -> Result<Self, String> {
+        let path = root.join(CONFIG_FILE);
+        match std::fs::read_to_string(&path) {
+            Ok(text) => Self::parse(&text).map_err(|e| format!("{}: {}", path.display(), e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("Failed to read {}: {}", path.display(), e)),
+        }
+    }
+
+    /// Parse config file contents
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut config = Self::default();
+
+        for (section, key, value, line_no) in parse_entries(text)? {
+            let unexpected = || format!("line {}: unexpected value for {}.{}", line_no, section, key);
+            match (section.as_str(), key.as_str(), value) {
+                ("agent", "model", TomlValue::String(s)) => config.agent.model = Some(s),
+                ("agent", "temperature", v) => config.agent.temperature = Some(as_float(v).ok_or_else(unexpected)?),
+                ("agent", "top_p", v) => config.agent.top_p = Some(as_float(v).ok_or_else(unexpected)?),
+                ("agent", "seed", TomlValue::Integer(n)) if n >= 0 => config.agent.seed = Some(n as u64),
+                ("tools", "max_output_size", TomlValue::Integer(n)) if n > 0 => {
+                    config.tools.max_output_size = Some(n as usize)
+                }
+                ("tools", "search_skip_exts", TomlValue::Array(exts)) => config.tools.search_skip_exts = exts,
+                ("tools", "excludes", TomlValue::Array(patterns)) => config.tools.excludes = patterns,
+                ("agent", "model" | "seed", _) | ("tools", "max_output_size" | "search_skip_exts" | "excludes", _) => {
+                    return Err(unexpected());
+                }
+                // Unknown keys are ignored so newer config files still load
+                _ => {}
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Model to use: OPENROUTER_MODEL, then the config file, then the built-in default
+    pub fn resolved_model(&self) -> String {
+        std::env::var("OPENROUTER_MODEL")
+            .ok()
+            .or_else(|| self.agent.model.clone())
+            .unwrap_or_else(|| DEFAULT_MODEL.to_string())
+    }
+
+    /// Sampling parameters with GROK_TEMPERATURE / GROK_TOP_P / GROK_SEED taking precedence
+    pub fn resolved_sampling(&self) -> SamplingConfig {
+        let env = SamplingConfig::from_env();
+        SamplingConfig {
+            temperature: env.temperature.or(self.agent.temperature),
+            top_p: env.top_p.or(self.agent.top_p),
+            seed: env.seed.or(self.agent.seed),
+        }
+    }
+
+    /// Tool settings with GROK_TOOL_MAX_OUTPUT_SIZE / GROK_SEARCH_SKIP_EXTS taking precedence,
+    /// handed to each `ToolExecutor` the agent builds
+    pub fn resolved_tools(&self) -> ToolSettings {
+        self.resolved_tools_with(|key| std::env::var(key).ok())
+    }
+
+    fn resolved_tools_with(&self, env: impl Fn(&str) -> Option<String>) -> ToolSettings {
+        let search_skip_exts = match env("GROK_SEARCH_SKIP_EXTS") {
+            Some(exts) => exts.split(',').map(str::to_string).collect(),
+            None => self.tools.search_skip_exts.clone(),
+        };
+        ToolSettings {
+            max_output_size: env("GROK_TOOL_MAX_OUTPUT_SIZE")
+                .and_then(|v| v.trim().parse().ok())
+                .or(self.tools.max_output_size),
+            search_skip_exts,
+            excludes: self.tools.excludes.clone(),
+        }
+    }
+}
+
+impl Config {
+    /// Human-readable summary of the settings in effect, for `/env`. API keys are only
+    /// reported as set or unset; their values never appear.
+    pub fn describe_env(&self, workspace_root: &Path) -> String {
+        self.describe_with(workspace_root, |key| std::env::var(key).ok())
+    }
+
+    fn describe_with(&self, workspace_root: &Path, env: impl Fn(&str) -> Option<String>) -> String {
+        let parse = |key: &str| env(key).and_then(|v| v.trim().parse::<usize>().ok());
+        let model_source = if env("OPENROUTER_MODEL").is_some() {
+            "OPENROUTER_MODEL"
+        } else if self.agent.model.is_some() {
+            CONFIG_FILE
+        } else {
+            "default"
+        };
+        let model = env("OPENROUTER_MODEL")
+            .or_else(|| self.agent.model.clone())
+            .unwrap_or_else(|| DEFAULT_MODEL.to_string());
+        let fallback = match (env("VERCEL_AI_GATEWAY_API_KEY"), env("VERCEL_AI_GATEWAY_MODEL")) {
+            (Some(_), Some(model)) => format!("Vercel AI Gateway ({})", model),
+            _ => "none".to_string(),
+        };
+        let max_output = parse("GROK_TOOL_MAX_OUTPUT_SIZE")
+            .or(self.tools.max_output_size)
+            .unwrap_or(crate::tools::executor::DEFAULT_MAX_OUTPUT_SIZE);
+        let transcript_cap = parse("GROK_TRANSCRIPT_RESULT_MAX_BYTES")
+            .unwrap_or(crate::agent::agent_logic::DEFAULT_TRANSCRIPT_RESULT_BYTES);
+        let sampling = |key: &str, file: Option<String>| {
+            env(key).or(file).unwrap_or_else(|| "provider default".to_string())
+        };
+        let presence = |key: &str| if env(key).is_some_and(|v| !v.is_empty()) { "set" } else { "unset" };
+
+        let mut out = String::from("Configuration:");
+        out.push_str(&format!("\n• Model: {} (from {})", model, model_source));
+        out.push_str(&format!("\n• Fallback provider: {}", fallback));
+        out.push_str(&format!("\n• Temperature: {}", sampling("GROK_TEMPERATURE", self.agent.temperature.map(|t| t.to_string()))));
+        out.push_str(&format!("\n• Top-p: {}", sampling("GROK_TOP_P", self.agent.top_p.map(|t| t.to_string()))));
+        out.push_str(&format!("\n• Seed: {}", sampling("GROK_SEED", self.agent.seed.map(|s| s.to_string()))));
+        out.push_str(&format!("\n• Max tool output: {} bytes", max_output));
+        out.push_str(&format!("\n• Transcript cap per tool result: {} bytes", transcript_cap));
+        out.push_str(&format!("\n• Default shell timeout: {} ms", crate::tools::executors::DEFAULT_SHELL_TIMEOUT_MS));
+        out.push_str(&format!("\n• Workspace root: {}", workspace_root.display()));
+        out.push_str("\nAPI keys:");
+        for key in ["OPENROUTER_API_KEY", "VERCEL_AI_GATEWAY_API_KEY"] {
+            out.push_str(&format!("\n• {}: {}", key, presence(key)));
+        }
+        out
+    }
+}
+
+fn as_float(value: TomlValue) -> Option<f64> {
+    match value {
+        TomlValue::Float(f) => Some(f),
+        TomlValue::Integer(n) => Some(n as f64),
+        _ => None,
+    }
+}
+
+/// Flatten the file into `(section, key, value, line)` entries
+fn parse_entries(text: &str) -> Result<Vec<(String, String, TomlValue, usize)>, String> {
+    let mut entries = Vec::new();
+    let mut section = String::new();
+    let mut seen = BTreeMap::new();
+
+    for (i, raw) in text.lines().enumerate() {
+        let line_no = i + 1;
+        let line = strip_comment(raw).trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = name.trim().to_string();
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| format!("line {}: expected `key = value`", line_no))?;
+        let key = key.trim().to_string();
+        let value = parse_value(value.trim()).ok_or_else(|| format!("line {}: invalid value for {}", line_no, key))?;
+
+        if seen.insert((section.clone(), key.clone()), line_no).is_some() {
+            return Err(format!("line {}: duplicate key {}", line_no, key));
+        }
+        entries.push((section.clone(), key, value, line_no));
+    }
+
+    Ok(entries)
+}
+
+/// Drop a trailing `#` comment that isn't inside a `"` or `'` string
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    for (i, c) in line.char_indices() {
+        match (c, quote) {
+            ('"' | '\'', None) => quote = Some(c),
+            (c, Some(open)) if c == open => quote = None,
+            ('#', None) => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn parse_value(text: &str) -> Option<TomlValue> {
+    if let Some(s) = parse_string(text) {
+        return Some(TomlValue::String(s));
+    }
+    if let Some(inner) = text.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+        let items = inner
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(parse_string)
+            .collect::<Option<Vec<_>>>()?;
+        return Some(TomlValue::Array(items));
+    }
+    match text {
+        "true" => return Some(TomlValue::Bool(true)),
+        "false" => return Some(TomlValue::Bool(false)),
+        _ => {}
+    }
+    let digits = text.replace('_', "");
+    if let Ok(n) = digits.parse::<i64>() {
+        return Some(TomlValue::Integer(n));
+    }
+    digits.parse::<f64>().ok().map(TomlValue::Float)
+}
+
+fn parse_string(text: &str) -> Option<String> {
+    text.strip_prefix('"')
+        .and_then(|t| t.strip_suffix('"'))
+        .or_else(|| text.strip_prefix('\'').and_then(|t| t.strip_suffix('\'')))
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"
+# Project settings
+[agent]
+model = "x-ai/grok-code-fast-1"   # a cheaper model for this repo
+temperature = 0.3
+seed = 7
+
+[tools]
+max_output_size = 65_536
+search_skip_exts = ["lock", "svg"]
+excludes = ['vendor/', "*.min.js"]   # kept out of search and find
+unknown_key = true
+"#;
+
+    #[test]
+    fn test_parse_sample_config() {
+        let config = Config::parse(SAMPLE).unwrap();
+
+        assert_eq!(config.agent.model.as_deref(), Some("x-ai/grok-code-fast-1"));
+        assert_eq!(config.agent.temperature, Some(0.3));
+        assert_eq!(config.agent.top_p, None);
+        assert_eq!(config.agent.seed, Some(7));
+        assert_eq!(config.tools.max_output_size, Some(65_536));
+        assert_eq!(config.tools.search_skip_exts, vec!["lock", "svg"]);
+        assert_eq!(config.tools.excludes, vec!["vendor/", "*.min.js"]);
+    }
+
+    #[test]
+    fn test_comments_inside_strings_are_kept() {
+        let config = Config::parse("[agent]\nmodel = 'a#b' # single quotes\n[tools]\nexcludes = [\"#tmp\", '#x']").unwrap();
+
+        assert_eq!(config.agent.model.as_deref(), Some("a#b"));
+        assert_eq!(config.tools.excludes, vec!["#tmp", "#x"]);
+    }
+
+    #[test]
+    fn test_env_overrides_tool_settings() {
+        let config = Config::parse(SAMPLE).unwrap();
+
+        let resolved = config.resolved_tools_with(|_| None);
+        assert_eq!(resolved, config.tools);
+
+        let resolved = config.resolved_tools_with(|key| match key {
+            "GROK_TOOL_MAX_OUTPUT_SIZE" => Some("4096".to_string()),
+            "GROK_SEARCH_SKIP_EXTS" => Some("map,snap".to_string()),
+            _ => None,
+        });
+        assert_eq!(resolved.max_output_size, Some(4096));
+        assert_eq!(resolved.search_skip_exts, vec!["map", "snap"]);
+        assert_eq!(resolved.excludes, config.tools.excludes);
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_values() {
+        assert!(Config::parse("[agent]\nmodel = 3").unwrap_err().contains("agent.model"));
+        assert!(Config::parse("[agent]\ntemperature = \"hot\"").is_err());
+        assert!(Config::parse("[agent]\nseed = 1\nseed = 2").unwrap_err().contains("duplicate"));
+        assert!(Config::parse("just words").is_err());
+    }
+
+    #[test]
+    fn test_describe_env_masks_keys() {
+        let config = Config::parse(SAMPLE).unwrap();
+        let env = |key: &str| match key {
+            "OPENROUTER_API_KEY" => Some("sk-or-secret-value".to_string()),
+            "GROK_TRANSCRIPT_RESULT_MAX_BYTES" => Some("2048".to_string()),
+            _ => None,
+        };
+        let report = config.describe_with(Path::new("/work/project"), env);
+
+        assert!(!report.contains("sk-or-secret-value"));
+        assert!(report.contains("• OPENROUTER_API_KEY: set"));
+        assert!(report.contains("• VERCEL_AI_GATEWAY_API_KEY: unset"));
+        assert!(report.contains("• Model: x-ai/grok-code-fast-1 (from .grok/config.toml)"));
+        assert!(report.contains("• Max tool output: 65536 bytes"));
+        assert!(report.contains("• Transcript cap per tool result: 2048 bytes"));
+        assert!(report.contains("• Workspace root: /work/project"));
+    }
+
+    #[test]
+    fn test_missing_file_uses_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(Config::load(dir.path()).unwrap(), Config::default());
+    }
+
+    #[test]
+    fn test_load_from_workspace_root() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join(".grok")).unwrap();
+        std::fs::write(dir.path().join(CONFIG_FILE), SAMPLE).unwrap();
+
+        let config = Config::load(dir.path()).unwrap();
+        assert_eq!(config.agent.seed, Some(7));
+    }
+}
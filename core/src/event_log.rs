@@ -0,0 +1,138 @@
+//! Opt-in JSON-lines log of every `AppEvent`, for debugging a session after the fact
+//!
+//! Enabled by pointing `GROK_EVENT_LOG` at a file. Each event becomes one line of the form
+//! `{"ts_ms": <unix millis>, "event": "<variant>", "data": {...}}`, appended to whatever the
+//! file already holds. Writes are buffered and flushed on [`EventLog::flush`] and on drop.
+
+use crate::events::AppEvent;
+use serde_json::{json, Value};
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Appends events to a log file as JSON lines
+pub struct EventLog {
+    writer: BufWriter<File>,
+}
+
+impl EventLog {
+    /// Open (or create) `path` for appending
+    pub fn open(path: &Path) -> std::io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self { writer: BufWriter::new(file) })
+    }
+
+    /// The log named by `GROK_EVENT_LOG`, if set and writable
+    pub fn from_env() -> Option<Self> {
+        let path = std::env::var("GROK_EVENT_LOG").ok().filter(|p| !p.trim().is_empty())?;
+        match Self::open(Path::new(path.trim())) {
+            Ok(log) => Some(log),
+            Err(e) => {
+                tracing::warn!("Could not open event log {}: {}", path, e);
+                None
+            }
+        }
+    }
+
+    /// Append one event; failures are logged and otherwise ignored
+    pub fn record(&mut self, event: &AppEvent) {
+        let ts_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+        let (name, data) = describe(event);
+        let line = json!({ "ts_ms": ts_ms, "event": name, "data": data });
+        if let Err(e) = writeln!(self.writer, "{}", line) {
+            tracing::warn!("Failed to write event log: {}", e);
+        }
+    }
+
+    /// Push buffered lines to the file
+    pub fn flush(&mut self) {
+        if let Err(e) = self.writer.flush() {
+            tracing::warn!("Failed to flush event log: {}", e);
+        }
+    }
+}
+
+impl Drop for EventLog {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+/// Variant name and payload for one event
+fn describe(event: &AppEvent) -> (&'static str, Value) {
+    match event {
+        AppEvent::UserInput(text) => ("UserInput", json!({ "text": text })),
+        AppEvent::AgentResponse(response) => ("AgentResponse", json!({
+            "content": response.content,
+            "model": response.metadata.model,
+            "tokens_used": response.metadata.tokens_used,
+        })),
+        AppEvent::AgentError(error) => ("AgentError", json!({ "message": error.to_string() })),
+        AppEvent::Quit => ("Quit", Value::Null),
+        AppEvent::Clear => ("Clear", Value::Null),
+        AppEvent::ShowAgentInfo => ("ShowAgentInfo", Value::Null),
+        AppEvent::ChatCreated => ("ChatCreated", Value::Null),
+        AppEvent::ChatDelta { text } => ("ChatDelta", json!({ "text": text })),
+        AppEvent::ChatCompleted { token_usage } => ("ChatCompleted", json!({ "token_usage": token_usage })),
+        AppEvent::ToolBegin { id, tool, summary, args } => ("ToolBegin", json!({
+            "id": id,
+            "tool": tool.wire_name(),
+            "summary": summary,
+            "args": args,
+        })),
+        AppEvent::ToolProgress { id, message } => ("ToolProgress", json!({ "id": id, "message": message })),
+        AppEvent::ToolStdout { id, chunk } => ("ToolStdout", json!({ "id": id, "chunk": chunk })),
+        AppEvent::ToolStderr { id, chunk } => ("ToolStderr", json!({ "id": id, "chunk": chunk })),
+        AppEvent::ToolResult { id, payload } => ("ToolResult", json!({ "id": id, "payload": payload })),
+        AppEvent::ToolEnd { id, ok, duration_ms, result_bytes } => ("ToolEnd", json!({
+            "id": id,
+            "ok": ok,
+            "duration_ms": duration_ms,
+            "result_bytes": result_bytes,
+        })),
+        AppEvent::ApprovalRequest(request) => ("ApprovalRequest", json!({
+            "id": request.id,
+            "ops": request.ops.iter().map(|op| &op.description).collect::<Vec<_>>(),
+        })),
+        AppEvent::Error { id, message } => ("Error", json!({ "id": id, "message": message })),
+        AppEvent::Notice(message) => ("Notice", json!({ "message": message })),
+        AppEvent::TokenCount(usage) => ("TokenCount", json!(usage)),
+        AppEvent::Background(message) => ("Background", json!({ "message": message })),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::ToolName;
+
+    #[test]
+    fn test_event_log_writes_json_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("events.jsonl");
+        let mut log = EventLog::open(&path).expect("log should open");
+        log.record(&AppEvent::UserInput("hello".to_string()));
+        log.record(&AppEvent::ToolBegin {
+            id: "t1".to_string(),
+            tool: ToolName::FsRead,
+            summary: "Reading main.rs".to_string(),
+            args: Some(json!({ "path": "main.rs" })),
+        });
+        // Dropping the log flushes what's buffered
+        drop(log);
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<Value> = contents.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["event"], "UserInput");
+        assert_eq!(lines[0]["data"]["text"], "hello");
+        assert!(lines[0]["ts_ms"].as_u64().unwrap() > 0);
+        assert_eq!(lines[1]["event"], "ToolBegin");
+        assert_eq!(lines[1]["data"]["tool"], "fs.read");
+        assert_eq!(lines[1]["data"]["args"]["path"], "main.rs");
+    }
+}
@@ -1,6 +1,7 @@
 use crate::agent::{AgentError, AgentResponse};
 use serde::{Deserialize, Serialize};
-use tokio::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{mpsc, oneshot};
 
 /// Requests sent to core
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,10 +42,15 @@ pub enum AppEvent {
     ToolStdout { id: String, chunk: String },
     ToolStderr { id: String, chunk: String },
     ToolResult { id: String, payload: serde_json::Value },
-    ToolEnd { id: String, ok: bool, duration_ms: u64 },
+    /// `result_bytes` is the size of the serialized result, when the tool produced one
+    ToolEnd { id: String, ok: bool, duration_ms: u64, result_bytes: Option<u64> },
+    /// A tool is waiting for the user to approve its edits
+    ApprovalRequest(PatchApprovalRequest),
 
     // Errors
     Error { id: Option<String>, message: String },
+    /// Transient status for the user (provider fallback, retries); not part of the conversation
+    Notice(String),
     TokenCount(TokenUsage),
     Background(String),
 }
@@ -63,9 +69,100 @@ pub enum ToolName {
     FsDeleteFile,
     FsRenameFile,
     FsFind,
+    FsTree,
     ShellExec,
     CodeSymbols,
+    CodeTest,
+    CodeBuild,
     LargeContextFetch,
+    HttpFetch,
+}
+
+impl ToolName {
+    /// Every tool, in registry order
+    pub const ALL: &'static [ToolName] = &[
+        ToolName::FsRead,
+        ToolName::FsSearch,
+        ToolName::FsWrite,
+        ToolName::FsApplyPatch,
+        ToolName::FsSetFile,
+        ToolName::FsReplaceOnce,
+        ToolName::FsInsertBefore,
+        ToolName::FsInsertAfter,
+        ToolName::FsDeleteFile,
+        ToolName::FsRenameFile,
+        ToolName::FsFind,
+        ToolName::FsTree,
+        ToolName::ShellExec,
+        ToolName::CodeSymbols,
+        ToolName::CodeTest,
+        ToolName::CodeBuild,
+        ToolName::LargeContextFetch,
+        ToolName::HttpFetch,
+    ];
+
+    /// Name used for the tool in model-facing function calls
+    pub fn wire_name(&self) -> &'static str {
+        match self {
+            ToolName::FsRead => "fs.read",
+            ToolName::FsSearch => "fs.search",
+            ToolName::FsWrite => "fs.write",
+            ToolName::FsApplyPatch => "fs.apply_patch",
+            ToolName::FsSetFile => "fs.set_file",
+            ToolName::FsReplaceOnce => "fs.replace_once",
+            ToolName::FsInsertBefore => "fs.insert_before",
+            ToolName::FsInsertAfter => "fs.insert_after",
+            ToolName::FsDeleteFile => "fs.delete_file",
+            ToolName::FsRenameFile => "fs.rename_file",
+            ToolName::FsFind => "fs.find",
+            ToolName::FsTree => "fs.tree",
+            ToolName::ShellExec => "shell.exec",
+            ToolName::CodeSymbols => "code.symbols",
+            ToolName::CodeTest => "code.test",
+            ToolName::CodeBuild => "code.build",
+            ToolName::LargeContextFetch => "large_context_fetch",
+            ToolName::HttpFetch => "http.fetch",
+        }
+    }
+
+    /// Parse a model-facing tool name back into a `ToolName`
+    pub fn from_wire_name(name: &str) -> Option<ToolName> {
+        Self::ALL.iter().find(|tool| tool.wire_name() == name).cloned()
+    }
+}
+
+/// One op of an `fs.apply_patch` call awaiting approval
+#[derive(Debug, Clone)]
+pub struct PatchOpPreview {
+    /// Op kind and path, e.g. `replace_once src/main.rs`
+    pub description: String,
+    /// Unified diff of this op's content changes, on top of the ops before it
+    pub diff: String,
+}
+
+/// An `fs.apply_patch` call held until the user answers with [`PatchApprovalRequest::respond`]
+#[derive(Debug, Clone)]
+pub struct PatchApprovalRequest {
+    /// Tool call id
+    pub id: String,
+    pub ops: Vec<PatchOpPreview>,
+    reply: Arc<Mutex<Option<oneshot::Sender<Vec<bool>>>>>,
+}
+
+impl PatchApprovalRequest {
+    /// A request plus the receiver its answer arrives on
+    pub fn new(id: String, ops: Vec<PatchOpPreview>) -> (Self, oneshot::Receiver<Vec<bool>>) {
+        let (sender, receiver) = oneshot::channel();
+        let request = Self { id, ops, reply: Arc::new(Mutex::new(Some(sender))) };
+        (request, receiver)
+    }
+
+    /// Answer with one flag per op, `true` to apply it; only the first answer counts
+    pub fn respond(&self, approved: Vec<bool>) {
+        if let Some(sender) = self.reply.lock().ok().and_then(|mut reply| reply.take()) {
+            let _ = sender.send(approved);
+        }
+    }
 }
 
 /// Token usage information
@@ -84,6 +181,8 @@ pub struct ToolSpec {
     pub output_schema: serde_json::Value,  // JSON Schema
     pub streaming: bool,                   // supports stdout/stderr/progress
     pub side_effects: bool,                // mutates filesystem/environment
+    #[serde(default)]
+    pub needs_approval: bool,              // must be confirmed before running
     pub timeout_ms: Option<u64>,
 }
 
@@ -182,4 +281,12 @@ mod tests {
             _ => panic!("Expected UserInput event"),
         }
     }
+
+    #[test]
+    fn test_tool_wire_name_round_trip() {
+        for tool in ToolName::ALL {
+            assert_eq!(ToolName::from_wire_name(tool.wire_name()).as_ref(), Some(tool));
+        }
+        assert_eq!(ToolName::from_wire_name("fs.unknown"), None);
+    }
 }
@@ -1,10 +1,13 @@
 pub mod agent;
+pub mod config;
+pub mod event_log;
 pub mod events;
 pub mod session;
 pub mod tools;
 
 // Re-export main types for convenience
-pub use agent::{Agent, AgentResponse, AgentError, AgentFactory};
-pub use events::{AppEvent, EventBus, Request, ToolName, ToolSpec, TokenUsage};
-pub use session::{Session, ChatMessage, MessageRole, ToolStatus, ToolMessageInfo};
+pub use agent::{Agent, AgentResponse, AgentError, AgentFactory, ImageAttachment, SubmitOptions, ToolChoice};
+pub use config::Config;
+pub use events::{AppEvent, EventBus, PatchApprovalRequest, PatchOpPreview, Request, ToolName, ToolSpec, TokenUsage};
+pub use session::{Session, ChatMessage, MessageKind, MessageRole, ToolCounter, ToolStatus, ToolMessageInfo};
 pub use tools::{ToolExecutor, ToolRegistry};
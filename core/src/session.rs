@@ -1,6 +1,7 @@
-use crate::agent::Agent;
-use crate::events::{EventSender, ToolName};
+use crate::agent::{Agent, ImageAttachment, SubmitOptions, ToolChoice};
+use crate::events::{EventSender, TokenUsage, ToolName};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::time::SystemTime;
 use std::env;
 use std::fs;
@@ -13,8 +14,150 @@ pub struct Session {
     messages: Vec<ChatMessage>,
     agent: std::sync::Arc<dyn Agent>,
     event_sender: EventSender,
+    /// Overrides applied to the next submission only
+    next_submit_options: SubmitOptions,
+    /// Working directory for tools; `None` means the process working directory
+    workspace_root: Option<PathBuf>,
+    /// Most messages kept when history is saved or loaded; `None` keeps everything
+    history_max_messages: Option<usize>,
+    /// Block side-effecting tools; starts from `GROK_READ_ONLY`
+    read_only: bool,
+    /// Hold `fs.apply_patch` for the user's approval; only a frontend that answers can turn this on
+    patch_approval: bool,
+    /// When this session started, for `/stats`
+    started_at: SystemTime,
+    /// Prompt and completion tokens summed over every request
+    tokens_used: (u64, u64),
+    /// Calls per tool wire name, kept across `/clear`
+    tool_counters: BTreeMap<&'static str, ToolCounter>,
 }
 
+/// How often one tool was called and how those calls ended
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ToolCounter {
+    pub invocations: u64,
+    pub succeeded: u64,
+    pub failed: u64,
+}
+
+/// Activity totals shown by `/stats`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionStats {
+    /// Messages per role name, as shown in `/history`
+    pub messages_by_role: BTreeMap<&'static str, usize>,
+    /// Tool calls per tool wire name
+    pub tool_calls: BTreeMap<&'static str, ToolCounter>,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// File contents returned by `fs.read`
+    pub bytes_read: u64,
+    /// Bytes reported by `fs.write`
+    pub bytes_written: u64,
+    pub duration_secs: u64,
+}
+
+impl SessionStats {
+    /// Multi-line report for the chat
+    pub fn describe(&self) -> String {
+        let tally = |counts: Vec<String>| if counts.is_empty() { "none".to_string() } else { counts.join(", ") };
+        let messages = self.messages_by_role.iter().map(|(role, n)| format!("{} {}", role, n)).collect();
+        let tools = self
+            .tool_calls
+            .iter()
+            .map(|(name, counter)| match counter.failed {
+                0 => format!("{} {}", name, counter.invocations),
+                failed => format!("{} {} ({} failed)", name, counter.invocations, failed),
+            })
+            .collect();
+        let minutes = self.duration_secs / 60;
+        [
+            "Session stats:".to_string(),
+            format!("• Messages: {} ({})", self.messages_by_role.values().sum::<usize>(), tally(messages)),
+            format!("• Tool calls: {} ({})", self.tool_calls.values().map(|c| c.invocations).sum::<u64>(), tally(tools)),
+            format!(
+                "• Tokens: {} ({} input, {} output)",
+                self.input_tokens + self.output_tokens,
+                self.input_tokens,
+                self.output_tokens
+            ),
+            format!("• Bytes read: {} • Bytes written: {}", self.bytes_read, self.bytes_written),
+            format!("• Duration: {}m {}s", minutes, self.duration_secs % 60),
+        ]
+        .join("\n")
+    }
+}
+
+/// Role name used in listings
+fn role_name(role: &MessageRole) -> &'static str {
+    match role {
+        MessageRole::User => "user",
+        MessageRole::Agent => "assistant",
+        MessageRole::System => "system",
+        MessageRole::Error => "error",
+        MessageRole::Tool => "tool",
+    }
+}
+
+
+/// Longest content preview per message in [`Session::history_listing`]
+const HISTORY_PREVIEW_CHARS: usize = 80;
+
+/// Saved-history cap unless GROK_HISTORY_MAX_MESSAGES says otherwise
+pub const DEFAULT_HISTORY_MAX_MESSAGES: usize = 1000;
+
+/// Cap from GROK_HISTORY_MAX_MESSAGES; `0` turns it off
+fn history_max_messages_from_env() -> Option<usize> {
+    let max = env::var("GROK_HISTORY_MAX_MESSAGES")
+        .ok()
+        .and_then(|s| s.trim().parse().ok())
+        .unwrap_or(DEFAULT_HISTORY_MAX_MESSAGES);
+    (max > 0).then_some(max)
+}
+
+/// Resolve `path` (absolute, `~/...` or relative to `base`) to an existing directory
+pub fn resolve_dir(base: &std::path::Path, path: &str) -> Result<PathBuf, String> {
+    let expanded = match path.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => {
+            let home = env::var("HOME").map_err(|_| "HOME is not set".to_string())?;
+            format!("{}{}", home, rest)
+        }
+        _ => path.to_string(),
+    };
+    let dir = base
+        .join(expanded)
+        .canonicalize()
+        .map_err(|e| format!("Cannot use {} as the working directory: {}", path, e))?;
+    if !dir.is_dir() {
+        return Err(format!("Not a directory: {}", path));
+    }
+    Ok(dir)
+}
+
+/// Collapse whitespace onto one line and cut to `max_chars`
+fn preview(content: &str, max_chars: usize) -> String {
+    let flat = content.split_whitespace().collect::<Vec<_>>().join(" ");
+    if flat.chars().count() <= max_chars {
+        flat
+    } else {
+        format!("{}…", flat.chars().take(max_chars).collect::<String>())
+    }
+}
+
+/// Code fence long enough that no backtick run inside `content` closes it early
+fn fence_for(content: &str) -> String {
+    let longest = content
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    "`".repeat(longest.max(2) + 1)
+}
+
+/// `content` as a fenced code block tagged `lang`
+fn fenced(lang: &str, content: &str) -> String {
+    let fence = fence_for(content);
+    format!("{}{}\n{}\n{}\n", fence, lang, content.trim_end_matches('\n'), fence)
+}
 
 /// Status of a tool execution
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -31,6 +174,20 @@ pub struct ChatMessage {
     pub content: String,
     pub timestamp_secs: u64,  // Unix timestamp in seconds for serialization
     pub tool_info: Option<ToolMessageInfo>,
+    /// Kept by `/clear` and by the saved-history cap
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default)]
+    pub kind: MessageKind,
+}
+
+/// What a message holds beyond its role, for the ones rendered or sent differently
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MessageKind {
+    #[default]
+    Text,
+    /// A `/open` file preview: shown with its line layout and never sent to the model
+    FilePreview,
 }
 
 /// Information about a tool execution for tool messages
@@ -45,6 +202,9 @@ pub struct ToolMessageInfo {
     pub stdout: String,
     pub stderr: String,
     pub result: Option<serde_json::Value>,
+    /// Size of the serialized result, reported when the tool ends
+    #[serde(default)]
+    pub result_bytes: Option<u64>,
 }
 
 /// Who sent the message
@@ -60,13 +220,58 @@ pub enum MessageRole {
 impl Session {
     /// Create a new session with the given agent
     pub fn new(agent: std::sync::Arc<dyn Agent>, event_sender: EventSender) -> Self {
-        let session = Self {
+        Self {
             messages: Vec::new(),
             agent,
             event_sender,
-        };
-           
-        session
+            next_submit_options: SubmitOptions::default(),
+            workspace_root: None,
+            history_max_messages: history_max_messages_from_env(),
+            read_only: crate::tools::executor::read_only_from_env(),
+            patch_approval: false,
+            started_at: SystemTime::now(),
+            tokens_used: (0, 0),
+            tool_counters: BTreeMap::new(),
+        }
+    }
+
+    /// Ask for approval (`AppEvent::ApprovalRequest`) before `fs.apply_patch` writes anything
+    pub fn with_patch_approval(mut self, patch_approval: bool) -> Self {
+        self.patch_approval = patch_approval;
+        self
+    }
+
+    /// Keep at most `max` messages when saving or loading history (`None` for no cap).
+    /// This bounds the history file; what gets sent to the model is trimmed separately.
+    pub fn with_history_max_messages(mut self, max: Option<usize>) -> Self {
+        self.history_max_messages = max;
+        self
+    }
+
+    /// Directory tools run in, as an absolute path
+    pub fn workspace_root(&self) -> PathBuf {
+        self.workspace_root
+            .clone()
+            .or_else(|| env::current_dir().ok())
+            .unwrap_or_else(|| PathBuf::from("."))
+    }
+
+    /// Start the session with tools rooted at `root` (already resolved, see [`resolve_dir`]),
+    /// taking the system prompt and `AGENTS.md` from there too
+    pub fn with_workspace_root(mut self, root: PathBuf) -> Self {
+        let _ = self.agent.reload_prompt(&root);
+        self.workspace_root = Some(root);
+        self
+    }
+
+    /// Point tools at `path` for this session and take the system prompt and `AGENTS.md`
+    /// from there. Relative paths resolve against the current workspace root; the process
+    /// working directory is left alone.
+    pub fn set_workspace_root(&mut self, path: &str) -> Result<PathBuf, String> {
+        let root = resolve_dir(&self.workspace_root(), path)?;
+        self.agent.reload_prompt(&root)?;
+        self.workspace_root = Some(root.clone());
+        Ok(root)
     }
 
     /// Default history path (~/.grok_code/chat_history.json)
@@ -81,22 +286,45 @@ impl Session {
     
     /// Save messages to JSON file (auto-save or manual)
     pub fn save(&self) -> Result<(), String> {
-        let path = Self::default_history_path();
-        let json = serde_json::to_string(&self.messages).map_err(|e| e.to_string())?;
-        fs::write(&path, json.as_bytes()).map_err(|e| e.to_string())?;
+        self.save_to(&Self::default_history_path())
+    }
+
+    /// Save [`Session::persisted_messages`] to `path` as JSON
+    pub fn save_to(&self, path: &std::path::Path) -> Result<(), String> {
+        let json = serde_json::to_string(&self.persisted_messages()).map_err(|e| e.to_string())?;
+        fs::write(path, json.as_bytes()).map_err(|e| e.to_string())?;
         Ok(())
     }
+
+    /// The messages a saved history keeps: the most recent ones, up to the history cap,
+    /// plus any pinned messages older than that
+    pub fn persisted_messages(&self) -> Vec<ChatMessage> {
+        self.retained(self.messages.clone())
+    }
+
+    fn retained(&self, messages: Vec<ChatMessage>) -> Vec<ChatMessage> {
+        let cutoff = match self.history_max_messages {
+            Some(max) if messages.len() > max => messages.len() - max,
+            _ => return messages,
+        };
+        messages
+            .into_iter()
+            .enumerate()
+            .filter(|(i, msg)| *i >= cutoff || msg.pinned)
+            .map(|(_, msg)| msg)
+            .collect()
+    }
     
     /// Load messages from JSON and replace current history
     pub fn load_into(&mut self, path: Option<PathBuf>) -> Result<(), String> {
-        let path = path.unwrap_or_else(|| Self::default_history_path());
+        let path = path.unwrap_or_else(Self::default_history_path);
         if !path.exists() {
             return Err("No history file found".to_string());
         }
         let json = fs::read_to_string(&path).map_err(|e| e.to_string())?;
         let messages: Vec<ChatMessage> = serde_json::from_str(&json).map_err(|e| e.to_string())?;
         
-        self.messages = messages;
+        self.messages = self.retained(messages);
         if self.messages.is_empty() {
             self.add_system_message("Welcome to Grok Code! Type your message and press Enter.".to_string());
         }
@@ -117,8 +345,12 @@ impl Session {
         let agent = self.agent.clone();
         let sender = self.event_sender.clone();
         let history = self.messages.clone();
+        let options = std::mem::take(&mut self.next_submit_options)
+            .with_workspace_root(self.workspace_root.clone())
+            .with_read_only(self.read_only)
+            .with_patch_approval(self.patch_approval);
         tokio::spawn(async move {
-            match agent.submit(input, history).await {
+            match agent.submit(input, history, options).await {
                 Ok(response) => {
                     let _ = sender.send_agent_response(response);
                 }
@@ -129,6 +361,27 @@ impl Session {
         });
     }
     
+    /// Attach an image (relative to the workspace root) to the next message.
+    /// Fails when the agent's model doesn't accept images.
+    pub fn attach_image(&mut self, path: &str) -> Result<&ImageAttachment, String> {
+        if !self.agent.supports_vision() {
+            return Err("The configured model does not accept images. Switch to a vision model (e.g. set OPENROUTER_MODEL) to use /attach.".to_string());
+        }
+        let image = ImageAttachment::from_file(&self.workspace_root().join(path))?;
+        self.next_submit_options.attachments.push(image);
+        Ok(self.next_submit_options.attachments.last().expect("just pushed"))
+    }
+
+    /// Images waiting to be sent with the next message
+    pub fn pending_attachments(&self) -> &[ImageAttachment] {
+        &self.next_submit_options.attachments
+    }
+
+    /// Set the tool choice used for the next submission only
+    pub fn set_next_tool_choice(&mut self, tool_choice: ToolChoice) {
+        self.next_submit_options.tool_choice = tool_choice;
+    }
+
     /// Add a user message to the conversation
     pub fn add_user_message(&mut self, content: String) {
         let timestamp_secs = SystemTime::now()
@@ -139,6 +392,8 @@ impl Session {
             content,
             timestamp_secs,
             tool_info: None,
+            pinned: false,
+            kind: MessageKind::Text,
         };
         self.messages.push(message);
     }
@@ -153,6 +408,8 @@ impl Session {
             content,
             timestamp_secs,
             tool_info: None,
+            pinned: false,
+            kind: MessageKind::Text,
         };
         self.messages.push(message);
         
@@ -168,10 +425,20 @@ impl Session {
             content,
             timestamp_secs,
             tool_info: None,
+            pinned: false,
+            kind: MessageKind::Text,
         };
         self.messages.push(message);
     }
     
+    /// Add a `/open` file preview; it is shown like a system message but kept out of the model context
+    pub fn add_preview_message(&mut self, content: String) {
+        self.add_system_message(content);
+        if let Some(message) = self.messages.last_mut() {
+            message.kind = MessageKind::FilePreview;
+        }
+    }
+
     /// Add an error message to the conversation
     pub fn add_error_message(&mut self, content: String) {
         let timestamp_secs = SystemTime::now()
@@ -182,16 +449,154 @@ impl Session {
             content,
             timestamp_secs,
             tool_info: None,
+            pinned: false,
+            kind: MessageKind::Text,
         };
         self.messages.push(message);
     }
     
-    /// Clear all messages and reset session state
+    /// One line per message: its index, role and a single-line preview of the content
+    pub fn history_listing(&self) -> String {
+        if self.messages.is_empty() {
+            return "History is empty.".to_string();
+        }
+        let mut out = format!("History ({} messages):", self.messages.len());
+        for (i, msg) in self.messages.iter().enumerate() {
+            let role = role_name(&msg.role);
+            let content = match &msg.tool_info {
+                Some(info) => format!("{}: {}", info.tool.wire_name(), info.summary),
+                None => msg.content.clone(),
+            };
+            let pin = if msg.pinned { " 📌" } else { "" };
+            out.push_str(&format!("\n{:>3}. [{}]{} {}", i + 1, role, pin, preview(&content, HISTORY_PREVIEW_CHARS)));
+        }
+        out
+    }
+
+    /// Add one request's token usage to the session totals
+    pub fn record_token_usage(&mut self, usage: &TokenUsage) {
+        self.tokens_used.0 += u64::from(usage.input_tokens);
+        self.tokens_used.1 += u64::from(usage.output_tokens);
+    }
+
+    /// Totals for `/stats`, with the duration measured up to `now`
+    pub fn stats(&self, now: SystemTime) -> SessionStats {
+        let mut stats = SessionStats {
+            tool_calls: self.tool_counters.clone(),
+            input_tokens: self.tokens_used.0,
+            output_tokens: self.tokens_used.1,
+            duration_secs: now.duration_since(self.started_at).map(|d| d.as_secs()).unwrap_or(0),
+            ..SessionStats::default()
+        };
+        for msg in &self.messages {
+            *stats.messages_by_role.entry(role_name(&msg.role)).or_default() += 1;
+            let Some(info) = &msg.tool_info else { continue };
+            let result = info.result.as_ref();
+            match info.tool {
+                ToolName::FsRead => {
+                    let contents = result.and_then(|r| r.get("contents")).and_then(|c| c.as_str());
+                    stats.bytes_read += contents.map_or(0, |c| c.len() as u64);
+                }
+                ToolName::FsWrite => {
+                    stats.bytes_written += result.and_then(|r| r.get("bytes_written")).and_then(|b| b.as_u64()).unwrap_or(0);
+                }
+                _ => {}
+            }
+        }
+        stats
+    }
+
+    /// The conversation as Markdown for sharing or archiving: a heading per message,
+    /// message text as written, and each tool call as a collapsible `<details>` section
+    pub fn export_markdown(&self) -> String {
+        let mut out = String::from("# Grok Code conversation\n");
+        for msg in &self.messages {
+            out.push('\n');
+            match &msg.tool_info {
+                Some(info) => out.push_str(&tool_markdown(info)),
+                None => {
+                    let heading = match msg.role {
+                        MessageRole::User => "You",
+                        MessageRole::Agent => "Assistant",
+                        MessageRole::System => "System",
+                        MessageRole::Error => "Error",
+                        MessageRole::Tool => "Tool",
+                    };
+                    out.push_str(&format!("## {}\n\n{}\n", heading, msg.content.trim_end()));
+                }
+            }
+        }
+        out
+    }
+
+    /// Write [`Session::export_markdown`] to `path`, relative to the workspace root.
+    /// Returns the path written.
+    pub fn export_markdown_to(&self, path: &str) -> Result<PathBuf, String> {
+        let path = self.workspace_root().join(path);
+        fs::write(&path, self.export_markdown())
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        Ok(path)
+    }
+
+    /// Drop the last `n` messages, e.g. to remove a bad turn before retrying.
+    /// Returns how many were actually removed.
+    pub fn remove_last_messages(&mut self, n: usize) -> usize {
+        let removed = n.min(self.messages.len());
+        self.messages.truncate(self.messages.len() - removed);
+        removed
+    }
+
+    /// Clear all messages except pinned ones and note the reset in the chat
     pub fn clear(&mut self) {
-        self.messages.clear();
+        self.messages.retain(|msg| msg.pinned);
         self.add_system_message("Conversation and context cleared.".to_string());
     }
+
+    /// Pin or unpin the message at 1-based `position` (as numbered by
+    /// [`Session::history_listing`]); `None` picks the latest message that isn't a tool
+    /// call when pinning, or the latest pinned one when unpinning
+    pub fn set_pinned(&mut self, position: Option<usize>, pinned: bool) -> Result<usize, String> {
+        let index = match position {
+            Some(0) => return Err("Message numbers start at 1".to_string()),
+            Some(n) if n > self.messages.len() => {
+                return Err(format!("No message {} (history has {})", n, self.messages.len()))
+            }
+            Some(n) => n - 1,
+            None => self
+                .messages
+                .iter()
+                .rposition(|msg| if pinned { msg.role != MessageRole::Tool } else { msg.pinned })
+                .ok_or_else(|| if pinned { "No message to pin" } else { "No pinned messages" }.to_string())?,
+        };
+        self.messages[index].pinned = pinned;
+        Ok(index + 1)
+    }
     
+    /// Whether side-effecting tools are blocked for this session
+    pub fn read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Block or allow side-effecting tools from the next message on
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+    }
+
+    /// Whether `fs.apply_patch` asks for approval before writing
+    pub fn patch_approval(&self) -> bool {
+        self.patch_approval
+    }
+
+    /// Ask for approval before `fs.apply_patch` writes, or apply edits directly, from the next message on
+    pub fn set_patch_approval(&mut self, patch_approval: bool) {
+        self.patch_approval = patch_approval;
+    }
+
+    /// Re-read the system prompt and `AGENTS.md` from the workspace root for later turns
+    pub fn reload_prompt(&self) -> Result<String, String> {
+        self.agent.reload_prompt(&self.workspace_root())
+    }
+
     /// Get agent information
     pub fn agent_info(&self) -> crate::agent::AgentInfo {
         self.agent.info()
@@ -207,6 +612,8 @@ impl Session {
             content: format!("Agent ran {}", tool_info.summary),
             timestamp_secs,
             tool_info: Some(tool_info),
+            pinned: false,
+            kind: MessageKind::Text,
         };
         self.messages.push(message);
     }
@@ -221,8 +628,14 @@ impl Session {
         self.messages.iter().filter(|msg| msg.role != MessageRole::Tool).collect()
     }
 
+    /// Calls per tool wire name since the session started
+    pub fn tool_counters(&self) -> &BTreeMap<&'static str, ToolCounter> {
+        &self.tool_counters
+    }
+
     /// Handle tool begin event - creates a new tool message
     pub fn handle_tool_begin(&mut self, id: String, tool: ToolName, summary: String, args: Option<serde_json::Value>) {
+        self.tool_counters.entry(tool.wire_name()).or_default().invocations += 1;
         let tool_info = ToolMessageInfo {
             id: id.clone(),
             tool,
@@ -233,6 +646,7 @@ impl Session {
             stdout: String::new(),
             stderr: String::new(),
             result: None,
+            result_bytes: None,
         };
         self.add_tool_message(tool_info);
     }
@@ -286,21 +700,58 @@ impl Session {
     }
 
     /// Handle tool end event
-    pub fn handle_tool_end(&mut self, id: String, ok: bool, _duration_ms: u64) {
+    pub fn handle_tool_end(&mut self, id: String, ok: bool, _duration_ms: u64, result_bytes: Option<u64>) {
         if let Some(msg) = self.messages.iter_mut().rev().find(|msg| {
             msg.role == MessageRole::Tool && 
             msg.tool_info.as_ref().map(|ti| ti.id == id).unwrap_or(false)
         }) {
             if let Some(ref mut tool_info) = msg.tool_info {
                 tool_info.status = if ok { ToolStatus::Completed } else { ToolStatus::Failed };
+                tool_info.result_bytes = result_bytes;
+                let counter = self.tool_counters.entry(tool_info.tool.wire_name()).or_default();
+                if ok {
+                    counter.succeeded += 1;
+                } else {
+                    counter.failed += 1;
+                }
             }
         }
     }
     
-    /// Replace all messages with new ones (for loading saved chats)
+    /// Replace all messages with new ones (for loading saved chats), applying the history cap
     pub fn replace_messages(&mut self, messages: Vec<ChatMessage>) {
-        self.messages = messages;
+        self.messages = self.retained(messages);
+    }
+}
+
+/// One tool call as a collapsed section holding its arguments, output and result
+fn tool_markdown(info: &ToolMessageInfo) -> String {
+    let status = match info.status {
+        ToolStatus::Running => "running",
+        ToolStatus::Completed => "completed",
+        ToolStatus::Failed => "failed",
+    };
+    let mut out = format!(
+        "<details>\n<summary>Tool: {} — {} ({})</summary>\n\n",
+        info.tool.wire_name(),
+        info.summary,
+        status
+    );
+    let pretty = |value: &serde_json::Value| serde_json::to_string_pretty(value).unwrap_or_default();
+    if let Some(args) = &info.args {
+        out.push_str(&format!("**Arguments**\n\n{}\n", fenced("json", &pretty(args))));
     }
+    if !info.stdout.is_empty() {
+        out.push_str(&format!("**Stdout**\n\n{}\n", fenced("text", &info.stdout)));
+    }
+    if !info.stderr.is_empty() {
+        out.push_str(&format!("**Stderr**\n\n{}\n", fenced("text", &info.stderr)));
+    }
+    if let Some(result) = &info.result {
+        out.push_str(&format!("**Result**\n\n{}\n", fenced("json", &pretty(result))));
+    }
+    out.push_str("</details>\n");
+    out
 }
 
 impl ChatMessage {
@@ -329,3 +780,175 @@ impl ChatMessage {
 
 // TODO: Add tests back when we have a test agent implementation
 // The current test was tightly coupled to MockAgent behavior
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::agent_logic::MultiModelAgent;
+    use crate::events::EventBus;
+
+    fn test_session() -> Session {
+        let bus = EventBus::new();
+        let agent = MultiModelAgent::new("test-key".to_string(), "test-model".to_string(), bus.sender())
+            .expect("agent should build");
+        Session::new(std::sync::Arc::new(agent), bus.sender())
+    }
+
+    #[test]
+    fn test_history_listing_shows_roles_and_truncates() {
+        let mut session = test_session();
+        assert_eq!(session.history_listing(), "History is empty.");
+
+        session.add_user_message("read the\nmain file".to_string());
+        session.add_agent_message("x".repeat(200));
+
+        let listing = session.history_listing();
+        let lines: Vec<&str> = listing.lines().collect();
+        assert_eq!(lines[0], "History (2 messages):");
+        assert_eq!(lines[1], "  1. [user] read the main file");
+        assert!(lines[2].starts_with("  2. [assistant] xxx"));
+        assert!(lines[2].ends_with('…'));
+        assert_eq!(lines[2].chars().filter(|c| *c == 'x').count(), HISTORY_PREVIEW_CHARS);
+    }
+
+    #[test]
+    fn test_export_markdown_has_role_headers_and_fenced_tool_result() {
+        let mut session = test_session();
+        session.add_user_message("what's in main.rs?".to_string());
+        session.handle_tool_begin(
+            "t1".to_string(),
+            ToolName::FsRead,
+            "Reading file: main.rs".to_string(),
+            Some(serde_json::json!({ "path": "main.rs" })),
+        );
+        session.handle_tool_result("t1".to_string(), serde_json::json!({ "contents": "fn main() {}" }));
+        session.handle_tool_end("t1".to_string(), true, 5, None);
+        session.add_agent_message("It defines an empty `main`.".to_string());
+
+        let markdown = session.export_markdown();
+        assert!(markdown.contains("## You\n\nwhat's in main.rs?\n"));
+        assert!(markdown.contains("## Assistant\n\nIt defines an empty `main`.\n"));
+        assert!(markdown.contains("<details>\n<summary>Tool: fs.read — Reading file: main.rs (completed)</summary>"));
+        assert!(markdown.contains("**Result**\n\n```json\n{\n  \"contents\": \"fn main() {}\"\n}\n```\n"));
+        assert!(markdown.contains("</details>"));
+    }
+
+    #[test]
+    fn test_saved_history_keeps_only_most_recent_messages() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.json");
+        let mut session = test_session().with_history_max_messages(Some(3));
+        for i in 0..5 {
+            session.add_user_message(format!("message {}", i));
+        }
+
+        session.save_to(&path).unwrap();
+        // The in-memory conversation is untouched
+        assert_eq!(session.messages().len(), 5);
+
+        let saved: Vec<ChatMessage> = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        let contents: Vec<&str> = saved.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(contents, vec!["message 2", "message 3", "message 4"]);
+
+        let mut reloaded = test_session().with_history_max_messages(Some(2));
+        reloaded.load_into(Some(path)).unwrap();
+        assert_eq!(reloaded.messages().len(), 2);
+        assert_eq!(reloaded.messages()[0].content, "message 3");
+    }
+
+    #[test]
+    fn test_clear_keeps_pinned_messages() {
+        let mut session = test_session();
+        session.add_system_message("Project context: use tabs".to_string());
+        session.add_user_message("hello".to_string());
+        session.add_agent_message("hi".to_string());
+        session.add_user_message("remember: deploy on fridays".to_string());
+
+        assert_eq!(session.set_pinned(Some(1), true), Ok(1));
+        assert_eq!(session.set_pinned(None, true), Ok(4));
+        session.clear();
+
+        let contents: Vec<&str> = session.messages().iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(contents, vec![
+            "Project context: use tabs",
+            "remember: deploy on fridays",
+            "Conversation and context cleared.",
+        ]);
+
+        // Unpinned messages go on the next clear
+        assert_eq!(session.set_pinned(None, false), Ok(2));
+        session.clear();
+        assert_eq!(session.messages().len(), 2);
+        assert_eq!(session.messages()[0].content, "Project context: use tabs");
+        assert!(session.set_pinned(Some(9), true).is_err());
+    }
+
+    #[test]
+    fn test_stats_counts_messages_tools_tokens_and_bytes() {
+        let mut session = test_session();
+        session.add_user_message("read and write".to_string());
+        session.handle_tool_begin("t1".to_string(), ToolName::FsRead, "Reading file: a.rs".to_string(), None);
+        session.handle_tool_result("t1".to_string(), serde_json::json!({ "contents": "fn a() {}" }));
+        session.handle_tool_end("t1".to_string(), true, 5, None);
+        session.handle_tool_begin("t2".to_string(), ToolName::FsWrite, "Writing to file: b.rs".to_string(), None);
+        session.handle_tool_result("t2".to_string(), serde_json::json!({ "bytes_written": 42 }));
+        session.handle_tool_end("t2".to_string(), true, 5, None);
+        session.handle_tool_begin("t3".to_string(), ToolName::FsRead, "Reading file: c.rs".to_string(), None);
+        session.handle_tool_end("t3".to_string(), false, 5, None);
+        session.add_agent_message("done".to_string());
+        session.record_token_usage(&TokenUsage { input_tokens: 100, output_tokens: 20, total_tokens: 120 });
+        session.record_token_usage(&TokenUsage { input_tokens: 50, output_tokens: 5, total_tokens: 55 });
+
+        let stats = session.stats(session.started_at + std::time::Duration::from_secs(125));
+        assert_eq!(stats.messages_by_role, BTreeMap::from([("assistant", 1), ("tool", 3), ("user", 1)]));
+        assert_eq!(stats.tool_calls["fs.read"].invocations, 2);
+        assert_eq!(stats.tool_calls["fs.write"].invocations, 1);
+        assert_eq!((stats.input_tokens, stats.output_tokens), (150, 25));
+        assert_eq!((stats.bytes_read, stats.bytes_written), (9, 42));
+        assert_eq!(stats.duration_secs, 125);
+
+        let report = stats.describe();
+        assert!(report.contains("• Messages: 5 (assistant 1, tool 3, user 1)"));
+        assert!(report.contains("• Tool calls: 3 (fs.read 2 (1 failed), fs.write 1)"));
+        assert!(report.contains("• Tokens: 175 (150 input, 25 output)"));
+        assert!(report.contains("• Duration: 2m 5s"));
+    }
+
+    #[test]
+    fn test_tool_counters_track_invocations_and_outcomes() {
+        let mut session = test_session();
+        assert!(session.tool_counters().is_empty());
+
+        session.handle_tool_begin("t1".to_string(), ToolName::ShellExec, "Executing: ls".to_string(), None);
+        session.handle_tool_end("t1".to_string(), true, 5, None);
+        session.handle_tool_begin("t2".to_string(), ToolName::ShellExec, "Executing: false".to_string(), None);
+        session.handle_tool_end("t2".to_string(), false, 5, None);
+        session.handle_tool_begin("t3".to_string(), ToolName::FsRead, "Reading file: a.rs".to_string(), None);
+
+        let counters = session.tool_counters();
+        assert_eq!(counters["shell.exec"], ToolCounter { invocations: 2, succeeded: 1, failed: 1 });
+        // Still running, so neither succeeded nor failed yet
+        assert_eq!(counters["fs.read"], ToolCounter { invocations: 1, succeeded: 0, failed: 0 });
+
+        // Counters outlive the conversation
+        session.clear();
+        assert_eq!(session.tool_counters()["shell.exec"].invocations, 2);
+    }
+
+    #[test]
+    fn test_remove_last_messages() {
+        let mut session = test_session();
+        session.add_user_message("first".to_string());
+        session.add_agent_message("first answer".to_string());
+        session.add_user_message("bad prompt".to_string());
+        session.add_agent_message("bad answer".to_string());
+
+        assert_eq!(session.remove_last_messages(2), 2);
+        assert_eq!(session.messages().len(), 2);
+        assert_eq!(session.messages()[1].content, "first answer");
+
+        // Asking for more than exist empties the history
+        assert_eq!(session.remove_last_messages(10), 2);
+        assert!(session.messages().is_empty());
+    }
+}
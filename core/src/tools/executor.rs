@@ -1,17 +1,38 @@
+use crate::config::ToolSettings;
 use crate::events::{AppEvent, EventSender, ToolName};
 use crate::tools::types::*;
-use crate::tools::executors::{FsExecutor, ShellExecutor, CodeExecutor, LlmExecutor};
+use crate::tools::executors::{FsExecutor, ShellExecutor, CodeExecutor, LlmExecutor, HttpExecutor};
+use crate::tools::ToolRegistry;
 use serde_json::Value;
+use std::path::PathBuf;
 use std::time::Instant;
 
+/// Output cap for each tool result unless GROK_TOOL_MAX_OUTPUT_SIZE says otherwise
+pub const DEFAULT_MAX_OUTPUT_SIZE: usize = 1024 * 1024;
+
+/// Longest path, command or query shown in a tool summary unless GROK_TOOL_SUMMARY_MAX_CHARS says otherwise
+pub const DEFAULT_SUMMARY_MAX_CHARS: usize = 80;
+
+/// Whether `GROK_READ_ONLY` asks for side-effecting tools to be blocked
+pub fn read_only_from_env() -> bool {
+    std::env::var("GROK_READ_ONLY")
+        .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "on" | "yes"))
+        .unwrap_or(false)
+}
+
 /// Tool executor that performs real file system and shell operations
 pub struct ToolExecutor {
     event_sender: EventSender,
     max_output_size: usize,
+    /// Cap on each value quoted in a tool summary; the full value stays in the tool's args
+    summary_max_chars: usize,
+    /// Refuse tools whose spec has side effects
+    read_only: bool,
     fs_executor: FsExecutor,
     shell_executor: ShellExecutor,
     code_executor: CodeExecutor,
     llm_executor: LlmExecutor,
+    http_executor: HttpExecutor,
 }
 
 impl ToolExecutor {
@@ -19,20 +40,29 @@ impl ToolExecutor {
         let max_output_size = std::env::var("GROK_TOOL_MAX_OUTPUT_SIZE")
             .ok()
             .and_then(|s| s.parse().ok())
-            .unwrap_or(1024 * 1024); // 1MB default
+            .unwrap_or(DEFAULT_MAX_OUTPUT_SIZE);
+        let summary_max_chars = std::env::var("GROK_TOOL_SUMMARY_MAX_CHARS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(DEFAULT_SUMMARY_MAX_CHARS);
+        let read_only = read_only_from_env();
 
         let fs_executor = FsExecutor::new(event_sender.clone(), max_output_size);
         let shell_executor = ShellExecutor::new(event_sender.clone(), max_output_size);
         let code_executor = CodeExecutor::new(event_sender.clone(), max_output_size);
         let llm_executor = LlmExecutor::new(event_sender.clone(), max_output_size);
+        let http_executor = HttpExecutor::new(event_sender.clone(), max_output_size);
 
         Self {
             event_sender,
             max_output_size,
+            summary_max_chars,
+            read_only,
             fs_executor,
             shell_executor,
             code_executor,
             llm_executor,
+            http_executor,
         }
     }
 
@@ -42,6 +72,54 @@ impl ToolExecutor {
         self.shell_executor = ShellExecutor::new(self.event_sender.clone(), max_output_size);
         self.code_executor = CodeExecutor::new(self.event_sender.clone(), max_output_size);
         self.llm_executor = LlmExecutor::new(self.event_sender.clone(), max_output_size);
+        self.http_executor = HttpExecutor::new(self.event_sender.clone(), max_output_size);
+        self
+    }
+
+    /// Apply the resolved `[tools]` settings. A new output size rebuilds every executor,
+    /// so call this before the other builders.
+    pub fn with_tool_settings(mut self, settings: &ToolSettings) -> Self {
+        if let Some(max_output_size) = settings.max_output_size {
+            self = self.with_max_output_size(max_output_size);
+        }
+        if !settings.search_skip_exts.is_empty() {
+            self.fs_executor = self.fs_executor.with_search_skip_exts(settings.search_skip_exts.clone());
+        }
+        self.fs_executor = self.fs_executor.with_excludes(settings.excludes.clone());
+        self
+    }
+
+    pub fn with_summary_max_chars(mut self, summary_max_chars: usize) -> Self {
+        self.summary_max_chars = summary_max_chars;
+        self
+    }
+
+    /// Block every side-effecting tool (writes, patches, shell and build commands)
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Why `tool` can't run right now, if it can't
+    fn blocked_reason(&self, tool: &ToolName) -> Option<String> {
+        let side_effects = ToolRegistry::shared().get_spec(tool).is_some_and(|spec| spec.side_effects);
+        (self.read_only && side_effects)
+            .then(|| format!("tool {} is disabled in read-only mode", tool.wire_name()))
+    }
+
+    /// Ask the user to approve `fs.apply_patch` ops before they are written
+    pub fn with_patch_approval(mut self, patch_approval: bool) -> Self {
+        self.fs_executor = self.fs_executor.with_patch_approval(patch_approval);
+        self
+    }
+
+    /// Resolve tool paths and shell working directories against `root` instead of the
+    /// process working directory. `None` keeps the process working directory.
+    pub fn with_root(mut self, root: Option<PathBuf>) -> Self {
+        self.fs_executor = self.fs_executor.with_root(root.clone());
+        self.shell_executor = self.shell_executor.with_root(root.clone());
+        self.code_executor = self.code_executor.with_root(root.clone());
+        self.llm_executor = self.llm_executor.with_root(root);
         self
     }
 
@@ -61,30 +139,47 @@ impl ToolExecutor {
         let start = Instant::now();
 
         // Execute the specific tool and get result
-        let result = match tool {
-            ToolName::FsRead => self.fs_executor.execute_read_with_result(id.clone(), args).await,
-            ToolName::FsSearch => self.fs_executor.execute_search_with_result(id.clone(), args).await,
-            ToolName::FsWrite => self.fs_executor.execute_write_with_result(id.clone(), args).await,
-            ToolName::FsApplyPatch => self.fs_executor.execute_apply_patch_with_result(id.clone(), args).await,
-            ToolName::FsSetFile => self.fs_executor.execute_set_file_with_result(id.clone(), args).await,
-            ToolName::FsReplaceOnce => self.fs_executor.execute_replace_once_with_result(id.clone(), args).await,
-            ToolName::FsInsertBefore => self.fs_executor.execute_insert_before_with_result(id.clone(), args).await,
-            ToolName::FsInsertAfter => self.fs_executor.execute_insert_after_with_result(id.clone(), args).await,
-            ToolName::FsDeleteFile => self.fs_executor.execute_delete_file_with_result(id.clone(), args).await,
-            ToolName::FsRenameFile => self.fs_executor.execute_rename_file_with_result(id.clone(), args).await,
-            ToolName::FsFind => self.fs_executor.execute_find_with_result(id.clone(), args).await,
-            ToolName::ShellExec => self.shell_executor.execute_with_result(id.clone(), args).await,
-            ToolName::CodeSymbols => self.code_executor.execute_symbols_with_result(id.clone(), args).await,
-            ToolName::LargeContextFetch => self.llm_executor.execute_large_context_fetch_with_result(id.clone(), args).await,
+        let result = if let Some(reason) = self.blocked_reason(&tool) {
+            Err(reason)
+        } else {
+            match tool {
+                ToolName::FsRead => self.fs_executor.execute_read_with_result(id.clone(), args).await,
+                ToolName::FsSearch => self.fs_executor.execute_search_with_result(id.clone(), args).await,
+                ToolName::FsWrite => self.fs_executor.execute_write_with_result(id.clone(), args).await,
+                ToolName::FsApplyPatch => self.fs_executor.execute_apply_patch_with_result(id.clone(), args).await,
+                ToolName::FsSetFile => self.fs_executor.execute_set_file_with_result(id.clone(), args).await,
+                ToolName::FsReplaceOnce => self.fs_executor.execute_replace_once_with_result(id.clone(), args).await,
+                ToolName::FsInsertBefore => self.fs_executor.execute_insert_before_with_result(id.clone(), args).await,
+                ToolName::FsInsertAfter => self.fs_executor.execute_insert_after_with_result(id.clone(), args).await,
+                ToolName::FsDeleteFile => self.fs_executor.execute_delete_file_with_result(id.clone(), args).await,
+                ToolName::FsRenameFile => self.fs_executor.execute_rename_file_with_result(id.clone(), args).await,
+                ToolName::FsFind => self.fs_executor.execute_find_with_result(id.clone(), args).await,
+                ToolName::FsTree => self.fs_executor.execute_tree_with_result(id.clone(), args).await,
+                ToolName::ShellExec => self.shell_executor.execute_with_result(id.clone(), args).await,
+                ToolName::CodeSymbols => self.code_executor.execute_symbols_with_result(id.clone(), args).await,
+                ToolName::CodeTest => self.code_executor.execute_test_with_result(id.clone(), args).await,
+                ToolName::CodeBuild => self.code_executor.execute_build_with_result(id.clone(), args).await,
+                ToolName::LargeContextFetch => self.llm_executor.execute_large_context_fetch_with_result(id.clone(), args).await,
+                ToolName::HttpFetch => self.http_executor.execute_fetch_with_result(id.clone(), args).await,
+            }
         };
 
         let duration_ms = start.elapsed().as_millis() as u64;
 
+        #[cfg(debug_assertions)]
+        if let Ok(value) = &result {
+            report_schema_drift(&tool, value);
+        }
+
+        let result_bytes = result.as_ref().ok()
+            .map(|value| serde_json::to_vec(value).map(|bytes| bytes.len() as u64).unwrap_or(0));
+
         // Send tool end event
         self.event_sender.send(AppEvent::ToolEnd {
             id: id.clone(),
             ok: result.is_ok(),
             duration_ms,
+            result_bytes,
         }).map_err(|e| format!("Failed to send ToolEnd event: {}", e))?;
 
         result
@@ -105,21 +200,29 @@ impl ToolExecutor {
         let start = Instant::now();
 
         // Execute the specific tool
-        let result = match tool {
-            ToolName::FsRead => self.fs_executor.execute_read(id.clone(), args).await,
-            ToolName::FsSearch => self.fs_executor.execute_search(id.clone(), args).await,
-            ToolName::FsWrite => self.fs_executor.execute_write(id.clone(), args).await,
-            ToolName::FsApplyPatch => self.fs_executor.execute_apply_patch(id.clone(), args).await,
-            ToolName::FsSetFile => self.fs_executor.execute_set_file(id.clone(), args).await,
-            ToolName::FsReplaceOnce => self.fs_executor.execute_replace_once(id.clone(), args).await,
-            ToolName::FsInsertBefore => self.fs_executor.execute_insert_before(id.clone(), args).await,
-            ToolName::FsInsertAfter => self.fs_executor.execute_insert_after(id.clone(), args).await,
-            ToolName::FsDeleteFile => self.fs_executor.execute_delete_file(id.clone(), args).await,
-            ToolName::FsRenameFile => self.fs_executor.execute_rename_file(id.clone(), args).await,
-            ToolName::FsFind => self.fs_executor.execute_find(id.clone(), args).await,
-            ToolName::ShellExec => self.shell_executor.execute(id.clone(), args).await,
-            ToolName::CodeSymbols => self.code_executor.execute_symbols(id.clone(), args).await,
-            ToolName::LargeContextFetch => self.llm_executor.execute_large_context_fetch(id.clone(), args).await,
+        let result = if let Some(reason) = self.blocked_reason(&tool) {
+            Err(reason)
+        } else {
+            match tool {
+                ToolName::FsRead => self.fs_executor.execute_read(id.clone(), args).await,
+                ToolName::FsSearch => self.fs_executor.execute_search(id.clone(), args).await,
+                ToolName::FsWrite => self.fs_executor.execute_write(id.clone(), args).await,
+                ToolName::FsApplyPatch => self.fs_executor.execute_apply_patch(id.clone(), args).await,
+                ToolName::FsSetFile => self.fs_executor.execute_set_file(id.clone(), args).await,
+                ToolName::FsReplaceOnce => self.fs_executor.execute_replace_once(id.clone(), args).await,
+                ToolName::FsInsertBefore => self.fs_executor.execute_insert_before(id.clone(), args).await,
+                ToolName::FsInsertAfter => self.fs_executor.execute_insert_after(id.clone(), args).await,
+                ToolName::FsDeleteFile => self.fs_executor.execute_delete_file(id.clone(), args).await,
+                ToolName::FsRenameFile => self.fs_executor.execute_rename_file(id.clone(), args).await,
+                ToolName::FsFind => self.fs_executor.execute_find(id.clone(), args).await,
+                ToolName::FsTree => self.fs_executor.execute_tree(id.clone(), args).await,
+                ToolName::ShellExec => self.shell_executor.execute(id.clone(), args).await,
+                ToolName::CodeSymbols => self.code_executor.execute_symbols(id.clone(), args).await,
+                ToolName::CodeTest => self.code_executor.execute_test(id.clone(), args).await,
+                ToolName::CodeBuild => self.code_executor.execute_build(id.clone(), args).await,
+                ToolName::LargeContextFetch => self.llm_executor.execute_large_context_fetch(id.clone(), args).await,
+                ToolName::HttpFetch => self.http_executor.execute_fetch(id.clone(), args).await,
+            }
         };
 
         let duration_ms = start.elapsed().as_millis() as u64;
@@ -129,30 +232,33 @@ impl ToolExecutor {
             id: id.clone(),
             ok: result.is_ok(),
             duration_ms,
+            result_bytes: None,
         }).map_err(|e| format!("Failed to send ToolEnd event: {}", e))?;
 
         result
     }
 
     fn get_tool_summary(&self, tool: &ToolName, args: &Value) -> String {
+        let path = |p: &str| ellipsize_middle(p, self.summary_max_chars);
+        let text = |t: &str| ellipsize_tail(t, self.summary_max_chars);
         match tool {
             ToolName::FsRead => {
                 if let Ok(args) = serde_json::from_value::<FsReadArgs>(args.clone()) {
-                    format!("Reading file: {}", args.path)
+                    format!("Reading file: {}", path(&args.path))
                 } else {
                     "Reading file".to_string()
                 }
             }
             ToolName::FsSearch => {
                 if let Ok(args) = serde_json::from_value::<FsSearchArgs>(args.clone()) {
-                    format!("Searching for: {}", args.query)
+                    format!("Searching for: {}", text(&args.query))
                 } else {
                     "Searching files".to_string()
                 }
             }
             ToolName::FsWrite => {
                 if let Ok(args) = serde_json::from_value::<FsWriteArgs>(args.clone()) {
-                    format!("Writing to file: {}", args.path)
+                    format!("Writing to file: {}", path(&args.path))
                 } else {
                     "Writing file".to_string()
                 }
@@ -160,75 +266,139 @@ impl ToolExecutor {
             ToolName::FsApplyPatch => "Applying patch".to_string(),
             ToolName::FsSetFile => {
                 if let Ok(args) = serde_json::from_value::<FsSetFileArgs>(args.clone()) {
-                    format!("Setting file contents: {}", args.path)
+                    format!("Setting file contents: {}", path(&args.path))
                 } else {
                     "Setting file contents".to_string()
                 }
             }
             ToolName::FsReplaceOnce => {
                 if let Ok(args) = serde_json::from_value::<FsReplaceOnceArgs>(args.clone()) {
-                    format!("Replacing text in file: {}", args.path)
+                    format!("Replacing text in file: {}", path(&args.path))
                 } else {
                     "Replacing text in file".to_string()
                 }
             }
             ToolName::FsInsertBefore => {
                 if let Ok(args) = serde_json::from_value::<FsInsertBeforeArgs>(args.clone()) {
-                    format!("Inserting text before anchor in file: {}", args.path)
+                    format!("Inserting text before anchor in file: {}", path(&args.path))
                 } else {
                     "Inserting text before anchor".to_string()
                 }
             }
             ToolName::FsInsertAfter => {
                 if let Ok(args) = serde_json::from_value::<FsInsertAfterArgs>(args.clone()) {
-                    format!("Inserting text after anchor in file: {}", args.path)
+                    format!("Inserting text after anchor in file: {}", path(&args.path))
                 } else {
                     "Inserting text after anchor".to_string()
                 }
             }
             ToolName::FsDeleteFile => {
                 if let Ok(args) = serde_json::from_value::<FsDeleteFileArgs>(args.clone()) {
-                    format!("Deleting file: {}", args.path)
+                    format!("Deleting file: {}", path(&args.path))
                 } else {
                     "Deleting file".to_string()
                 }
             }
             ToolName::FsRenameFile => {
                 if let Ok(args) = serde_json::from_value::<FsRenameFileArgs>(args.clone()) {
-                    format!("Renaming file {} to {}", args.path, args.to)
+                    format!("Renaming file {} to {}", path(&args.path), path(&args.to))
                 } else {
                     "Renaming file".to_string()
                 }
             }
             ToolName::FsFind => {
                 if let Ok(args) = serde_json::from_value::<FsFindArgs>(args.clone()) {
-                    format!("Finding files: {}", args.pattern)
+                    format!("Finding files: {}", text(&args.pattern))
                 } else {
                     "Finding files".to_string()
                 }
             }
+            ToolName::FsTree => {
+                if let Ok(args) = serde_json::from_value::<FsTreeArgs>(args.clone()) {
+                    format!("Listing tree: {}", path(args.path.as_deref().unwrap_or(".")))
+                } else {
+                    "Listing directory tree".to_string()
+                }
+            }
             ToolName::ShellExec => {
                 if let Ok(args) = serde_json::from_value::<ShellExecArgs>(args.clone()) {
-                    format!("Executing: {}", args.command.join(" "))
+                    format!("Executing: {}", text(&args.command.join(" ")))
                 } else {
                     "Executing command".to_string()
                 }
             }
             ToolName::CodeSymbols => {
                 if let Ok(args) = serde_json::from_value::<CodeSymbolsArgs>(args.clone()) {
-                    format!("Analyzing symbols in: {}", args.path)
+                    format!("Analyzing symbols in: {}", path(&args.path))
                 } else {
                     "Analyzing code symbols".to_string()
                 }
             }
+            ToolName::CodeTest => {
+                if let Ok(args) = serde_json::from_value::<CodeTestArgs>(args.clone()) {
+                    format!("Running tests in: {}", path(args.path.as_deref().unwrap_or(".")))
+                } else {
+                    "Running tests".to_string()
+                }
+            }
+            ToolName::CodeBuild => {
+                if let Ok(args) = serde_json::from_value::<CodeBuildArgs>(args.clone()) {
+                    format!("Checking build in: {}", path(args.path.as_deref().unwrap_or(".")))
+                } else {
+                    "Checking build".to_string()
+                }
+            }
             ToolName::LargeContextFetch => {
                 if let Ok(args) = serde_json::from_value::<LargeContextFetchArgs>(args.clone()) {
                     let base = args.base_path.as_deref().unwrap_or(".");
-                    format!("Fetching relevant code context for query: {} (from: {})", args.user_query, base)
+                    format!("Fetching relevant code context for query: {} (from: {})", text(&args.user_query), path(base))
                 } else {
                     "Fetching relevant code context".to_string()
                 }
             }
+            ToolName::HttpFetch => {
+                if let Ok(args) = serde_json::from_value::<HttpFetchArgs>(args.clone()) {
+                    format!("Fetching URL: {}", text(&args.url))
+                } else {
+                    "Fetching URL".to_string()
+                }
+            }
         }
     }
-}
\ No newline at end of file
+}
+
+/// `value` cut to `max` characters by replacing its middle with "…", keeping both ends
+/// of a path (the root and the file name) visible
+fn ellipsize_middle(value: &str, max: usize) -> String {
+    let len = value.chars().count();
+    if len <= max || max == 0 {
+        return value.to_string();
+    }
+    let keep = max.saturating_sub(1);
+    let tail = keep / 2;
+    let head = keep - tail;
+    let start: String = value.chars().take(head).collect();
+    let end: String = value.chars().skip(len - tail).collect();
+    format!("{}…{}", start, end)
+}
+
+/// `value` cut to `max` characters, ending in "…"
+fn ellipsize_tail(value: &str, max: usize) -> String {
+    if value.chars().count() <= max || max == 0 {
+        return value.to_string();
+    }
+    let start: String = value.chars().take(max.saturating_sub(1)).collect();
+    format!("{}…", start)
+}
+
+/// Debug-only check that a tool's result still matches its declared `output_schema`.
+/// Test builds panic so drift fails the suite; other debug builds only log.
+#[cfg(debug_assertions)]
+fn report_schema_drift(tool: &ToolName, value: &Value) {
+    if let Err(e) = crate::tools::schema::validate_tool_output(tool, value) {
+        if cfg!(test) {
+            panic!("{}", e);
+        }
+        tracing::warn!("{}", e);
+    }
+}
@@ -1,12 +1,28 @@
 use crate::events::{AppEvent, EventSender};
+use crate::tools::executors::ShellExecutor;
 use crate::tools::types::*;
 use serde_json::Value;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+mod diagnostics;
+mod project;
+mod scope;
+
+use project::ProjectKind;
+use scope::{BraceScopes, IndentScopes};
+
+/// Default limit for a whole test run
+const DEFAULT_TEST_TIMEOUT_MS: u64 = 300_000;
+
+/// Default limit for a build or typecheck
+const DEFAULT_BUILD_TIMEOUT_MS: u64 = 300_000;
 
 /// Code analysis executor
 pub struct CodeExecutor {
     event_sender: EventSender,
     max_output_size: usize,
+    /// Relative paths resolve against this instead of the process working directory
+    root: Option<PathBuf>,
 }
 
 impl CodeExecutor {
@@ -14,9 +30,20 @@ impl CodeExecutor {
         Self {
             event_sender,
             max_output_size,
+            root: None,
         }
     }
 
+    /// Resolve relative paths against `root` (the session's working directory)
+    pub fn with_root(mut self, root: Option<PathBuf>) -> Self {
+        self.root = super::root::normalize(root);
+        self
+    }
+
+    fn resolve(&self, path: &str) -> String {
+        super::root::resolve(self.root.as_deref(), path)
+    }
+
     /// Truncate a JSON value if it exceeds the maximum output size
     fn truncate_result(&self, result: Value) -> Value {
         let json_str = serde_json::to_string(&result).unwrap_or_else(|_| "{}".to_string());
@@ -41,8 +68,9 @@ impl CodeExecutor {
     }
 
     pub async fn execute_symbols_with_result(&self, id: String, args: Value) -> Result<Value, String> {
-        let args: CodeSymbolsArgs = serde_json::from_value(args)
+        let mut args: CodeSymbolsArgs = serde_json::from_value(args)
             .map_err(|e| format!("Invalid CodeSymbols arguments: {}", e))?;
+        args.path = self.resolve(&args.path);
 
         // Send progress event
         self.event_sender.send(AppEvent::ToolProgress {
@@ -89,6 +117,125 @@ impl CodeExecutor {
 
         Ok(truncated_result)
     }
+
+    pub async fn execute_test(&self, id: String, args: Value) -> Result<(), String> {
+        let _result = self.execute_test_with_result(id, args).await?;
+        Ok(())
+    }
+
+    pub async fn execute_test_with_result(&self, id: String, args: Value) -> Result<Value, String> {
+        let args: CodeTestArgs = serde_json::from_value(args)
+            .map_err(|e| format!("Invalid CodeTest arguments: {}", e))?;
+
+        let root = self.resolve(args.path.as_deref().unwrap_or("."));
+        let kind = ProjectKind::detect(Path::new(&root)).ok_or_else(|| {
+            format!("No Cargo.toml, package.json, go.mod or pytest config found in: {}", root)
+        })?;
+        let command = kind.test_command(args.filter.as_deref());
+
+        self.event_sender.send(AppEvent::ToolProgress {
+            id: id.clone(),
+            message: format!("Running {} tests in: {}", kind.name(), root),
+        }).ok();
+
+        // Output streams to the UI like any shell.exec call; a failing run is still a result
+        let shell = ShellExecutor::new(self.event_sender.clone(), self.max_output_size);
+        let shell_args = ShellExecArgs {
+            command: command.clone(),
+            cwd: Some(root),
+            env: None,
+            timeout_ms: Some(args.timeout_ms.unwrap_or(DEFAULT_TEST_TIMEOUT_MS)),
+            with_escalated_permissions: None,
+            justification: None,
+            expand_env: None,
+            undefined_env_empty: None,
+        };
+        let run = shell.run(&id, &shell_args).await?;
+
+        let output = format!("{}\n{}", run.stdout, run.stderr);
+        let counts = kind.parse_test_output(&output);
+        let result = CodeTestResult {
+            project_type: kind.name().to_string(),
+            command,
+            exit_code: run.exit_code,
+            duration_ms: run.duration_ms,
+            passed: counts.as_ref().map(|c| c.passed),
+            failed: counts.as_ref().map(|c| c.failed),
+            failing_tests: counts.as_ref().map(|c| c.failing_tests.clone()).unwrap_or_default(),
+            raw_output: counts.is_none().then_some(output),
+        };
+
+        let result_value = serde_json::to_value(result).unwrap();
+        let truncated_result = self.truncate_result(result_value.clone());
+
+        self.event_sender.send(AppEvent::ToolResult {
+            id,
+            payload: result_value,
+        }).ok();
+
+        Ok(truncated_result)
+    }
+
+    pub async fn execute_build(&self, id: String, args: Value) -> Result<(), String> {
+        let _result = self.execute_build_with_result(id, args).await?;
+        Ok(())
+    }
+
+    pub async fn execute_build_with_result(&self, id: String, args: Value) -> Result<Value, String> {
+        let args: CodeBuildArgs = serde_json::from_value(args)
+            .map_err(|e| format!("Invalid CodeBuild arguments: {}", e))?;
+
+        let root = self.resolve(args.path.as_deref().unwrap_or("."));
+        let kind = ProjectKind::detect(Path::new(&root)).ok_or_else(|| {
+            format!("No Cargo.toml, package.json or go.mod found in: {}", root)
+        })?;
+        let command = kind
+            .build_command()
+            .ok_or_else(|| format!("No build command for {} projects", kind.name()))?;
+
+        self.event_sender.send(AppEvent::ToolProgress {
+            id: id.clone(),
+            message: format!("Running {} in: {}", command.join(" "), root),
+        }).ok();
+
+        // A failed build is the interesting case, so a non-zero exit is still a result
+        let shell = ShellExecutor::new(self.event_sender.clone(), self.max_output_size);
+        let shell_args = ShellExecArgs {
+            command: command.clone(),
+            cwd: Some(root),
+            env: None,
+            timeout_ms: Some(args.timeout_ms.unwrap_or(DEFAULT_BUILD_TIMEOUT_MS)),
+            with_escalated_permissions: None,
+            justification: None,
+            expand_env: None,
+            undefined_env_empty: None,
+        };
+        let run = shell.run(&id, &shell_args).await?;
+
+        let diagnostics = kind.parse_build_output(&run.stdout, &run.stderr).unwrap_or_default();
+        // Fall back to the raw text when a failure produced nothing we could parse
+        let raw_output = (run.exit_code != 0 && diagnostics.is_empty())
+            .then(|| format!("{}\n{}", run.stdout, run.stderr));
+        let result = CodeBuildResult {
+            project_type: kind.name().to_string(),
+            command,
+            exit_code: run.exit_code,
+            duration_ms: run.duration_ms,
+            success: run.exit_code == 0,
+            diagnostics,
+            raw_output,
+        };
+
+        let result_value = serde_json::to_value(result).unwrap();
+        let truncated_result = self.truncate_result(result_value.clone());
+
+        self.event_sender.send(AppEvent::ToolResult {
+            id,
+            payload: result_value,
+        }).ok();
+
+        Ok(truncated_result)
+    }
 }
 
 // Helper functions for code analysis
@@ -135,10 +282,13 @@ fn extract_symbols(content: &str, language: &str, symbol_types: Option<&[String]
 
 fn extract_rust_symbols(content: &str, symbols: &mut Vec<CodeSymbol>, _symbol_types: Option<&[String]>) {
     let lines: Vec<&str> = content.lines().collect();
+    let mut scopes = BraceScopes::default();
     
     for (line_num, line) in lines.iter().enumerate() {
         let line_number = (line_num + 1) as u32;
         let trimmed = line.trim();
+        let scope = scopes.current();
+        let found_before = symbols.len();
         
         // Functions
         if let Some(fn_match) = extract_rust_function(trimmed) {
@@ -147,7 +297,7 @@ fn extract_rust_symbols(content: &str, symbols: &mut Vec<CodeSymbol>, _symbol_ty
                 symbol_type: "function".to_string(),
                 line_start: line_number,
                 line_end: line_number,
-                scope: None,
+                scope: scope.clone(),
                 visibility: get_rust_visibility(trimmed),
             });
         }
@@ -159,7 +309,7 @@ fn extract_rust_symbols(content: &str, symbols: &mut Vec<CodeSymbol>, _symbol_ty
                 symbol_type: "struct".to_string(),
                 line_start: line_number,
                 line_end: line_number,
-                scope: None,
+                scope: scope.clone(),
                 visibility: get_rust_visibility(trimmed),
             });
         }
@@ -171,7 +321,7 @@ fn extract_rust_symbols(content: &str, symbols: &mut Vec<CodeSymbol>, _symbol_ty
                 symbol_type: "enum".to_string(),
                 line_start: line_number,
                 line_end: line_number,
-                scope: None,
+                scope: scope.clone(),
                 visibility: get_rust_visibility(trimmed),
             });
         }
@@ -183,7 +333,7 @@ fn extract_rust_symbols(content: &str, symbols: &mut Vec<CodeSymbol>, _symbol_ty
                 symbol_type: "trait".to_string(),
                 line_start: line_number,
                 line_end: line_number,
-                scope: None,
+                scope: scope.clone(),
                 visibility: get_rust_visibility(trimmed),
             });
         }
@@ -195,13 +345,63 @@ fn extract_rust_symbols(content: &str, symbols: &mut Vec<CodeSymbol>, _symbol_ty
                 symbol_type: "module".to_string(),
                 line_start: line_number,
                 line_end: line_number,
-                scope: None,
+                scope: scope.clone(),
+                visibility: get_rust_visibility(trimmed),
+            });
+        }
+
+        // Constants, statics and type aliases
+        if let Some((item_match, symbol_type)) = extract_rust_item(trimmed) {
+            symbols.push(CodeSymbol {
+                name: item_match,
+                symbol_type: symbol_type.to_string(),
+                line_start: line_number,
+                line_end: line_number,
+                scope: scope.clone(),
                 visibility: get_rust_visibility(trimmed),
             });
         }
+
+        // The first declaration on the line (or an impl block) encloses what follows
+        if let Some(name) = extract_rust_impl_target(trimmed).or_else(|| symbols.get(found_before).map(|s| s.name.clone())) {
+            scopes.open(name);
+        }
+        scopes.advance(line);
     }
 }
 
+/// The type an `impl` block is for: `Foo` in `impl<T> fmt::Display for Foo<T> {`
+fn extract_rust_impl_target(line: &str) -> Option<String> {
+    let line = line.strip_prefix("unsafe ").unwrap_or(line);
+    let rest = line.strip_prefix("impl")?;
+    if !rest.starts_with([' ', '<']) {
+        return None;
+    }
+
+    // Skip the impl's own generic parameters, which may nest
+    let mut rest = rest.trim_start();
+    if rest.starts_with('<') {
+        let mut depth = 0;
+        let close = rest.char_indices().find_map(|(i, c)| {
+            match c {
+                '<' => depth += 1,
+                '>' => depth -= 1,
+                _ => {}
+            }
+            (depth == 0).then_some(i)
+        })?;
+        rest = &rest[close + 1..];
+    }
+
+    let header = rest.split('{').next().unwrap_or(rest);
+    let header = header.split(" where").next().unwrap_or(header);
+    let target = header.rsplit_once(" for ").map_or(header, |(_, ty)| ty).trim();
+    let target = target.trim_start_matches('&').trim_start_matches("dyn ").trim();
+    let path = target.split(|c: char| c == '<' || c.is_whitespace()).next()?;
+    let name = path.rsplit("::").next()?;
+    (!name.is_empty()).then(|| name.to_string())
+}
+
 fn extract_rust_function(line: &str) -> Option<String> {
     if line.contains("fn ") {
         let parts: Vec<&str> = line.split("fn ").collect();
@@ -222,7 +422,8 @@ fn extract_rust_struct(line: &str) -> Option<String> {
         let parts: Vec<&str> = line.split("struct ").collect();
         if parts.len() > 1 {
             let after_struct = parts[1];
-            let name_end = after_struct.find(|c: char| c.is_whitespace() || c == '{' || c == '<')
+            // Unit and tuple structs end their name with `;` or `(`
+            let name_end = after_struct.find(|c: char| c.is_whitespace() || matches!(c, '{' | '<' | ';' | '('))
                 .unwrap_or(after_struct.len());
             let name = after_struct[..name_end].trim();
             if !name.is_empty() {
@@ -281,6 +482,42 @@ fn extract_rust_module(line: &str) -> Option<String> {
     None
 }
 
+/// `const NAME:`, `static [mut] NAME:` and `type Name` declarations, with their symbol type
+fn extract_rust_item(line: &str) -> Option<(String, &'static str)> {
+    let rest = strip_rust_visibility(line);
+    let (rest, symbol_type) = if let Some(rest) = rest.strip_prefix("const ") {
+        // `const fn` is a function, picked up by extract_rust_function
+        if rest.starts_with("fn ") || rest.starts_with("unsafe ") {
+            return None;
+        }
+        (rest, "constant")
+    } else if let Some(rest) = rest.strip_prefix("static ") {
+        (rest.strip_prefix("mut ").unwrap_or(rest), "static")
+    } else if let Some(rest) = rest.strip_prefix("type ") {
+        (rest, "type")
+    } else {
+        return None;
+    };
+
+    let name_end = rest.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(rest.len());
+    let name = &rest[..name_end];
+    let follows = rest[name_end..].trim_start();
+    // Require the punctuation that ends the name, so prose like "type of" isn't a symbol
+    let declared = match symbol_type {
+        "type" => follows.starts_with(['=', '<', ';', ':']),
+        _ => follows.starts_with(':'),
+    };
+    (!name.is_empty() && declared).then(|| (name.to_string(), symbol_type))
+}
+
+/// Drop a leading `pub` / `pub(...)` so the item keyword comes first
+fn strip_rust_visibility(line: &str) -> &str {
+    if let Some(rest) = line.strip_prefix("pub(") {
+        return rest.split_once(')').map_or(line, |(_, after)| after.trim_start());
+    }
+    line.strip_prefix("pub ").map_or(line, str::trim_start)
+}
+
 fn get_rust_visibility(line: &str) -> Option<String> {
     if line.starts_with("pub ") {
         Some("public".to_string())
@@ -293,10 +530,13 @@ fn get_rust_visibility(line: &str) -> Option<String> {
 
 fn extract_js_symbols(content: &str, symbols: &mut Vec<CodeSymbol>, _symbol_types: Option<&[String]>) {
     let lines: Vec<&str> = content.lines().collect();
+    let mut scopes = BraceScopes::default();
     
     for (line_num, line) in lines.iter().enumerate() {
         let line_number = (line_num + 1) as u32;
         let trimmed = line.trim();
+        let scope = scopes.current();
+        let found_before = symbols.len();
         
         // Functions
         if let Some(fn_match) = extract_js_function(trimmed) {
@@ -305,7 +545,7 @@ fn extract_js_symbols(content: &str, symbols: &mut Vec<CodeSymbol>, _symbol_type
                 symbol_type: "function".to_string(),
                 line_start: line_number,
                 line_end: line_number,
-                scope: None,
+                scope: scope.clone(),
                 visibility: None,
             });
         }
@@ -317,10 +557,16 @@ fn extract_js_symbols(content: &str, symbols: &mut Vec<CodeSymbol>, _symbol_type
                 symbol_type: "class".to_string(),
                 line_start: line_number,
                 line_end: line_number,
-                scope: None,
+                scope: scope.clone(),
                 visibility: None,
             });
         }
+
+        // A function or class declared on this line encloses what follows
+        if let Some(name) = symbols.get(found_before).map(|s| s.name.clone()) {
+            scopes.open(name);
+        }
+        scopes.advance(line);
     }
 }
 
@@ -372,10 +618,13 @@ fn extract_js_class(line: &str) -> Option<String> {
 
 fn extract_python_symbols(content: &str, symbols: &mut Vec<CodeSymbol>, _symbol_types: Option<&[String]>) {
     let lines: Vec<&str> = content.lines().collect();
+    let mut scopes = IndentScopes::default();
     
     for (line_num, line) in lines.iter().enumerate() {
         let line_number = (line_num + 1) as u32;
         let trimmed = line.trim();
+        let scope = scopes.enter_line(line);
+        let found_before = symbols.len();
         
         // Functions
         if let Some(fn_match) = extract_python_function(trimmed) {
@@ -384,7 +633,7 @@ fn extract_python_symbols(content: &str, symbols: &mut Vec<CodeSymbol>, _symbol_
                 symbol_type: "function".to_string(),
                 line_start: line_number,
                 line_end: line_number,
-                scope: None,
+                scope: scope.clone(),
                 visibility: None,
             });
         }
@@ -396,10 +645,15 @@ fn extract_python_symbols(content: &str, symbols: &mut Vec<CodeSymbol>, _symbol_
                 symbol_type: "class".to_string(),
                 line_start: line_number,
                 line_end: line_number,
-                scope: None,
+                scope: scope.clone(),
                 visibility: None,
             });
         }
+
+        // Lines indented under a class or def belong to it
+        if let Some(symbol) = symbols.get(found_before) {
+            scopes.open(symbol.name.clone(), line);
+        }
     }
 }
 
@@ -436,10 +690,13 @@ fn extract_python_class(line: &str) -> Option<String> {
 
 fn extract_java_symbols(content: &str, symbols: &mut Vec<CodeSymbol>, _symbol_types: Option<&[String]>) {
     let lines: Vec<&str> = content.lines().collect();
+    let mut scopes = BraceScopes::default();
     
     for (line_num, line) in lines.iter().enumerate() {
         let line_number = (line_num + 1) as u32;
         let trimmed = line.trim();
+        let scope = scopes.current();
+        let found_before = symbols.len();
         
         // Classes
         if let Some(class_match) = extract_java_class(trimmed) {
@@ -448,7 +705,7 @@ fn extract_java_symbols(content: &str, symbols: &mut Vec<CodeSymbol>, _symbol_ty
                 symbol_type: "class".to_string(),
                 line_start: line_number,
                 line_end: line_number,
-                scope: None,
+                scope: scope.clone(),
                 visibility: get_java_visibility(trimmed),
             });
         }
@@ -460,10 +717,16 @@ fn extract_java_symbols(content: &str, symbols: &mut Vec<CodeSymbol>, _symbol_ty
                 symbol_type: "function".to_string(),
                 line_start: line_number,
                 line_end: line_number,
-                scope: None,
+                scope: scope.clone(),
                 visibility: get_java_visibility(trimmed),
             });
         }
+
+        // A class or method declared on this line encloses what follows
+        if let Some(name) = symbols.get(found_before).map(|s| s.name.clone()) {
+            scopes.open(name);
+        }
+        scopes.advance(line);
     }
 }
 
@@ -0,0 +1,75 @@
+//! Compiler diagnostics parsing for `code.build`
+
+use super::project::ProjectKind;
+use crate::tools::types::CodeDiagnostic;
+use serde_json::Value;
+
+impl ProjectKind {
+    /// Diagnostics reported by the build command; `None` when the output format isn't known
+    pub(crate) fn parse_build_output(self, stdout: &str, stderr: &str) -> Option<Vec<CodeDiagnostic>> {
+        match self {
+            ProjectKind::Cargo => Some(parse_cargo_json(stdout)),
+            ProjectKind::Npm => Some(stdout.lines().chain(stderr.lines()).filter_map(parse_tsc_line).collect()),
+            ProjectKind::Go => Some(stderr.lines().filter_map(parse_go_line).collect()),
+            ProjectKind::Python => None,
+        }
+    }
+}
+
+/// `cargo check --message-format=json` prints one JSON object per line; only
+/// `compiler-message` entries with a primary span are kept
+fn parse_cargo_json(stdout: &str) -> Vec<CodeDiagnostic> {
+    stdout
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .filter(|entry| entry["reason"] == "compiler-message")
+        .filter_map(|entry| {
+            let message = &entry["message"];
+            let span = message["spans"]
+                .as_array()?
+                .iter()
+                .find(|span| span["is_primary"] == true)?;
+            Some(CodeDiagnostic {
+                file: span["file_name"].as_str()?.to_string(),
+                line: span["line_start"].as_u64()? as u32,
+                column: span["column_start"].as_u64()? as u32,
+                severity: message["level"].as_str().unwrap_or("error").to_string(),
+                message: message["message"].as_str().unwrap_or_default().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// `src/app.ts(12,5): error TS2322: Type 'string' is not assignable to type 'number'.`
+fn parse_tsc_line(line: &str) -> Option<CodeDiagnostic> {
+    let (location, rest) = line.split_once("): ")?;
+    let (file, position) = location.rsplit_once('(')?;
+    let (line_no, column) = position.split_once(',')?;
+    let (severity, message) = rest.split_once(' ')?;
+    if severity != "error" && severity != "warning" {
+        return None;
+    }
+    Some(CodeDiagnostic {
+        file: file.to_string(),
+        line: line_no.parse().ok()?,
+        column: column.parse().ok()?,
+        severity: severity.to_string(),
+        message: message.to_string(),
+    })
+}
+
+/// `./main.go:7:2: undefined: fmt.Printn`
+fn parse_go_line(line: &str) -> Option<CodeDiagnostic> {
+    let mut parts = line.splitn(4, ':');
+    let file = parts.next()?;
+    let line_no = parts.next()?.parse().ok()?;
+    let column = parts.next()?.parse().ok()?;
+    let message = parts.next()?.trim();
+    Some(CodeDiagnostic {
+        file: file.to_string(),
+        line: line_no,
+        column,
+        severity: "error".to_string(),
+        message: message.to_string(),
+    })
+}
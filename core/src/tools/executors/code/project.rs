@@ -0,0 +1,171 @@
+//! Project detection and test output parsing for `code.test` and `code.build`
+
+use std::path::Path;
+
+/// Toolchains recognised from marker files in the project root
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ProjectKind {
+    Cargo,
+    Npm,
+    Python,
+    Go,
+}
+
+/// What a test run reported, when the output format is understood
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct TestCounts {
+    pub passed: u64,
+    pub failed: u64,
+    pub failing_tests: Vec<String>,
+}
+
+impl ProjectKind {
+    /// Checked in order, so a Rust crate with a `package.json` for tooling is still Cargo
+    pub(crate) fn detect(root: &Path) -> Option<Self> {
+        const MARKERS: &[(&str, ProjectKind)] = &[
+            ("Cargo.toml", ProjectKind::Cargo),
+            ("go.mod", ProjectKind::Go),
+            ("package.json", ProjectKind::Npm),
+            ("pyproject.toml", ProjectKind::Python),
+            ("pytest.ini", ProjectKind::Python),
+            ("setup.py", ProjectKind::Python),
+            ("conftest.py", ProjectKind::Python),
+        ];
+        MARKERS
+            .iter()
+            .find(|(marker, _)| root.join(marker).is_file())
+            .map(|(_, kind)| *kind)
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            ProjectKind::Cargo => "cargo",
+            ProjectKind::Npm => "npm",
+            ProjectKind::Python => "pytest",
+            ProjectKind::Go => "go",
+        }
+    }
+
+    /// Test command, optionally narrowed to tests matching `filter`
+    pub(crate) fn test_command(self, filter: Option<&str>) -> Vec<String> {
+        let mut command: Vec<String> = match self {
+            ProjectKind::Cargo => vec!["cargo", "test"],
+            ProjectKind::Npm => vec!["npm", "test"],
+            ProjectKind::Python => vec!["python", "-m", "pytest", "-rf"],
+            ProjectKind::Go => vec!["go", "test", "-v", "./..."],
+        }
+        .into_iter()
+        .map(String::from)
+        .collect();
+
+        if let Some(filter) = filter {
+            match self {
+                ProjectKind::Cargo => command.push(filter.to_string()),
+                ProjectKind::Npm => command.extend(["--".to_string(), filter.to_string()]),
+                ProjectKind::Python => command.extend(["-k".to_string(), filter.to_string()]),
+                ProjectKind::Go => command.extend(["-run".to_string(), filter.to_string()]),
+            }
+        }
+        command
+    }
+
+    /// Build or typecheck command; pytest projects have nothing to compile
+    pub(crate) fn build_command(self) -> Option<Vec<String>> {
+        let command: Vec<&str> = match self {
+            ProjectKind::Cargo => vec!["cargo", "check", "--message-format=json"],
+            ProjectKind::Npm => vec!["npx", "tsc", "--noEmit", "--pretty", "false"],
+            ProjectKind::Go => vec!["go", "build", "./..."],
+            ProjectKind::Python => return None,
+        };
+        Some(command.into_iter().map(String::from).collect())
+    }
+
+    /// Pull pass/fail counts out of the runner's output; `None` when the format isn't known
+    pub(crate) fn parse_test_output(self, output: &str) -> Option<TestCounts> {
+        match self {
+            ProjectKind::Cargo => parse_cargo(output),
+            ProjectKind::Python => parse_pytest(output),
+            ProjectKind::Go => parse_go(output),
+            ProjectKind::Npm => None,
+        }
+    }
+}
+
+/// Sums every `test result:` line (one per test binary) and collects `... FAILED` names
+fn parse_cargo(output: &str) -> Option<TestCounts> {
+    let mut counts = TestCounts::default();
+    let mut saw_summary = false;
+
+    for line in output.lines() {
+        let line = line.trim();
+        if let Some(summary) = line.strip_prefix("test result:") {
+            saw_summary = true;
+            counts.passed += count_before(summary, "passed").unwrap_or(0);
+            counts.failed += count_before(summary, "failed").unwrap_or(0);
+        } else if let Some(name) = line.strip_prefix("test ").and_then(|rest| rest.strip_suffix(" ... FAILED")) {
+            counts.failing_tests.push(name.to_string());
+        }
+    }
+
+    saw_summary.then_some(counts)
+}
+
+/// Reads the final `N failed, M passed in Xs` line and `FAILED path::test` entries
+fn parse_pytest(output: &str) -> Option<TestCounts> {
+    let mut counts = TestCounts::default();
+    let mut saw_summary = false;
+
+    for line in output.lines() {
+        let line = line.trim();
+        if let Some(rest) = line.strip_prefix("FAILED ") {
+            let name = rest.split(" - ").next().unwrap_or(rest);
+            counts.failing_tests.push(name.to_string());
+        } else if line.contains(" passed") || line.contains(" failed") {
+            let summary = line.trim_matches('=').trim();
+            if summary.contains(" in ") {
+                saw_summary = true;
+                counts.passed = count_before(summary, "passed").unwrap_or(0);
+                counts.failed = count_before(summary, "failed").unwrap_or(0);
+            }
+        }
+    }
+
+    saw_summary.then_some(counts)
+}
+
+/// Counts `--- PASS:` / `--- FAIL:` lines from `go test -v`
+fn parse_go(output: &str) -> Option<TestCounts> {
+    let mut counts = TestCounts::default();
+    let mut saw_result = false;
+
+    for line in output.lines() {
+        let line = line.trim();
+        if line.starts_with("--- PASS:") {
+            saw_result = true;
+            counts.passed += 1;
+        } else if let Some(rest) = line.strip_prefix("--- FAIL:") {
+            saw_result = true;
+            counts.failed += 1;
+            if let Some(name) = rest.split_whitespace().next() {
+                counts.failing_tests.push(name.to_string());
+            }
+        } else if line.starts_with("ok ") || line.starts_with("FAIL") {
+            saw_result = true;
+        }
+    }
+
+    saw_result.then_some(counts)
+}
+
+/// The number immediately before `label`, e.g. `3` in `ok. 3 passed; 0 failed`
+fn count_before(text: &str, label: &str) -> Option<u64> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    words.iter().enumerate().find_map(|(i, word)| {
+        let word = word.trim_end_matches([';', ',', '.']);
+        if word == label && i > 0 {
+            words[i - 1].parse().ok()
+        } else {
+            None
+        }
+    })
+}
@@ -0,0 +1,97 @@
+//! Enclosing-scope tracking for `code.symbols`
+//!
+//! Extraction is line based, so scopes are rebuilt from the text around each symbol:
+//! brace depth for Rust, JavaScript and Java, indentation for Python. Both trackers
+//! are heuristics and can be fooled by braces in multi-line strings or block comments.
+
+/// Scopes opened by `{` and closed by the matching `}`
+#[derive(Default)]
+pub(crate) struct BraceScopes {
+    /// Open scopes with the depth their opening brace reached
+    stack: Vec<(String, usize)>,
+    depth: usize,
+    /// A declaration whose opening brace hasn't been seen yet
+    pending: Option<String>,
+}
+
+impl BraceScopes {
+    /// Innermost scope enclosing the line about to be processed
+    pub(crate) fn current(&self) -> Option<String> {
+        self.stack.last().map(|(name, _)| name.clone())
+    }
+
+    /// The next `{` opens a scope called `name`
+    pub(crate) fn open(&mut self, name: String) {
+        self.pending = Some(name);
+    }
+
+    /// Apply the braces on `line`, ignoring ones in strings, char literals and `//` comments
+    pub(crate) fn advance(&mut self, line: &str) {
+        let chars: Vec<char> = line.chars().collect();
+        let mut in_string = false;
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            if in_string {
+                match c {
+                    '\\' => i += 1,
+                    '"' => in_string = false,
+                    _ => {}
+                }
+            } else {
+                match c {
+                    '"' => in_string = true,
+                    '/' if chars.get(i + 1) == Some(&'/') => break,
+                    // Skip '{' and '}' char literals; a lone quote is a lifetime
+                    '\'' if chars.get(i + 2) == Some(&'\'') => i += 2,
+                    '{' => {
+                        self.depth += 1;
+                        if let Some(name) = self.pending.take() {
+                            self.stack.push((name, self.depth));
+                        }
+                    }
+                    '}' => {
+                        if self.stack.last().is_some_and(|(_, depth)| *depth == self.depth) {
+                            self.stack.pop();
+                        }
+                        self.depth = self.depth.saturating_sub(1);
+                    }
+                    _ => {}
+                }
+            }
+            i += 1;
+        }
+
+        // A declaration ending in `;` (`struct Unit;`, `fn required();`) has no body
+        if line.trim_end().ends_with(';') {
+            self.pending = None;
+        }
+    }
+}
+
+/// Scopes that last while lines stay indented deeper than their header
+#[derive(Default)]
+pub(crate) struct IndentScopes {
+    stack: Vec<(String, usize)>,
+}
+
+impl IndentScopes {
+    /// Close scopes `line` has dedented out of and return the one it sits in.
+    /// Blank and comment lines don't end a scope, so they keep the current one.
+    pub(crate) fn enter_line(&mut self, line: &str) -> Option<String> {
+        let trimmed = line.trim_start();
+        if !trimmed.is_empty() && !trimmed.starts_with('#') {
+            let indent = line.len() - trimmed.len();
+            while self.stack.last().is_some_and(|(_, open)| *open >= indent) {
+                self.stack.pop();
+            }
+        }
+        self.stack.last().map(|(name, _)| name.clone())
+    }
+
+    /// `line` is a header (`class`, `def`) opening a scope called `name`
+    pub(crate) fn open(&mut self, name: String, line: &str) {
+        let indent = line.len() - line.trim_start().len();
+        self.stack.push((name, indent));
+    }
+}
@@ -1,20 +1,302 @@
-use crate::events::{AppEvent, EventSender};
+use crate::events::{AppEvent, EventSender, PatchApprovalRequest, PatchOpPreview};
 use crate::tools::types::*;
 use serde_json::Value;
-use std::io::ErrorKind;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 use walkdir::WalkDir;
-use globset::{Glob, GlobSet, GlobSetBuilder};
+use globset::{Glob, GlobBuilder, GlobSet, GlobSetBuilder};
 
+mod diff;
+mod ignore_patterns;
+mod paging;
+mod rank;
 mod simple_edit;
+mod trash;
+mod tree;
+
+use super::root;
+pub use diff::diff_files;
+use ignore_patterns::IgnorePatterns;
+use paging::{Pager, fingerprint};
+use simple_edit::SimpleEditPlanner;
+pub use trash::TRASH_DIR;
+use trash::Trash;
+
+/// Extensions that `fs.search` never opens; extended via GROK_SEARCH_SKIP_EXTS
+const DEFAULT_SEARCH_SKIP_EXTS: &[&str] = &[
+    "exe", "dll", "so", "dylib", "bin", "o", "a", "lib", "obj", "class", "jar", "wasm", "pyc",
+    "png", "jpg", "jpeg", "gif", "bmp", "ico", "webp", "pdf",
+    "zip", "gz", "tgz", "bz2", "xz", "7z", "rar", "tar",
+    "mp3", "mp4", "wav", "mov", "avi", "mkv",
+    "ttf", "otf", "woff", "woff2", "sqlite", "db",
+];
+
+/// Default for `GROK_SEARCH_MAX_FILE_BYTES`: larger files are never opened by `fs.search`
+const DEFAULT_SEARCH_MAX_FILE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Default for `GROK_SEARCH_MAX_LINE_BYTES`: longer matched lines are cut around the match
+const DEFAULT_SEARCH_MAX_LINE_BYTES: u64 = 1000;
+
+/// Contents `fs.read` returns unless `max_bytes` says otherwise
+const DEFAULT_READ_MAX_BYTES: usize = 1024 * 1024;
+
+/// Room left for the other `fs.read` result fields when contents are sized to the output cap
+const READ_RESULT_OVERHEAD_BYTES: usize = 1024;
+
+/// Default for `GROK_MAX_WRITE_BYTES`: bigger `fs.write` calls are treated as runaway output
+const DEFAULT_MAX_WRITE_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Number of leading bytes inspected when sniffing for binary content
+const BINARY_SNIFF_BYTES: usize = 8192;
+
+/// Default skip list merged with `extra` extensions, given with or without the leading dot
+pub(crate) fn search_skip_extensions<'a>(extra: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut exts: Vec<String> = DEFAULT_SEARCH_SKIP_EXTS.iter().map(|e| e.to_string()).collect();
+    exts.extend(
+        extra
+            .into_iter()
+            .map(|e| e.trim().trim_start_matches('.').to_lowercase())
+            .filter(|e| !e.is_empty()),
+    );
+    exts
+}
+
+/// Size cap for `fs.write`: the argument, then GROK_MAX_WRITE_BYTES, then the default
+fn max_write_bytes(arg: Option<u64>) -> u64 {
+    arg.or_else(|| {
+        std::env::var("GROK_MAX_WRITE_BYTES")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+    })
+    .unwrap_or(DEFAULT_MAX_WRITE_BYTES)
+}
+
+/// Size cap for `fs.search`: the argument, then GROK_SEARCH_MAX_FILE_BYTES, then the default
+fn search_max_file_bytes(arg: Option<u64>) -> u64 {
+    arg.or_else(|| {
+        std::env::var("GROK_SEARCH_MAX_FILE_BYTES")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+    })
+    .unwrap_or(DEFAULT_SEARCH_MAX_FILE_BYTES)
+}
+
+/// Matched-line cap for `fs.search`: the argument, then GROK_SEARCH_MAX_LINE_BYTES, then the default
+fn search_max_line_bytes(arg: Option<u64>) -> u64 {
+    arg.or_else(|| {
+        std::env::var("GROK_SEARCH_MAX_LINE_BYTES")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+    })
+    .unwrap_or(DEFAULT_SEARCH_MAX_LINE_BYTES)
+    .max(1)
+}
+
+/// Count lines and bytes in chunks so huge files never sit in memory
+async fn summarize_file(path: &Path) -> std::io::Result<FsReadSummary> {
+    use tokio::io::AsyncReadExt;
+
+    let mut file = tokio::fs::File::open(path).await?;
+    let mut buf = vec![0u8; 64 * 1024];
+    let mut byte_size = 0u64;
+    let mut newlines = 0u64;
+    let mut is_binary = false;
+    let mut last_byte = None;
+
+    loop {
+        let n = file.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        let chunk = &buf[..n];
+        if byte_size < BINARY_SNIFF_BYTES as u64 && looks_binary(chunk) {
+            is_binary = true;
+        }
+        newlines += chunk.iter().filter(|b| **b == b'\n').count() as u64;
+        byte_size += n as u64;
+        last_byte = chunk.last().copied();
+    }
+
+    // A trailing line without a newline still counts
+    let line_count = match last_byte {
+        Some(b'\n') | None => newlines,
+        Some(_) => newlines + 1,
+    };
+
+    Ok(FsReadSummary { line_count, byte_size, is_binary })
+}
+
+/// Line counts and changes between a replaced file and the text written over it
+fn overwrite_stats(previous: &str, contents: &str) -> FsOverwriteStats {
+    let (added, removed) = diff::line_changes(previous, contents);
+    FsOverwriteStats {
+        previous_lines: previous.lines().count() as u64,
+        new_lines: contents.lines().count() as u64,
+        lines_added: added as u64,
+        lines_removed: removed as u64,
+    }
+}
 
-use simple_edit::{SimpleEditPlanner, normalize_newlines};
+/// Run a regex over the whole file and report every line each match touches,
+/// stopping once `limit` lines are found (the unit the pager counts in).
+fn multiline_matches(re: &regex::Regex, content: &str, limit: usize) -> Vec<SearchLine> {
+    let lines: Vec<&str> = content.lines().collect();
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(content.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    let line_of = |offset: usize| line_starts.partition_point(|&start| start <= offset).saturating_sub(1);
+
+    let mut out: Vec<SearchLine> = Vec::new();
+    for m in re.find_iter(content) {
+        let first = line_of(m.start());
+        // A match ending right after a newline doesn't touch the next line
+        let last = line_of(m.end().saturating_sub(1).max(m.start()));
+        for idx in first..=last {
+            let ln = (idx + 1) as u64;
+            if out.last().is_some_and(|l| l.ln >= ln) {
+                continue;
+            }
+            if let Some(text) = lines.get(idx) {
+                out.push(SearchLine { ln, text: text.to_string(), columns: None });
+            }
+            if out.len() >= limit {
+                return out;
+            }
+        }
+    }
+    out
+}
+
+/// Scan a file line by line, stopping once `limit` lines match so the rest is never read.
+/// Lines that aren't valid UTF-8 are skipped; `None` means the file is unreadable or binary.
+fn streamed_line_matches(
+    path: &Path,
+    limit: usize,
+    line_matches: impl Fn(&str) -> bool,
+) -> Option<Vec<SearchLine>> {
+    use std::io::{BufRead, BufReader, ErrorKind};
+
+    let file = std::fs::File::open(path).ok()?;
+    let mut reader = BufReader::with_capacity(64 * 1024, file);
+    if looks_binary(reader.fill_buf().ok()?) {
+        return None;
+    }
+
+    let mut out = Vec::new();
+    for (line_num, line) in reader.lines().enumerate() {
+        let line = match line {
+            Ok(line) => line,
+            // The reader has already moved past the bad line, so carry on with the next
+            Err(e) if e.kind() == ErrorKind::InvalidData => continue,
+            Err(_) => break,
+        };
+        if line_matches(&line) {
+            out.push(SearchLine { ln: (line_num + 1) as u64, text: line, columns: None });
+            if out.len() >= limit {
+                break;
+            }
+        }
+    }
+    Some(out)
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Literal substring match that requires non-word characters (or line edges) around the hit
+fn contains_whole_word(line: &str, query: &str, case_insensitive: bool) -> bool {
+    if query.is_empty() {
+        return false;
+    }
+    let (haystack, needle) = if case_insensitive {
+        (line.to_lowercase(), query.to_lowercase())
+    } else {
+        (line.to_string(), query.to_string())
+    };
+
+    haystack.match_indices(&needle).any(|(start, m)| {
+        let before = haystack[..start].chars().next_back();
+        let after = haystack[start + m.len()..].chars().next();
+        !before.is_some_and(is_word_char) && !after.is_some_and(is_word_char)
+    })
+}
+
+/// Spans of every non-empty `re` match in `line`; with `whole_word`, only those with
+/// non-word characters (or line edges) on both sides
+fn match_spans(re: &regex::Regex, line: &str, whole_word: bool) -> Vec<MatchSpan> {
+    re.find_iter(line)
+        .filter(|m| !m.is_empty())
+        .filter(|m| {
+            !whole_word || {
+                let before = line[..m.start()].chars().next_back();
+                let after = line[m.end()..].chars().next();
+                !before.is_some_and(is_word_char) && !after.is_some_and(is_word_char)
+            }
+        })
+        .map(|m| MatchSpan { start: m.start(), end: m.end() })
+        .collect()
+}
+
+/// Cut `text` to `max_bytes` centered on `focus` (the first match), marking each cut end
+/// with `…`. Lines within the limit are returned unchanged.
+fn window_around(text: &str, focus: Option<MatchSpan>, max_bytes: usize) -> String {
+    if text.len() <= max_bytes {
+        return text.to_string();
+    }
+    let center = focus.map_or(0, |m| m.start + (m.end - m.start) / 2);
+    let start = floor_char_boundary(text, center.saturating_sub(max_bytes / 2).min(text.len() - max_bytes));
+    let end = floor_char_boundary(text, start + max_bytes);
+    format!(
+        "{}{}{}",
+        if start > 0 { "…" } else { "" },
+        &text[start..end],
+        if end < text.len() { "…" } else { "" }
+    )
+}
+
+/// The largest char boundary of `text` at or before `index`
+pub fn floor_char_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+/// The longest prefix of `text`, in bytes, whose JSON-escaped form fits in `room`
+fn escaped_prefix_len(text: &str, room: usize) -> usize {
+    let mut escaped = 0;
+    for (index, ch) in text.char_indices() {
+        escaped += match ch {
+            '"' | '\\' | '\n' | '\r' | '\t' | '\u{08}' | '\u{0c}' => 2,
+            c if (c as u32) < 0x20 => 6,
+            c => c.len_utf8(),
+        };
+        if escaped > room {
+            return index;
+        }
+    }
+    text.len()
+}
+
+/// Treat content with a NUL byte near the start as binary
+fn looks_binary(bytes: &[u8]) -> bool {
+    bytes.iter().take(BINARY_SNIFF_BYTES).any(|b| *b == 0)
+}
 
 /// File system operations executor
 pub struct FsExecutor {
     event_sender: EventSender,
     max_output_size: usize,
+    /// Relative paths resolve against this instead of the process working directory
+    root: Option<PathBuf>,
+    /// Hold `fs.apply_patch` until the user approves its ops
+    patch_approval: bool,
+    /// Extra `fs.search` skip extensions; `None` reads GROK_SEARCH_SKIP_EXTS
+    search_skip_exts: Option<Vec<String>>,
+    /// Gitignore-style patterns, relative to the root, that `fs.search` and `fs.find` never walk into
+    excludes: Vec<String>,
 }
 
 impl FsExecutor {
@@ -22,9 +304,44 @@ impl FsExecutor {
         Self {
             event_sender,
             max_output_size,
+            root: None,
+            patch_approval: false,
+            search_skip_exts: None,
+            excludes: Vec::new(),
         }
     }
 
+    /// Send an `ApprovalRequest` before each `fs.apply_patch` and apply only the approved ops.
+    /// Dry runs are never held.
+    pub fn with_patch_approval(mut self, patch_approval: bool) -> Self {
+        self.patch_approval = patch_approval;
+        self
+    }
+
+    /// Resolve relative paths against `root` (the session's working directory)
+    pub fn with_root(mut self, root: Option<PathBuf>) -> Self {
+        self.root = super::root::normalize(root);
+        self
+    }
+
+    /// Extensions `fs.search` skips on top of the built-in binary list, in place of
+    /// GROK_SEARCH_SKIP_EXTS
+    pub fn with_search_skip_exts(mut self, exts: Vec<String>) -> Self {
+        self.search_skip_exts = Some(exts);
+        self
+    }
+
+    /// Leave paths matching these gitignore-style patterns (the project config's
+    /// `excludes`) out of `fs.search` and `fs.find`
+    pub fn with_excludes(mut self, excludes: Vec<String>) -> Self {
+        self.excludes = excludes;
+        self
+    }
+
+    fn resolve(&self, path: &str) -> String {
+        super::root::resolve(self.root.as_deref(), path)
+    }
+
     /// Truncate a JSON value if it exceeds the maximum output size
     fn truncate_result(&self, result: Value) -> Value {
         let json_str = serde_json::to_string(&result).unwrap_or_else(|_| "{}".to_string());
@@ -49,8 +366,9 @@ impl FsExecutor {
     }
 
     pub async fn execute_read_with_result(&self, id: String, args: Value) -> Result<Value, String> {
-        let args: FsReadArgs = serde_json::from_value(args)
+        let mut args: FsReadArgs = serde_json::from_value(args)
             .map_err(|e| format!("Invalid FsRead arguments: {}", e))?;
+        args.path = self.resolve(&args.path);
 
         // Send progress event
         self.event_sender.send(AppEvent::ToolProgress {
@@ -69,6 +387,26 @@ impl FsExecutor {
             return Err(format!("Path is not a file: {}", args.path));
         }
 
+        if args.summary_only.unwrap_or(false) {
+            let summary = summarize_file(path).await
+                .map_err(|e| format!("Failed to read file {}: {}", args.path, e))?;
+            let result = FsReadResult {
+                contents: String::new(),
+                encoding: args.encoding.unwrap_or_else(|| "utf-8".to_string()),
+                truncated: false,
+                is_empty: summary.byte_size == 0,
+                whitespace_only: false,
+                summary: Some(summary),
+                adjusted_range: None,
+            };
+            let result_value = serde_json::to_value(result).unwrap();
+            self.event_sender.send(AppEvent::ToolResult {
+                id,
+                payload: result_value.clone(),
+            }).ok();
+            return Ok(result_value);
+        }
+
         // Read file contents
         let contents = tokio::fs::read(&args.path).await
             .map_err(|e| format!("Failed to read file {}: {}", args.path, e))?;
@@ -79,22 +417,51 @@ impl FsExecutor {
             "utf-8" => String::from_utf8_lossy(&contents).to_string(),
             _ => return Err(format!("Unsupported encoding: {}", encoding)),
         };
+        // Describes the whole file, even when only a range is returned
+        let is_empty = text_contents.is_empty();
+        let whitespace_only = !is_empty && text_contents.trim().is_empty();
 
-        // Handle range if specified
+        // Handle range if specified. Cuts inside a multi-byte character move back to its start.
+        let mut adjusted_range = None;
         let (final_contents, truncated) = if let Some(range) = args.range {
             let start = range.start as usize;
             let end = range.end as usize;
             if start < text_contents.len() {
                 let end_clamped = end.min(text_contents.len());
-                (text_contents[start..end_clamped].to_string(), end < text_contents.len())
+                let safe_start = floor_char_boundary(&text_contents, start);
+                let safe_end = floor_char_boundary(&text_contents, end_clamped).max(safe_start);
+                if (safe_start, safe_end) != (start, end_clamped) {
+                    adjusted_range = Some(safe_start as u64..safe_end as u64);
+                }
+                (text_contents[safe_start..safe_end].to_string(), end < text_contents.len())
             } else {
                 (String::new(), false)
             }
         } else {
-            // Check if we should truncate very large files (>1MB)
-            const MAX_SIZE: usize = 1024 * 1024;
-            if text_contents.len() > MAX_SIZE {
-                (text_contents[..MAX_SIZE].to_string(), true)
+            // Large files are cut at 1 MiB unless `max_bytes` asks otherwise; an override
+            // (including 0, no limit) stays within what the output cap lets through once
+            // the contents are escaped into the JSON result
+            let output_room = self.max_output_size.saturating_sub(READ_RESULT_OVERHEAD_BYTES);
+            let (max_size, cut) = match args.max_bytes {
+                None => (
+                    DEFAULT_READ_MAX_BYTES,
+                    floor_char_boundary(&text_contents, DEFAULT_READ_MAX_BYTES),
+                ),
+                Some(n) => {
+                    let max_size = match n {
+                        0 => output_room,
+                        n => usize::try_from(n).unwrap_or(usize::MAX).min(output_room),
+                    };
+                    let cut = floor_char_boundary(&text_contents, max_size)
+                        .min(escaped_prefix_len(&text_contents, output_room));
+                    (max_size, cut)
+                }
+            };
+            if cut < text_contents.len() {
+                if cut != max_size {
+                    adjusted_range = Some(0..cut as u64);
+                }
+                (text_contents[..cut].to_string(), true)
             } else {
                 (text_contents, false)
             }
@@ -104,6 +471,10 @@ impl FsExecutor {
             contents: final_contents,
             encoding: encoding.to_string(),
             truncated,
+            is_empty,
+            whitespace_only,
+            summary: None,
+            adjusted_range,
         };
 
         let result_value = serde_json::to_value(result).unwrap();
@@ -143,9 +514,31 @@ impl FsExecutor {
             None
         };
 
+        // Literal queries are located with an escaped regex so offsets match the original line
+        let span_regex = match &regex {
+            Some(re) => re.clone(),
+            None => regex::RegexBuilder::new(&regex::escape(&args.query))
+                .case_insensitive(args.case_insensitive)
+                .build()
+                .map_err(|e| format!("Invalid query: {}", e))?,
+        };
+        let include_columns = args.include_columns.unwrap_or(false);
+        let max_line_bytes = search_max_line_bytes(args.max_line_bytes) as usize;
+
         let mut matches = Vec::new();
         let max_results = args.max_results.unwrap_or(100) as usize;
-        let mut total_matches = 0;
+        let skip_exts = match &self.search_skip_exts {
+            Some(extra) => search_skip_extensions(extra.iter().map(String::as_str)),
+            None => search_skip_extensions(std::env::var("GROK_SEARCH_SKIP_EXTS").unwrap_or_default().split(',')),
+        };
+        let whole_word = args.whole_word.unwrap_or(false);
+        let max_file_bytes = search_max_file_bytes(args.max_file_bytes);
+        let mut skipped_large_files = 0u64;
+        let rank = args.rank.unwrap_or(false);
+        let query_fingerprint = fingerprint(&(
+            &args.query, &args.globs, args.regex, args.case_insensitive, args.multiline, whole_word, max_file_bytes, rank,
+        ));
+        let mut pager = Pager::resume(args.continue_from.as_deref(), query_fingerprint, max_results)?;
 
         // Note: we used to determine search_paths here, but now handle globs directly in the loop below
 
@@ -162,7 +555,9 @@ impl FsExecutor {
                         builder.add(Glob::new("**/*").map_err(|e| format!("Invalid glob pattern {}: {}", g, e))?);
                         continue;
                     }
-                    let pattern = if g.contains('/') { g.clone() } else { format!("**/{}", g) };
+                    // Matched against root-relative paths, which never start with `./`
+                    let g = root::strip_dot_prefix(g);
+                    let pattern = if g.contains('/') { g.to_string() } else { format!("**/{}", g) };
                     let glob = Glob::new(&pattern)
                         .map_err(|e| format!("Invalid glob pattern {}: {}", g, e))?;
                     builder.add(glob);
@@ -171,9 +566,20 @@ impl FsExecutor {
             }
         } else { None };
 
-        // Walk through files
-        for entry in WalkDir::new(".").max_depth(10) {
-            if total_matches >= max_results {
+        // Ranking orders whole files, so it gathers every match before the pager picks a page
+        let mut ranked: Vec<SearchMatch> = Vec::new();
+
+        // Walk through files in sorted order so continuation offsets stay stable
+        let walk_root = PathBuf::from(self.resolve("."));
+        let excludes = IgnorePatterns::new(&self.excludes)?;
+        let walker = WalkDir::new(&walk_root).max_depth(10).sort_by_file_name().into_iter()
+            .filter_entry(|entry| {
+                let relative = entry.path().strip_prefix(&walk_root).unwrap_or(entry.path());
+                entry.depth() == 0
+                    || !(trash::is_trash_dir(entry.path()) || excludes.is_ignored(relative, entry.file_type().is_dir()))
+            });
+        for entry in walker {
+            if pager.is_full() {
                 break;
             }
 
@@ -184,59 +590,102 @@ impl FsExecutor {
             }
 
             let path = entry.path();
-            let path_str = path.to_string_lossy();
+            // Globs and reported paths are relative to the root, as `src/...`
+            let path_str = root::as_shown(path, &walk_root, ".");
 
             // Check if path matches any glob pattern
             if let Some(ref gs) = compiled_globs {
-                if !gs.is_match(path) {
+                if !gs.is_match(Path::new(&path_str)) {
                     continue;
                 }
             }
 
-            // Skip binary files (basic heuristic)
+            // Skip binary files by extension first, then by content
             if let Some(ext) = path.extension() {
                 let ext_str = ext.to_string_lossy().to_lowercase();
-                if matches!(ext_str.as_str(), "exe" | "dll" | "so" | "dylib" | "bin" | "png" | "jpg" | "jpeg" | "gif" | "pdf") {
+                if skip_exts.contains(&ext_str) {
                     continue;
                 }
             }
 
-            // Read and search file
-            if let Ok(content) = std::fs::read_to_string(path) {
-                let mut file_matches = Vec::new();
+            // Check the size before reading so huge files never get loaded
+            if entry.metadata().is_ok_and(|meta| meta.len() > max_file_bytes) {
+                skipped_large_files += 1;
+                continue;
+            }
 
-                for (line_num, line) in content.lines().enumerate() {
-                    let line_matches = if let Some(ref re) = regex {
+            let budget = if rank { usize::MAX } else { pager.budget() };
+            let file_matches = if let (Some(re), true) = (regex.as_ref(), args.multiline) {
+                // Patterns may span lines, so match against the whole file
+                let Ok(bytes) = std::fs::read(path) else { continue };
+                if looks_binary(&bytes) {
+                    continue;
+                }
+                let Ok(content) = String::from_utf8(bytes) else { continue };
+                multiline_matches(re, &content, budget)
+            } else {
+                let line_matches = |line: &str| {
+                    if let Some(ref re) = regex {
                         re.is_match(line)
+                    } else if whole_word {
+                        contains_whole_word(line, &args.query, args.case_insensitive)
                     } else if args.case_insensitive {
                         line.to_lowercase().contains(&args.query.to_lowercase())
                     } else {
                         line.contains(&args.query)
-                    };
-
-                    if line_matches {
-                        file_matches.push(SearchLine {
-                            ln: (line_num + 1) as u64,
-                            text: line.to_string(),
-                        });
-                        total_matches += 1;
-
-                        if total_matches >= max_results {
-                            break;
-                        }
                     }
-                }
+                };
+                let Some(file_matches) = streamed_line_matches(path, budget, line_matches) else { continue };
+                file_matches
+            };
 
+            if rank {
                 if !file_matches.is_empty() {
-                    matches.push(SearchMatch {
-                        path: path_str.to_string(),
-                        lines: file_matches,
-                    });
+                    ranked.push(SearchMatch { path: path_str, lines: file_matches });
+                }
+                continue;
+            }
+
+            let file_matches = pager.page(file_matches);
+            if !file_matches.is_empty() {
+                matches.push(SearchMatch {
+                    path: path_str,
+                    lines: file_matches,
+                });
+            }
+        }
+
+        if rank {
+            rank::rank_matches(&mut ranked, &args.query);
+            for candidate in ranked {
+                if pager.is_full() {
+                    break;
+                }
+                let lines = pager.page(candidate.lines);
+                if !lines.is_empty() {
+                    matches.push(SearchMatch { path: candidate.path, lines });
                 }
             }
         }
 
-        let result = FsSearchResult { matches };
+        // Only lines that made the page get cut down and have their columns found
+        let literal_whole_word = whole_word && !args.regex;
+        for line in matches.iter_mut().flat_map(|m| m.lines.iter_mut()) {
+            if line.text.len() > max_line_bytes {
+                let first = match_spans(&span_regex, &line.text, literal_whole_word).first().copied();
+                line.text = window_around(&line.text, first, max_line_bytes);
+            }
+            if include_columns {
+                // Lines inside a multiline match may hold no match of their own
+                line.columns = Some(match_spans(&span_regex, &line.text, literal_whole_word));
+            }
+        }
+
+        let result = FsSearchResult {
+            matches,
+            continuation: pager.continuation(query_fingerprint),
+            skipped_large_files,
+        };
 
         let result_value = serde_json::to_value(result).unwrap();
         let truncated_result = self.truncate_result(result_value.clone());
@@ -256,8 +705,9 @@ impl FsExecutor {
     }
 
     pub async fn execute_write_with_result(&self, id: String, args: Value) -> Result<Value, String> {
-        let args: FsWriteArgs = serde_json::from_value(args)
+        let mut args: FsWriteArgs = serde_json::from_value(args)
             .map_err(|e| format!("Invalid FsWrite arguments: {}", e))?;
+        args.path = self.resolve(&args.path);
 
         // Send progress event
         self.event_sender.send(AppEvent::ToolProgress {
@@ -265,6 +715,14 @@ impl FsExecutor {
             message: format!("Writing to file: {}", args.path),
         }).ok();
 
+        let limit = max_write_bytes(args.max_write_bytes);
+        if args.contents.len() as u64 > limit {
+            return Err(format!(
+                "Refusing to write {} bytes to {}: over the {}-byte write limit. If this is intended, retry with a larger max_write_bytes",
+                args.contents.len(), args.path, limit
+            ));
+        }
+
         let path = Path::new(&args.path);
 
         // Check if file exists and handle overwrite policy
@@ -272,6 +730,13 @@ impl FsExecutor {
             return Err(format!("File already exists and overwrite is false: {}", args.path));
         }
 
+        // Keep what's being replaced so the result can say how much changed
+        let previous = if path.is_file() {
+            tokio::fs::read_to_string(path).await.ok()
+        } else {
+            None
+        };
+
         // Create parent directories if needed
         if args.create_if_missing {
             if let Some(parent) = path.parent() {
@@ -286,6 +751,9 @@ impl FsExecutor {
 
         let result = FsWriteResult {
             bytes_written: args.contents.len() as u64,
+            overwrite: previous
+                .filter(|previous| *previous != args.contents)
+                .map(|previous| overwrite_stats(&previous, &args.contents)),
         };
 
         let result_value = serde_json::to_value(result).unwrap();
@@ -306,15 +774,46 @@ impl FsExecutor {
     }
 
     pub async fn execute_apply_patch_with_result(&self, id: String, args: Value) -> Result<Value, String> {
-        let spec: FsApplyPatchArgs = serde_json::from_value(args)
+        let mut spec: FsApplyPatchArgs = serde_json::from_value(args)
             .map_err(|e| format!("Invalid FsApplyPatch arguments: {}", e))?;
+        for op in &mut spec.ops {
+            self.resolve_op(op);
+        }
 
         self.event_sender.send(AppEvent::ToolProgress {
             id: id.clone(),
             message: format!("Planning {} edit operation(s)...", spec.ops.len()),
         }).ok();
 
-        let summary = self.apply_simple_edit_spec(&spec).await;
+        // Ops that don't plan skip the prompt and fail below as usual
+        let mut rejected = Vec::new();
+        if self.patch_approval && !spec.dry_run {
+            if let Ok(approved) = self.request_patch_approval(&id, &spec.ops).await {
+                let (kept, dropped): (Vec<_>, Vec<_>) = std::mem::take(&mut spec.ops)
+                    .into_iter()
+                    .zip(approved.into_iter().chain(std::iter::repeat(false)))
+                    .partition(|(_, approved)| *approved);
+                spec.ops = kept.into_iter().map(|(op, _)| op).collect();
+                rejected = dropped.into_iter().map(|(op, _)| op.describe()).collect();
+            }
+        }
+
+        if spec.ops.is_empty() && !rejected.is_empty() {
+            let result = FsApplyPatchResult {
+                success: false,
+                summary: format!("The user rejected all {} edit operation(s); nothing was written.", rejected.len()),
+                rejected_hunks: Some(rejected),
+                diff: None,
+            };
+            let result_value = serde_json::to_value(result).unwrap();
+            self.event_sender.send(AppEvent::ToolResult {
+                id,
+                payload: result_value.clone(),
+            }).ok();
+            return Ok(result_value);
+        }
+
+        let summary = self.apply_simple_edit_spec(&id, &spec).await;
 
         self.event_sender.send(AppEvent::ToolProgress {
             id: id.clone(),
@@ -326,15 +825,25 @@ impl FsExecutor {
         }).ok();
 
         let result = match summary {
-            Ok(summary_text) => FsApplyPatchResult {
-                success: true,
-                rejected_hunks: None,
-                summary: summary_text,
-            },
+            Ok((mut summary_text, diff)) => {
+                if !rejected.is_empty() {
+                    summary_text.push_str("\nRejected by the user:");
+                    for desc in &rejected {
+                        summary_text.push_str(&format!("\n  - {}", desc));
+                    }
+                }
+                FsApplyPatchResult {
+                    success: true,
+                    rejected_hunks: (!rejected.is_empty()).then_some(rejected),
+                    summary: summary_text,
+                    diff: (!diff.is_empty()).then_some(diff),
+                }
+            }
             Err(e) => FsApplyPatchResult {
                 success: false,
                 rejected_hunks: Some(vec![e.clone()]),
                 summary: format!("Failed to apply edits: {}", e),
+                diff: None,
             },
         };
 
@@ -349,12 +858,83 @@ impl FsExecutor {
         Ok(truncated_result)
     }
 
-    async fn apply_simple_edit_spec(&self, spec: &FsApplyPatchArgs) -> Result<String, String> {
-        let mut planner = SimpleEditPlanner::new(spec.dry_run);
+    /// Plan (and unless dry-running, commit) the edits; returns the summary and a unified diff
+    async fn apply_simple_edit_spec(&self, id: &str, spec: &FsApplyPatchArgs) -> Result<(String, String), String> {
+        let mut planner = SimpleEditPlanner::new(spec.dry_run)
+            .with_trash(self.trash())
+            .with_progress(self.event_sender.clone(), id.to_string());
         for op in &spec.ops {
             planner.apply_op(op).await?;
         }
-        planner.finish().await
+        let diff = planner.diff();
+        planner.finish().await.map(|(summary, _)| (summary, diff))
+    }
+
+    /// Preview each op's diff, ask the user which to apply and wait for the answer.
+    /// Returns one flag per op; a request nobody answers approves nothing.
+    async fn request_patch_approval(&self, id: &str, ops: &[SimpleEditOp]) -> Result<Vec<bool>, String> {
+        let mut planner = SimpleEditPlanner::new(true);
+        let mut previews = Vec::with_capacity(ops.len());
+        for op in ops {
+            let diff = planner.apply_op_with_diff(op).await?;
+            previews.push(PatchOpPreview { description: op.describe(), diff });
+        }
+
+        let (request, reply) = PatchApprovalRequest::new(id.to_string(), previews);
+        if self.event_sender.send(AppEvent::ApprovalRequest(request)).is_err() {
+            return Ok(vec![false; ops.len()]);
+        }
+        Ok(reply.await.unwrap_or_else(|_| vec![false; ops.len()]))
+    }
+
+    /// Point an edit operation's paths at the workspace root
+    fn resolve_op(&self, op: &mut SimpleEditOp) {
+        match op {
+            SimpleEditOp::SetFile { path, .. }
+            | SimpleEditOp::ReplaceOnce { path, .. }
+            | SimpleEditOp::InsertBefore { path, .. }
+            | SimpleEditOp::InsertAfter { path, .. }
+            | SimpleEditOp::DeleteFile { path }
+            | SimpleEditOp::CreateEmpty { path }
+            | SimpleEditOp::CreateDir { path } => *path = self.resolve(path),
+            SimpleEditOp::RenameFile { path, to } => {
+                *path = self.resolve(path);
+                *to = self.resolve(to);
+            }
+        }
+    }
+
+    /// Run a single granular edit through the same planner as `fs.apply_patch`, so both
+    /// paths share newline normalization and exactly-once anchor checks.
+    /// Returns where any deleted file was moved in the trash.
+    async fn apply_single_op(&self, op: SimpleEditOp) -> Result<Vec<PathBuf>, String> {
+        let mut planner = SimpleEditPlanner::new(false).with_trash(self.trash());
+        planner.apply_op(&op).await?;
+        planner.finish().await.map(|(_, trashed)| trashed)
+    }
+
+    /// Trash for deletions under this executor's root
+    fn trash(&self) -> Option<Trash> {
+        Trash::for_root(self.root.as_deref())
+    }
+
+    fn send_simple_op_result(&self, id: String) -> Value {
+        self.send_simple_op_result_with_trash(id, None)
+    }
+
+    fn send_simple_op_result_with_trash(&self, id: String, trash_path: Option<PathBuf>) -> Value {
+        let result = FsSimpleOpResult {
+            success: true,
+            trash_path: trash_path.map(|p| p.to_string_lossy().into_owned()),
+        };
+        let result_value = serde_json::to_value(result).unwrap();
+
+        self.event_sender.send(AppEvent::ToolResult {
+            id,
+            payload: result_value.clone(),
+        }).ok();
+
+        result_value
     }
 
     pub async fn execute_set_file(&self, id: String, args: Value) -> Result<(), String> {
@@ -363,31 +943,28 @@ impl FsExecutor {
     }
 
     pub async fn execute_set_file_with_result(&self, id: String, args: Value) -> Result<Value, String> {
-        let args: FsSetFileArgs = serde_json::from_value(args)
+        let mut args: FsSetFileArgs = serde_json::from_value(args)
             .map_err(|e| format!("Invalid FsSetFile arguments: {}", e))?;
+        args.path = self.resolve(&args.path);
 
         self.event_sender.send(AppEvent::ToolProgress {
             id: id.clone(),
             message: format!("Setting file contents: {}", args.path),
         }).ok();
 
-        let path = Path::new(&args.path);
-
-        // Create parent directories if needed
-        if args.create_if_missing {
-            if let Some(parent) = path.parent() {
-                tokio::fs::create_dir_all(parent).await
-                    .map_err(|e| format!("Failed to create parent directories for {}: {}", args.path, e))?;
+        // The planner creates parent directories on commit, so honor the opt-out up front
+        if !args.create_if_missing {
+            if let Some(parent) = Path::new(&args.path).parent() {
+                if !parent.as_os_str().is_empty() && !parent.exists() {
+                    return Err(format!("Parent directory does not exist for {}", args.path));
+                }
             }
         }
 
-        // Write the file
-        tokio::fs::write(&args.path, &args.contents).await
-            .map_err(|e| format!("Failed to write file {}: {}", args.path, e))?;
+        let bytes_written = args.contents.len() as u64;
+        self.apply_single_op(SimpleEditOp::SetFile { path: args.path, contents: args.contents }).await?;
 
-        let result = FsWriteResult {
-            bytes_written: args.contents.len() as u64,
-        };
+        let result = FsWriteResult { bytes_written, overwrite: None };
 
         let result_value = serde_json::to_value(result).unwrap();
         let truncated_result = self.truncate_result(result_value.clone());
@@ -406,39 +983,21 @@ impl FsExecutor {
     }
 
     pub async fn execute_replace_once_with_result(&self, id: String, args: Value) -> Result<Value, String> {
-        let args: FsReplaceOnceArgs = serde_json::from_value(args)
+        let mut args: FsReplaceOnceArgs = serde_json::from_value(args)
             .map_err(|e| format!("Invalid FsReplaceOnce arguments: {}", e))?;
+        args.path = self.resolve(&args.path);
 
         self.event_sender.send(AppEvent::ToolProgress {
             id: id.clone(),
             message: format!("Replacing text in file: {}", args.path),
         }).ok();
 
-        let content = tokio::fs::read_to_string(&args.path).await
-            .map_err(|e| format!("Failed to read file {}: {}", args.path, e))?;
-
-        let normalized_find = normalize_newlines(&args.find);
-        let normalized_replace = normalize_newlines(&args.replace);
-
-        if let Some(idx) = content.find(&normalized_find) {
-            let mut new_content = content.clone();
-            new_content.replace_range(idx..idx + normalized_find.len(), &normalized_replace);
-
-            tokio::fs::write(&args.path, &new_content).await
-                .map_err(|e| format!("Failed to write file {}: {}", args.path, e))?;
+        let path = args.path.clone();
+        self.apply_single_op(SimpleEditOp::ReplaceOnce { path: args.path, find: args.find, replace: args.replace })
+            .await
+            .map_err(|e| format!("Failed to replace text in {}: {}", path, e))?;
 
-            let result = FsSimpleOpResult { success: true };
-            let result_value = serde_json::to_value(result).unwrap();
-
-            self.event_sender.send(AppEvent::ToolResult {
-                id,
-                payload: result_value.clone(),
-            }).ok();
-
-            Ok(result_value)
-        } else {
-            Err(format!("Text '{}' not found in file {}", args.find, args.path))
-        }
+        Ok(self.send_simple_op_result(id))
     }
 
     pub async fn execute_insert_before(&self, id: String, args: Value) -> Result<(), String> {
@@ -447,39 +1006,21 @@ impl FsExecutor {
     }
 
     pub async fn execute_insert_before_with_result(&self, id: String, args: Value) -> Result<Value, String> {
-        let args: FsInsertBeforeArgs = serde_json::from_value(args)
+        let mut args: FsInsertBeforeArgs = serde_json::from_value(args)
             .map_err(|e| format!("Invalid FsInsertBefore arguments: {}", e))?;
+        args.path = self.resolve(&args.path);
 
         self.event_sender.send(AppEvent::ToolProgress {
             id: id.clone(),
             message: format!("Inserting text before anchor in file: {}", args.path),
         }).ok();
 
-        let content = tokio::fs::read_to_string(&args.path).await
-            .map_err(|e| format!("Failed to read file {}: {}", args.path, e))?;
-
-        let normalized_anchor = normalize_newlines(&args.anchor);
-        let normalized_insert = normalize_newlines(&args.insert);
-
-        if let Some(idx) = content.find(&normalized_anchor) {
-            let mut new_content = content.clone();
-            new_content.insert_str(idx, &normalized_insert);
-
-            tokio::fs::write(&args.path, &new_content).await
-                .map_err(|e| format!("Failed to write file {}: {}", args.path, e))?;
-
-            let result = FsSimpleOpResult { success: true };
-            let result_value = serde_json::to_value(result).unwrap();
-
-            self.event_sender.send(AppEvent::ToolResult {
-                id,
-                payload: result_value.clone(),
-            }).ok();
+        let path = args.path.clone();
+        self.apply_single_op(SimpleEditOp::InsertBefore { path: args.path, anchor: args.anchor, insert: args.insert })
+            .await
+            .map_err(|e| format!("Failed to insert text in {}: {}", path, e))?;
 
-            Ok(result_value)
-        } else {
-            Err(format!("Anchor text '{}' not found in file {}", args.anchor, args.path))
-        }
+        Ok(self.send_simple_op_result(id))
     }
 
     pub async fn execute_insert_after(&self, id: String, args: Value) -> Result<(), String> {
@@ -488,39 +1029,21 @@ impl FsExecutor {
     }
 
     pub async fn execute_insert_after_with_result(&self, id: String, args: Value) -> Result<Value, String> {
-        let args: FsInsertAfterArgs = serde_json::from_value(args)
+        let mut args: FsInsertAfterArgs = serde_json::from_value(args)
             .map_err(|e| format!("Invalid FsInsertAfter arguments: {}", e))?;
+        args.path = self.resolve(&args.path);
 
         self.event_sender.send(AppEvent::ToolProgress {
             id: id.clone(),
             message: format!("Inserting text after anchor in file: {}", args.path),
         }).ok();
 
-        let content = tokio::fs::read_to_string(&args.path).await
-            .map_err(|e| format!("Failed to read file {}: {}", args.path, e))?;
-
-        let normalized_anchor = normalize_newlines(&args.anchor);
-        let normalized_insert = normalize_newlines(&args.insert);
+        let path = args.path.clone();
+        self.apply_single_op(SimpleEditOp::InsertAfter { path: args.path, anchor: args.anchor, insert: args.insert })
+            .await
+            .map_err(|e| format!("Failed to insert text in {}: {}", path, e))?;
 
-        if let Some(idx) = content.find(&normalized_anchor) {
-            let mut new_content = content.clone();
-            new_content.insert_str(idx + normalized_anchor.len(), &normalized_insert);
-
-            tokio::fs::write(&args.path, &new_content).await
-                .map_err(|e| format!("Failed to write file {}: {}", args.path, e))?;
-
-            let result = FsSimpleOpResult { success: true };
-            let result_value = serde_json::to_value(result).unwrap();
-
-            self.event_sender.send(AppEvent::ToolResult {
-                id,
-                payload: result_value.clone(),
-            }).ok();
-
-            Ok(result_value)
-        } else {
-            Err(format!("Anchor text '{}' not found in file {}", args.anchor, args.path))
-        }
+        Ok(self.send_simple_op_result(id))
     }
 
     pub async fn execute_delete_file(&self, id: String, args: Value) -> Result<(), String> {
@@ -529,39 +1052,22 @@ impl FsExecutor {
     }
 
     pub async fn execute_delete_file_with_result(&self, id: String, args: Value) -> Result<Value, String> {
-        let args: FsDeleteFileArgs = serde_json::from_value(args)
+        let mut args: FsDeleteFileArgs = serde_json::from_value(args)
             .map_err(|e| format!("Invalid FsDeleteFile arguments: {}", e))?;
+        args.path = self.resolve(&args.path);
 
         self.event_sender.send(AppEvent::ToolProgress {
             id: id.clone(),
             message: format!("Deleting file: {}", args.path),
         }).ok();
 
-        match tokio::fs::remove_file(&args.path).await {
-            Ok(_) => {
-                let result = FsSimpleOpResult { success: true };
-                let result_value = serde_json::to_value(result).unwrap();
-
-                self.event_sender.send(AppEvent::ToolResult {
-                    id,
-                    payload: result_value.clone(),
-                }).ok();
-
-                Ok(result_value)
-            }
-            Err(e) if e.kind() == ErrorKind::NotFound => {
-                let result = FsSimpleOpResult { success: true }; // File doesn't exist, consider it "deleted"
-                let result_value = serde_json::to_value(result).unwrap();
-
-                self.event_sender.send(AppEvent::ToolResult {
-                    id,
-                    payload: result_value.clone(),
-                }).ok();
-
-                Ok(result_value)
-            }
-            Err(e) => Err(format!("Failed to delete file {}: {}", args.path, e)),
+        // A file that doesn't exist is already "deleted"
+        let mut trashed = Vec::new();
+        if Path::new(&args.path).exists() {
+            trashed = self.apply_single_op(SimpleEditOp::DeleteFile { path: args.path }).await?;
         }
+
+        Ok(self.send_simple_op_result_with_trash(id, trashed.pop()))
     }
 
     pub async fn execute_rename_file(&self, id: String, args: Value) -> Result<(), String> {
@@ -570,48 +1076,54 @@ impl FsExecutor {
     }
 
     pub async fn execute_rename_file_with_result(&self, id: String, args: Value) -> Result<Value, String> {
-        let args: FsRenameFileArgs = serde_json::from_value(args)
+        let mut args: FsRenameFileArgs = serde_json::from_value(args)
             .map_err(|e| format!("Invalid FsRenameFile arguments: {}", e))?;
-
-        if args.path == args.to {
-            return Err("Source and destination paths are the same".to_string());
-        }
+        args.path = self.resolve(&args.path);
+        args.to = self.resolve(&args.to);
 
         self.event_sender.send(AppEvent::ToolProgress {
             id: id.clone(),
             message: format!("Renaming file {} to {}", args.path, args.to),
         }).ok();
 
-        // Check if source exists
-        if !Path::new(&args.path).exists() {
-            return Err(format!("Source file not found: {}", args.path));
-        }
+        self.apply_single_op(SimpleEditOp::RenameFile { path: args.path, to: args.to }).await?;
 
-        // Check if destination already exists
-        if Path::new(&args.to).exists() {
-            return Err(format!("Destination already exists: {}", args.to));
-        }
+        Ok(self.send_simple_op_result(id))
+    }
 
-        // Create parent directories for destination if needed
-        if let Some(parent) = Path::new(&args.to).parent() {
-            if !parent.as_os_str().is_empty() {
-                tokio::fs::create_dir_all(parent).await
-                    .map_err(|e| format!("Failed to create parent directories for {}: {}", args.to, e))?;
-            }
-        }
+    pub async fn execute_tree(&self, id: String, args: Value) -> Result<(), String> {
+        let _result = self.execute_tree_with_result(id, args).await?;
+        Ok(())
+    }
+
+    pub async fn execute_tree_with_result(&self, id: String, args: Value) -> Result<Value, String> {
+        let args: FsTreeArgs = serde_json::from_value(args)
+            .map_err(|e| format!("Invalid FsTree arguments: {}", e))?;
+
+        let root = self.resolve(args.path.as_deref().unwrap_or("."));
+
+        // Send progress event
+        self.event_sender.send(AppEvent::ToolProgress {
+            id: id.clone(),
+            message: format!("Listing tree: {}", root),
+        }).ok();
 
-        tokio::fs::rename(&args.path, &args.to).await
-            .map_err(|e| format!("Failed to rename {} to {}: {}", args.path, args.to, e))?;
+        let result = tree::render_tree(
+            Path::new(&root),
+            args.max_depth.unwrap_or(tree::DEFAULT_MAX_DEPTH).max(1),
+            args.max_nodes.unwrap_or(tree::DEFAULT_MAX_NODES).max(1),
+        )?;
 
-        let result = FsSimpleOpResult { success: true };
         let result_value = serde_json::to_value(result).unwrap();
+        let truncated_result = self.truncate_result(result_value.clone());
 
+        // Send result event for UI
         self.event_sender.send(AppEvent::ToolResult {
             id,
-            payload: result_value.clone(),
+            payload: result_value,
         }).ok();
 
-        Ok(result_value)
+        Ok(truncated_result)
     }
 
     pub async fn execute_find(&self, id: String, args: Value) -> Result<(), String> {
@@ -633,21 +1145,69 @@ impl FsExecutor {
         
         let base_path = args.base_path.as_deref().unwrap_or(".");
         let max_results = args.max_results.unwrap_or(50) as usize;
-        let fuzzy = args.fuzzy.unwrap_or(true);
+        let regex = args.regex.unwrap_or(false);
+        if regex && args.fuzzy == Some(true) {
+            return Err("fuzzy and regex cannot both be set".to_string());
+        }
+        let fuzzy = args.fuzzy.unwrap_or(!regex);
         let case_sensitive = args.case_sensitive.unwrap_or(false);
         let file_type = args.file_type.as_deref().unwrap_or("both");
+        let name_regex = if regex {
+            Some(regex::RegexBuilder::new(&args.pattern)
+                .case_insensitive(!case_sensitive)
+                .build()
+                .map_err(|e| format!("Invalid regex pattern: {}", e))?)
+        } else {
+            None
+        };
+        // Globs match the real path, so case-insensitivity is left to globset rather than
+        // lowercasing the pattern. A pattern with a directory separator matches the full
+        // path; otherwise `**/` makes it match the file name. Invalid globs fall back to
+        // substring matching.
+        let name_glob = if fuzzy || regex {
+            None
+        } else {
+            let pattern = if args.pattern.contains('/') {
+                root::strip_dot_prefix(&args.pattern).to_string()
+            } else {
+                format!("**/{}", args.pattern)
+            };
+            GlobBuilder::new(&pattern)
+                .case_insensitive(!case_sensitive)
+                .build()
+                .ok()
+                .map(|glob| glob.compile_matcher())
+        };
 
         let mut matches = Vec::new();
-        let mut count = 0;
-
-        // Simple pattern matching implementation
-        for entry in WalkDir::new(base_path).max_depth(10) {
-            if count >= max_results {
+        let query_fingerprint = fingerprint(&(
+            &args.pattern, base_path, fuzzy, regex, case_sensitive, file_type, &args.ignore_patterns,
+        ));
+        let mut pager = Pager::resume(args.continue_from.as_deref(), query_fingerprint, max_results)?;
+        let ignore = IgnorePatterns::new(args.ignore_patterns.as_deref().unwrap_or_default())?;
+        let excludes = IgnorePatterns::new(&self.excludes)?;
+
+        // Simple pattern matching implementation, walked in sorted order so pages are stable.
+        // Ignored directories and the trash are pruned, so nothing beneath them is visited.
+        let walk_root = PathBuf::from(self.resolve(base_path));
+        let walker = WalkDir::new(&walk_root).max_depth(10).sort_by_file_name().into_iter()
+            .filter_entry(|entry| {
+                let relative = entry.path().strip_prefix(&walk_root).unwrap_or(entry.path());
+                let is_dir = entry.file_type().is_dir();
+                // Excludes are relative to the root rather than the base path
+                let from_root = root::as_shown(entry.path(), &walk_root, base_path);
+                let excluded = excludes.is_ignored(Path::new(&from_root), is_dir);
+                entry.depth() == 0
+                    || !(trash::is_trash_dir(entry.path()) || excluded || ignore.is_ignored(relative, is_dir))
+            });
+        for entry in walker {
+            if pager.is_full() {
                 break;
             }
 
             let entry = entry.map_err(|e| format!("Walk error: {}", e))?;
-            let path = entry.path();
+            let shown = root::as_shown(entry.path(), &walk_root, base_path);
+            let path = Path::new(&shown);
             let path_str = path.to_string_lossy();
 
             // Check file type filter
@@ -663,22 +1223,6 @@ impl FsExecutor {
                 continue;
             }
 
-            // Apply ignore patterns if specified
-            if let Some(ref ignore_patterns) = args.ignore_patterns {
-                let mut should_ignore = false;
-                for pattern in ignore_patterns {
-                    if path_str.contains(pattern) || path.file_name()
-                        .and_then(|n| n.to_str())
-                        .map_or(false, |name| name.contains(pattern)) {
-                        should_ignore = true;
-                        break;
-                    }
-                }
-                if should_ignore {
-                    continue;
-                }
-            }
-
             // Get file/directory name for matching
             let name = path.file_name()
                 .and_then(|n| n.to_str())
@@ -697,7 +1241,15 @@ impl FsExecutor {
             };
 
             // Simple matching logic
-            let (is_match, match_type, score) = if fuzzy {
+            let (is_match, match_type, score) = if let Some(re) = &name_regex {
+                // Like globs, a pattern with a directory separator matches the full path
+                let target = if args.pattern.contains('/') { root::strip_dot_prefix(&path_str) } else { name };
+                if re.is_match(target) {
+                    (true, "regex".to_string(), Some(1.0))
+                } else {
+                    (false, "".to_string(), None)
+                }
+            } else if fuzzy {
                 // Simple fuzzy matching - check if all characters of pattern exist in order
                 if fuzzy_match(&pattern_to_match, &name_to_match) {
                     let score = calculate_fuzzy_score(&pattern_to_match, &name_to_match);
@@ -709,28 +1261,9 @@ impl FsExecutor {
                 }
             } else {
                 // Support glob patterns using globset when fuzzy is disabled
-                let mut builder = GlobSetBuilder::new();
-                // If the pattern has a directory separator, match against full path; else match filename by prefixing **/
-                let pattern = if pattern_to_match.contains('/') { pattern_to_match.clone() } else { format!("**/{}", pattern_to_match) };
-                if let Ok(glob) = Glob::new(&pattern) {
-                    builder.add(glob);
-                    if let Ok(gs) = builder.build() {
-                        if gs.is_match(path) {
-                            let is_exact = name_to_match == pattern_to_match;
-                            (true, if is_exact { "exact".to_string() } else { "partial".to_string() }, Some(if is_exact { 1.0 } else { 0.9 }))
-                        } else if name_to_match.contains(&pattern_to_match) {
-                            (true, "partial".to_string(), Some(0.9))
-                        } else {
-                            (false, "".to_string(), None)
-                        }
-                    } else {
-                        // Fallback to substring on build error
-                        if name_to_match.contains(&pattern_to_match) {
-                            (true, "partial".to_string(), Some(0.9))
-                        } else {
-                            (false, "".to_string(), None)
-                        }
-                    }
+                if name_glob.as_ref().is_some_and(|glob| glob.is_match(path)) {
+                    let is_exact = name_to_match == pattern_to_match;
+                    (true, if is_exact { "exact".to_string() } else { "partial".to_string() }, Some(if is_exact { 1.0 } else { 0.9 }))
                 } else if name_to_match.contains(&pattern_to_match) {
                     (true, "partial".to_string(), Some(0.9))
                 } else {
@@ -739,16 +1272,15 @@ impl FsExecutor {
             };
 
             if is_match {
-                matches.push(FileMatch {
+                matches.extend(pager.page(vec![FileMatch {
                     path: path_str.to_string(),
                     score,
                     match_type,
-                });
-                count += 1;
+                }]));
             }
         }
 
-        // Sort by score if fuzzy matching
+        // Sort by score if fuzzy matching (within the page, so paging stays deterministic)
         if fuzzy {
             matches.sort_by(|a, b| {
                 b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal)
@@ -760,6 +1292,7 @@ impl FsExecutor {
         let result = FsFindResult {
             matches,
             search_time_ms,
+            continuation: pager.continuation(query_fingerprint),
         };
 
         let result_value = serde_json::to_value(result).unwrap();
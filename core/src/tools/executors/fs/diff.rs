@@ -0,0 +1,178 @@
+//! Unified diffs of planned edits, so `fs.apply_patch` can show what it changed
+
+/// Unchanged lines kept around each change
+const CONTEXT_LINES: usize = 3;
+
+/// Above this many line comparisons the changed region is shown as one replacement
+/// instead of running the quadratic line matcher
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// Unified diff turning `old` into `new`. `None` stands for a file that doesn't exist
+/// on that side. Returns an empty string when nothing changed.
+pub(crate) fn unified_diff(path: &str, old: Option<&str>, new: Option<&str>) -> String {
+    if old.is_some() == new.is_some() && old == new {
+        return String::new();
+    }
+    let old_label = if old.is_some() { format!("a/{}", path) } else { "/dev/null".to_string() };
+    let new_label = if new.is_some() { format!("b/{}", path) } else { "/dev/null".to_string() };
+    labeled_diff(&old_label, &new_label, old.unwrap_or(""), new.unwrap_or(""))
+}
+
+/// Unified diff between two different files, headed by their paths. Returns an empty
+/// string when the contents are identical.
+pub fn diff_files(old_path: &str, new_path: &str, old: &str, new: &str) -> String {
+    if old == new {
+        return String::new();
+    }
+    labeled_diff(old_path, new_path, old, new)
+}
+
+fn labeled_diff(old_label: &str, new_label: &str, old_text: &str, new_text: &str) -> String {
+    let old_lines: Vec<&str> = old_text.lines().collect();
+    let new_lines: Vec<&str> = new_text.lines().collect();
+    let ops = diff_lines(&old_lines, &new_lines);
+
+    let mut out = format!("--- {}\n+++ {}\n", old_label, new_label);
+    for hunk in hunks(&ops) {
+        out.push_str(&render_hunk(&ops, hunk, &old_lines, &new_lines));
+    }
+    out
+}
+
+/// Lines added and removed turning `old` into `new`
+pub(crate) fn line_changes(old: &str, new: &str) -> (usize, usize) {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let ops = diff_lines(&old_lines, &new_lines);
+    let added = ops.iter().filter(|op| **op == Op::Insert).count();
+    let removed = ops.iter().filter(|op| **op == Op::Delete).count();
+    (added, removed)
+}
+
+/// Edit script between two line lists: common prefix and suffix are matched directly,
+/// the middle by longest common subsequence
+fn diff_lines(old: &[&str], new: &[&str]) -> Vec<Op> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    let mut ops = vec![Op::Equal; prefix];
+    if old_mid.len().saturating_mul(new_mid.len()) > MAX_DIFF_CELLS {
+        ops.extend(std::iter::repeat_n(Op::Delete, old_mid.len()));
+        ops.extend(std::iter::repeat_n(Op::Insert, new_mid.len()));
+    } else {
+        ops.extend(lcs_ops(old_mid, new_mid));
+    }
+    ops.extend(std::iter::repeat_n(Op::Equal, suffix));
+    ops
+}
+
+fn lcs_ops(old: &[&str], new: &[&str]) -> Vec<Op> {
+    let (n, m) = (old.len(), new.len());
+    // lengths[i][j]: longest common subsequence of old[i..] and new[j..]
+    let mut lengths = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lengths[i][j] = if old[i] == new[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::with_capacity(n + m);
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && old[i] == new[j] {
+            ops.push(Op::Equal);
+            i += 1;
+            j += 1;
+        } else if j < m && (i == n || lengths[i][j + 1] >= lengths[i + 1][j]) {
+            ops.push(Op::Insert);
+            j += 1;
+        } else {
+            ops.push(Op::Delete);
+            i += 1;
+        }
+    }
+    // Within each change, list deletions before insertions
+    let mut start = 0;
+    while start < ops.len() {
+        if ops[start] == Op::Equal {
+            start += 1;
+            continue;
+        }
+        let end = ops[start..].iter().position(|op| *op == Op::Equal).map_or(ops.len(), |p| start + p);
+        ops[start..end].sort_by_key(|op| *op == Op::Insert);
+        start = end;
+    }
+    ops
+}
+
+/// Ranges of `ops` to print: each change plus its context, merging changes whose
+/// context overlaps
+fn hunks(ops: &[Op]) -> Vec<(usize, usize)> {
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for (idx, op) in ops.iter().enumerate() {
+        if *op == Op::Equal {
+            continue;
+        }
+        let start = idx.saturating_sub(CONTEXT_LINES);
+        let end = (idx + 1 + CONTEXT_LINES).min(ops.len());
+        match ranges.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => ranges.push((start, end)),
+        }
+    }
+    ranges
+}
+
+fn render_hunk(ops: &[Op], (start, end): (usize, usize), old: &[&str], new: &[&str]) -> String {
+    // Line positions at the start of the hunk
+    let mut old_pos = ops[..start].iter().filter(|op| **op != Op::Insert).count();
+    let mut new_pos = ops[..start].iter().filter(|op| **op != Op::Delete).count();
+    let old_count = ops[start..end].iter().filter(|op| **op != Op::Insert).count();
+    let new_count = ops[start..end].iter().filter(|op| **op != Op::Delete).count();
+
+    // Empty sides are numbered from the line before, as `diff -u` does
+    let header_start = |pos: usize, count: usize| if count == 0 { pos } else { pos + 1 };
+    let mut out = format!(
+        "@@ -{},{} +{},{} @@\n",
+        header_start(old_pos, old_count),
+        old_count,
+        header_start(new_pos, new_count),
+        new_count
+    );
+    for op in &ops[start..end] {
+        match op {
+            Op::Equal => {
+                out.push_str(&format!(" {}\n", old[old_pos]));
+                old_pos += 1;
+                new_pos += 1;
+            }
+            Op::Delete => {
+                out.push_str(&format!("-{}\n", old[old_pos]));
+                old_pos += 1;
+            }
+            Op::Insert => {
+                out.push_str(&format!("+{}\n", new[new_pos]));
+                new_pos += 1;
+            }
+        }
+    }
+    out
+}
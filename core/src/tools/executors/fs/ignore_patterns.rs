@@ -0,0 +1,63 @@
+//! Gitignore-style `ignore_patterns` for `fs.find` and the project config's `excludes`
+//!
+//! Patterns are read like `.gitignore` lines relative to the search base: one without a
+//! `/` matches a name at any depth, a leading or inner `/` anchors it to the base, a
+//! trailing `/` matches directories only, and `!` re-includes. The last matching
+//! pattern wins.
+
+use globset::{GlobBuilder, GlobMatcher};
+use std::path::Path;
+
+struct Rule {
+    matcher: GlobMatcher,
+    dir_only: bool,
+    negated: bool,
+}
+
+pub(crate) struct IgnorePatterns {
+    rules: Vec<Rule>,
+}
+
+impl IgnorePatterns {
+    /// Compile `patterns`, skipping blank lines and `#` comments
+    pub(crate) fn new(patterns: &[String]) -> Result<Self, String> {
+        let mut rules = Vec::new();
+        for raw in patterns {
+            let pattern = raw.trim();
+            if pattern.is_empty() || pattern.starts_with('#') {
+                continue;
+            }
+            let (negated, pattern) = match pattern.strip_prefix('!') {
+                Some(rest) => (true, rest),
+                None => (false, pattern),
+            };
+            let (dir_only, pattern) = match pattern.strip_suffix('/') {
+                Some(rest) => (true, rest),
+                None => (false, pattern),
+            };
+            let glob = match pattern.strip_prefix('/') {
+                Some(anchored) => anchored.to_string(),
+                None if pattern.contains('/') => pattern.to_string(),
+                None => format!("**/{}", pattern),
+            };
+            let matcher = GlobBuilder::new(&glob)
+                .literal_separator(true)
+                .build()
+                .map_err(|e| format!("Invalid ignore pattern {}: {}", raw, e))?
+                .compile_matcher();
+            rules.push(Rule { matcher, dir_only, negated });
+        }
+        Ok(Self { rules })
+    }
+
+    /// Whether `relative` (to the search base) is excluded; `is_dir` lets directory-only patterns apply
+    pub(crate) fn is_ignored(&self, relative: &Path, is_dir: bool) -> bool {
+        let mut ignored = false;
+        for rule in &self.rules {
+            if (!rule.dir_only || is_dir) && rule.matcher.is_match(relative) {
+                ignored = !rule.negated;
+            }
+        }
+        ignored
+    }
+}
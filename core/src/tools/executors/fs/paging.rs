@@ -0,0 +1,81 @@
+//! Continuation tokens for paging through `fs.search` and `fs.find` results
+//!
+//! Both tools walk in sorted order, so "skip the first N matches" lands on the same
+//! spot every time. A token records that offset plus a fingerprint of the query, which
+//! stops a token from one query being replayed against another.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// Hash the arguments that decide which matches a query produces
+pub(crate) fn fingerprint<T: Hash>(query: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    query.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Tracks one page: matches before `offset` are dropped, at most `limit` are kept,
+/// and seeing any match past that means another page exists.
+pub(crate) struct Pager {
+    offset: usize,
+    to_skip: usize,
+    remaining: usize,
+    taken: usize,
+    has_more: bool,
+}
+
+impl Pager {
+    /// Start from `continue_from` (or the beginning), rejecting tokens from a different query
+    pub(crate) fn resume(continue_from: Option<&str>, fingerprint: u64, limit: usize) -> Result<Self, String> {
+        let offset = match continue_from {
+            Some(token) => decode(token, fingerprint)?,
+            None => 0,
+        };
+        Ok(Self { offset, to_skip: offset, remaining: limit, taken: 0, has_more: false })
+    }
+
+    /// How many matches a source should produce for this page to be decided
+    pub(crate) fn budget(&self) -> usize {
+        self.to_skip + self.remaining + 1
+    }
+
+    /// Feed the next matches in walk order and get back the ones on this page
+    pub(crate) fn page<T>(&mut self, mut items: Vec<T>) -> Vec<T> {
+        let skipped = self.to_skip.min(items.len());
+        items.drain(..skipped);
+        self.to_skip -= skipped;
+
+        if items.len() > self.remaining {
+            items.truncate(self.remaining);
+            self.has_more = true;
+        }
+        self.remaining -= items.len();
+        self.taken += items.len();
+        items
+    }
+
+    /// Once set, the page is full and the walk can stop
+    pub(crate) fn is_full(&self) -> bool {
+        self.has_more
+    }
+
+    /// Token for the next page, if there is one
+    pub(crate) fn continuation(&self, fingerprint: u64) -> Option<String> {
+        self.has_more.then(|| encode(self.offset + self.taken, fingerprint))
+    }
+}
+
+fn encode(offset: usize, fingerprint: u64) -> String {
+    format!("{:x}-{:016x}", offset, fingerprint)
+}
+
+fn decode(token: &str, fingerprint: u64) -> Result<usize, String> {
+    let invalid = || format!("Invalid continuation token: {}", token);
+    let (offset, hash) = token.split_once('-').ok_or_else(invalid)?;
+    let offset = usize::from_str_radix(offset, 16).map_err(|_| invalid())?;
+    let hash = u64::from_str_radix(hash, 16).map_err(|_| invalid())?;
+    if hash != fingerprint {
+        return Err("Continuation token belongs to a different query".to_string());
+    }
+    Ok(offset)
+}
@@ -0,0 +1,44 @@
+//! Relevance ordering for `fs.search` results when `rank` is set
+//!
+//! The score favours files with more matching lines and a filename containing the
+//! query, and pushes vendored, generated and test trees down. Ties keep path order.
+
+use crate::tools::types::SearchMatch;
+
+/// Path components that usually hold third-party or build output
+const VENDORED_DIRS: &[&str] = &["vendor", "node_modules", "third_party", "target", "dist", "build", ".git"];
+
+/// Path components that usually hold tests and fixtures
+const TEST_DIRS: &[&str] = &["test", "tests", "__tests__", "spec", "fixtures", "testdata"];
+
+/// Path components that usually hold the project's own code
+const SOURCE_DIRS: &[&str] = &["src", "lib", "app", "pkg", "cmd"];
+
+fn score(m: &SearchMatch, query: &str) -> i64 {
+    let mut score = m.lines.len() as i64 * 10;
+    let components: Vec<String> = m
+        .path
+        .split(['/', '\\'])
+        .map(|c| c.to_lowercase())
+        .collect();
+    let has = |dirs: &[&str]| components.iter().any(|c| dirs.contains(&c.as_str()));
+
+    if has(VENDORED_DIRS) {
+        score -= 50;
+    } else if has(TEST_DIRS) {
+        score -= 10;
+    } else if has(SOURCE_DIRS) {
+        score += 5;
+    }
+
+    let query = query.to_lowercase();
+    if !query.is_empty() && components.last().is_some_and(|name| name.contains(&query)) {
+        score += 20;
+    }
+    score
+}
+
+/// Sort best-first, breaking ties by path so the order is deterministic
+pub(crate) fn rank_matches(matches: &mut [SearchMatch], query: &str) {
+    matches.sort_by_cached_key(|m| (std::cmp::Reverse(score(m, query)), m.path.clone()));
+}
@@ -1,7 +1,10 @@
+use super::diff::unified_diff;
+use super::trash::Trash;
+use crate::events::{AppEvent, EventSender};
 use crate::tools::types::SimpleEditOp;
 use std::collections::{BTreeMap, BTreeSet};
 use std::io::ErrorKind;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 pub(crate) struct PlannedFile {
     original: Option<String>,
@@ -25,9 +28,17 @@ pub(crate) struct SimpleEditPlanner {
     created: BTreeSet<String>,
     modified: BTreeSet<String>,
     deleted: BTreeSet<String>,
+    /// Directories to create that don't exist yet
+    dirs: BTreeSet<String>,
     descriptions: Vec<String>,
     bytes_added: u64,
     bytes_removed: u64,
+    /// Deleted files are moved here; `None` removes them outright
+    trash: Option<Trash>,
+    /// Files moved to the trash on commit, with where they went
+    trashed: Vec<(String, PathBuf)>,
+    /// Where to report each file as it is committed, with the tool call id
+    progress: Option<(EventSender, String)>,
 }
 
 impl SimpleEditPlanner {
@@ -39,19 +50,33 @@ impl SimpleEditPlanner {
             created: BTreeSet::new(),
             modified: BTreeSet::new(),
             deleted: BTreeSet::new(),
+            dirs: BTreeSet::new(),
             descriptions: Vec::new(),
             bytes_added: 0,
             bytes_removed: 0,
+            trash: None,
+            trashed: Vec::new(),
+            progress: None,
         }
     }
 
+    pub(crate) fn with_trash(mut self, trash: Option<Trash>) -> Self {
+        self.trash = trash;
+        self
+    }
+
+    /// Send a `ToolProgress` for tool call `id` per file renamed, written or deleted on commit
+    pub(crate) fn with_progress(mut self, event_sender: EventSender, id: String) -> Self {
+        self.progress = Some((event_sender, id));
+        self
+    }
+
     pub(crate) async fn apply_op(&mut self, op: &SimpleEditOp) -> Result<(), String> {
         match op {
             SimpleEditOp::SetFile { path, contents } => {
                 self.ensure_entry_allow_new(path).await?;
                 let normalized = normalize_newlines(contents);
                 self.set_current(path, normalized)?;
-                self.descriptions.push(format!("set_file {}", path));
             }
             SimpleEditOp::ReplaceOnce { path, find, replace } => {
                 self.ensure_entry(path).await?;
@@ -62,7 +87,6 @@ impl SimpleEditPlanner {
                 let mut new_content = current.clone();
                 new_content.replace_range(idx..idx + needle.len(), &replacement);
                 self.set_current(path, new_content)?;
-                self.descriptions.push(format!("replace_once {}", path));
             }
             SimpleEditOp::InsertBefore { path, anchor, insert } => {
                 self.ensure_entry(path).await?;
@@ -73,7 +97,6 @@ impl SimpleEditPlanner {
                 let mut new_content = current.clone();
                 new_content.insert_str(idx, &insertion);
                 self.set_current(path, new_content)?;
-                self.descriptions.push(format!("insert_before {}", path));
             }
             SimpleEditOp::InsertAfter { path, anchor, insert } => {
                 self.ensure_entry(path).await?;
@@ -84,12 +107,10 @@ impl SimpleEditPlanner {
                 let mut new_content = current.clone();
                 new_content.insert_str(idx + anchor_text.len(), &insertion);
                 self.set_current(path, new_content)?;
-                self.descriptions.push(format!("insert_after {}", path));
             }
             SimpleEditOp::DeleteFile { path } => {
                 self.ensure_entry(path).await?;
                 self.delete_current(path)?;
-                self.descriptions.push(format!("delete_file {}", path));
             }
             SimpleEditOp::RenameFile { path, to } => {
                 if path == to {
@@ -120,18 +141,72 @@ impl SimpleEditPlanner {
                 self.files.insert(to_owned.clone(), entry);
                 self.reassign_path(path, &to_owned);
                 self.renames.push((path.to_string(), to_owned.clone(), should_rename));
-                self.descriptions.push(format!("rename_file {} -> {}", path, to));
+            }
+            SimpleEditOp::CreateEmpty { path } => {
+                let exists = match self.files.get(path) {
+                    Some(entry) => entry.current.is_some(),
+                    None => path_exists_on_disk(path).await?,
+                };
+                if exists {
+                    return Err(format!("Path already exists: {}", path));
+                }
+                self.ensure_entry_allow_new(path).await?;
+                self.set_current(path, String::new())?;
+            }
+            SimpleEditOp::CreateDir { path } => {
+                if self.files.get(path).is_some_and(|entry| entry.current.is_some()) {
+                    return Err(format!("A file is planned at {}", path));
+                }
+                match tokio::fs::metadata(path).await {
+                    Ok(meta) if meta.is_dir() => {}
+                    Ok(_) => return Err(format!("Not a directory: {}", path)),
+                    Err(e) if e.kind() == ErrorKind::NotFound => {
+                        self.dirs.insert(path.to_string());
+                    }
+                    Err(e) => return Err(format!("Failed to inspect {}: {}", path, e)),
+                }
             }
         }
 
+        self.descriptions.push(op.describe());
         Ok(())
     }
 
-    pub(crate) async fn finish(self) -> Result<String, String> {
+    /// Apply `op` and return the diff of just what it changed. A renamed file is listed
+    /// under its new path and only shows a diff if its contents changed too.
+    pub(crate) async fn apply_op_with_diff(&mut self, op: &SimpleEditOp) -> Result<String, String> {
+        let before: BTreeMap<String, Option<String>> = self
+            .files
+            .iter()
+            .map(|(path, entry)| (path.clone(), entry.current.clone()))
+            .collect();
+        self.apply_op(op).await?;
+        Ok(self
+            .files
+            .iter()
+            .map(|(path, entry)| {
+                // Files first touched by this op start from what is on disk
+                let old = before.get(path).cloned().unwrap_or_else(|| entry.original.clone());
+                unified_diff(path, old.as_deref(), entry.current.as_deref())
+            })
+            .collect())
+    }
+
+    /// Unified diff of every planned file change, in path order
+    pub(crate) fn diff(&self) -> String {
+        self.files
+            .iter()
+            .map(|(path, entry)| unified_diff(path, entry.original.as_deref(), entry.current.as_deref()))
+            .collect()
+    }
+
+    /// Commit unless dry-running; returns the summary and where deleted files were trashed
+    pub(crate) async fn finish(mut self) -> Result<(String, Vec<PathBuf>), String> {
         if !self.dry_run {
             self.commit().await?;
         }
-        Ok(self.build_summary())
+        let trashed = self.trashed.iter().map(|(_, dest)| dest.clone()).collect();
+        Ok((self.build_summary(), trashed))
     }
 
     async fn ensure_entry(&mut self, path: &str) -> Result<(), String> {
@@ -248,11 +323,43 @@ impl SimpleEditPlanner {
         }
     }
 
-    async fn commit(&self) -> Result<(), String> {
+    /// Report step `step` of `total` ("Writing file 3/10: path")
+    fn report(&self, action: &str, step: usize, total: usize, path: &str) {
+        if let Some((sender, id)) = &self.progress {
+            sender.send(AppEvent::ToolProgress {
+                id: id.clone(),
+                message: format!("{} {}/{}: {}", action, step, total, path),
+            }).ok();
+        }
+    }
+
+    async fn commit(&mut self) -> Result<(), String> {
+        let renames = self.renames.iter().filter(|(from, to, should_rename)| *should_rename && from != to).count();
+        let changes = self
+            .files
+            .values()
+            .filter(|entry| match &entry.current {
+                Some(_) => entry.original.is_none() || entry.original != entry.current,
+                None => entry.original.is_some(),
+            })
+            .count();
+        let total = self.dirs.len() + renames + changes;
+        let mut step = 0;
+
+        for dir in &self.dirs {
+            step += 1;
+            self.report("Creating directory", step, total, dir);
+            tokio::fs::create_dir_all(dir)
+                .await
+                .map_err(|e| format!("Failed to create directory {}: {}", dir, e))?;
+        }
+
         for (from, to, should_rename) in &self.renames {
             if !should_rename || from == to {
                 continue;
             }
+            step += 1;
+            self.report("Renaming file", step, total, &format!("{} -> {}", from, to));
             if let Some(parent) = Path::new(to).parent() {
                 if !parent.as_os_str().is_empty() {
                     tokio::fs::create_dir_all(parent)
@@ -269,6 +376,8 @@ impl SimpleEditPlanner {
             match &entry.current {
                 Some(content) => {
                     if entry.original.is_none() || entry.original.as_ref() != entry.current.as_ref() {
+                        step += 1;
+                        self.report("Writing file", step, total, path);
                         if let Some(parent) = Path::new(path).parent() {
                             if !parent.as_os_str().is_empty() {
                                 tokio::fs::create_dir_all(parent)
@@ -283,6 +392,14 @@ impl SimpleEditPlanner {
                 }
                 None => {
                     if entry.original.is_some() {
+                        step += 1;
+                        self.report("Deleting file", step, total, path);
+                        if let Some(trash) = &self.trash {
+                            if let Some(dest) = trash.take(path).await? {
+                                self.trashed.push((path.clone(), dest));
+                            }
+                            continue;
+                        }
                         match tokio::fs::remove_file(path).await {
                             Ok(_) => {}
                             Err(e) if e.kind() == ErrorKind::NotFound => {}
@@ -307,12 +424,21 @@ impl SimpleEditPlanner {
         if !self.created.is_empty() {
             lines.push(format!("Created files: {}", self.created.iter().cloned().collect::<Vec<_>>().join(", ")));
         }
+        if !self.dirs.is_empty() {
+            lines.push(format!("Created directories: {}", self.dirs.iter().cloned().collect::<Vec<_>>().join(", ")));
+        }
         if !self.modified.is_empty() {
             lines.push(format!("Modified files: {}", self.modified.iter().cloned().collect::<Vec<_>>().join(", ")));
         }
         if !self.deleted.is_empty() {
             lines.push(format!("Deleted files: {}", self.deleted.iter().cloned().collect::<Vec<_>>().join(", ")));
         }
+        if !self.trashed.is_empty() {
+            lines.push("Moved to trash:".to_string());
+            for (path, dest) in &self.trashed {
+                lines.push(format!("  {} -> {}", path, dest.display()));
+            }
+        }
         if !self.renames.is_empty() {
             lines.push("Renamed files:".to_string());
             for (from, to, _) in &self.renames {
@@ -0,0 +1,109 @@
+//! Session trash for files removed by `fs.delete_file` and `fs.apply_patch`
+//!
+//! Deleted files are moved under `.grok/trash/<session>/` in the workspace (or the system
+//! temp directory when there is no workspace root), keeping their relative path so they
+//! can be put back by hand or by an undo. `GROK_HARD_DELETE=1` removes files outright instead.
+
+use std::io::ErrorKind;
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Workspace-relative directory holding each session's trash
+pub const TRASH_DIR: &str = ".grok/trash";
+
+/// Whether `path` is a workspace trash directory, which `fs.search` and `fs.find` skip
+/// so deleted files don't come back as hits
+pub(crate) fn is_trash_dir(path: &Path) -> bool {
+    path.ends_with(TRASH_DIR)
+}
+
+/// Where this process moves deleted files
+#[derive(Debug, Clone)]
+pub(crate) struct Trash {
+    dir: PathBuf,
+    root: Option<PathBuf>,
+}
+
+impl Trash {
+    /// Trash for a workspace, or `None` when `GROK_HARD_DELETE` opts out
+    pub(crate) fn for_root(root: Option<&Path>) -> Option<Self> {
+        let hard_delete = std::env::var("GROK_HARD_DELETE")
+            .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "on" | "yes"))
+            .unwrap_or(false);
+        if hard_delete {
+            return None;
+        }
+        let base = match root {
+            Some(root) => root.join(TRASH_DIR),
+            None => std::env::temp_dir().join("grok-trash"),
+        };
+        Some(Self::at(base.join(session_id()), root.map(Path::to_path_buf)))
+    }
+
+    /// Trash in `dir`, storing files under their path relative to `root`
+    pub(crate) fn at(dir: PathBuf, root: Option<PathBuf>) -> Self {
+        Self { dir, root }
+    }
+
+    /// Move `path` into the trash and return where it went; `None` if it was already gone
+    pub(crate) async fn take(&self, path: &str) -> Result<Option<PathBuf>, String> {
+        let source = Path::new(path);
+        let mut dest = self.dir.join(self.relative(source));
+        let mut n = 1;
+        while tokio::fs::try_exists(&dest).await.unwrap_or(false) {
+            dest = numbered(&self.dir.join(self.relative(source)), n);
+            n += 1;
+        }
+        if let Some(parent) = dest.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| format!("Failed to create trash directory {}: {}", parent.display(), e))?;
+        }
+
+        match tokio::fs::rename(source, &dest).await {
+            Ok(()) => Ok(Some(dest)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            // Renames can't cross filesystems, so fall back to copying
+            Err(_) => {
+                tokio::fs::copy(source, &dest)
+                    .await
+                    .map_err(|e| format!("Failed to move {} to trash: {}", path, e))?;
+                tokio::fs::remove_file(source)
+                    .await
+                    .map_err(|e| format!("Failed to delete file {}: {}", path, e))?;
+                Ok(Some(dest))
+            }
+        }
+    }
+
+    /// `path` inside the workspace, or its components without the root or drive prefix
+    fn relative(&self, path: &Path) -> PathBuf {
+        let inside = self.root.as_deref().and_then(|root| path.strip_prefix(root).ok()).unwrap_or(path);
+        inside
+            .components()
+            .filter_map(|c| match c {
+                Component::Normal(part) => Some(part),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// `name.ext` becomes `name.ext.1`, `name.ext.2`, ... for repeat deletions
+fn numbered(path: &Path, n: usize) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}", n));
+    path.with_file_name(name)
+}
+
+/// Directory name shared by every deletion in this process
+fn session_id() -> &'static str {
+    static ID: OnceLock<String> = OnceLock::new();
+    ID.get_or_init(|| {
+        let secs = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        format!("{}-{}", secs, std::process::id())
+    })
+}
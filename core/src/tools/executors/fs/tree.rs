@@ -0,0 +1,146 @@
+//! Directory tree rendering for `fs.tree`
+//!
+//! Produces a `tree`-style listing with per-directory entry counts. Noisy build and
+//! VCS directories are always skipped, and simple patterns from the root `.gitignore`
+//! are honored.
+
+use crate::tools::types::FsTreeResult;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::path::Path;
+
+/// Directories that never add useful structure to an overview
+const NOISY_DIRS: &[&str] = &[
+    ".git", "target", "node_modules", "dist", "build", ".cache", "__pycache__",
+    ".pytest_cache", ".venv", "venv", ".idea", ".vscode", "coverage",
+];
+
+pub(crate) const DEFAULT_MAX_DEPTH: usize = 3;
+pub(crate) const DEFAULT_MAX_NODES: usize = 500;
+
+/// Render the tree rooted at `root`, listing at most `max_nodes` entries
+pub(crate) fn render_tree(root: &Path, max_depth: usize, max_nodes: usize) -> Result<FsTreeResult, String> {
+    if !root.is_dir() {
+        return Err(format!("Path is not a directory: {}", root.display()));
+    }
+
+    let mut walker = TreeWalker {
+        root,
+        ignore: load_gitignore(root),
+        max_depth,
+        max_nodes,
+        lines: vec![root.display().to_string()],
+        total_dirs: 0,
+        total_files: 0,
+        truncated: false,
+    };
+    walker.walk(root, 1, "");
+
+    Ok(FsTreeResult {
+        tree: walker.lines.join("\n"),
+        total_dirs: walker.total_dirs,
+        total_files: walker.total_files,
+        truncated: walker.truncated,
+    })
+}
+
+struct TreeWalker<'a> {
+    root: &'a Path,
+    ignore: Option<GlobSet>,
+    max_depth: usize,
+    max_nodes: usize,
+    lines: Vec<String>,
+    total_dirs: u64,
+    total_files: u64,
+    truncated: bool,
+}
+
+impl TreeWalker<'_> {
+    fn walk(&mut self, dir: &Path, depth: usize, prefix: &str) {
+        let entries = self.visible_entries(dir);
+        let count = entries.len();
+
+        for (i, (path, is_dir)) in entries.into_iter().enumerate() {
+            // Header line is not a node, so `lines.len() - 1` entries have been emitted
+            if self.lines.len() > self.max_nodes {
+                self.truncated = true;
+                return;
+            }
+
+            let last = i + 1 == count;
+            let branch = if last { "└── " } else { "├── " };
+            let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+
+            if is_dir {
+                self.total_dirs += 1;
+                let children = self.visible_entries(&path).len();
+                self.lines.push(format!("{}{}{}/ ({} entries)", prefix, branch, name, children));
+                if depth < self.max_depth {
+                    let child_prefix = format!("{}{}", prefix, if last { "    " } else { "│   " });
+                    self.walk(&path, depth + 1, &child_prefix);
+                }
+            } else {
+                self.total_files += 1;
+                self.lines.push(format!("{}{}{}", prefix, branch, name));
+            }
+        }
+    }
+
+    /// Sorted children of `dir` (directories first), minus ignored entries
+    fn visible_entries(&self, dir: &Path) -> Vec<(std::path::PathBuf, bool)> {
+        let Ok(read_dir) = std::fs::read_dir(dir) else {
+            return Vec::new();
+        };
+
+        let mut entries: Vec<(std::path::PathBuf, bool)> = read_dir
+            .flatten()
+            .map(|e| {
+                let is_dir = e.file_type().map(|t| t.is_dir()).unwrap_or(false);
+                (e.path(), is_dir)
+            })
+            .filter(|(path, is_dir)| !self.is_ignored(path, *is_dir))
+            .collect();
+
+        entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.file_name().cmp(&b.0.file_name())));
+        entries
+    }
+
+    fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let name = path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+        if is_dir && NOISY_DIRS.contains(&name.as_ref()) {
+            return true;
+        }
+        match (&self.ignore, path.strip_prefix(self.root)) {
+            (Some(set), Ok(relative)) => set.is_match(relative),
+            _ => false,
+        }
+    }
+}
+
+/// Build a matcher from the root `.gitignore`. Negations are not supported and are skipped.
+fn load_gitignore(root: &Path) -> Option<GlobSet> {
+    let contents = std::fs::read_to_string(root.join(".gitignore")).ok()?;
+    let mut builder = GlobSetBuilder::new();
+    let mut any = false;
+
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with('!') {
+            continue;
+        }
+        let pattern = line.trim_end_matches('/');
+        // Patterns containing a slash are anchored to the root, others match at any depth
+        let base = if pattern.contains('/') {
+            pattern.trim_start_matches('/').to_string()
+        } else {
+            format!("**/{}", pattern)
+        };
+        for glob in [base.clone(), format!("{}/**", base)] {
+            if let Ok(glob) = Glob::new(&glob) {
+                builder.add(glob);
+                any = true;
+            }
+        }
+    }
+
+    if any { builder.build().ok() } else { None }
+}
@@ -0,0 +1,177 @@
+use crate::events::{AppEvent, EventSender};
+use crate::tools::types::*;
+use crate::tools::url_safety::UrlPolicy;
+use reqwest::Url;
+use serde_json::Value;
+use std::time::Duration;
+
+mod html;
+
+use html::html_to_text;
+
+/// Default cap on how much of a response body is downloaded
+const DEFAULT_FETCH_MAX_BYTES: u64 = 512 * 1024;
+
+/// Limit for each request
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Redirect hops followed before giving up
+const MAX_REDIRECTS: usize = 5;
+
+/// HTTP fetch executor
+pub struct HttpExecutor {
+    event_sender: EventSender,
+    max_output_size: usize,
+    /// Which URLs may be requested, checked again on every redirect
+    policy: UrlPolicy,
+}
+
+impl HttpExecutor {
+    pub fn new(event_sender: EventSender, max_output_size: usize) -> Self {
+        Self {
+            event_sender,
+            max_output_size,
+            policy: UrlPolicy::from_env(),
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn with_private_hosts_allowed(mut self) -> Self {
+        self.policy.allow_private = true;
+        self
+    }
+
+    /// Truncate a JSON value if it exceeds the maximum output size
+    fn truncate_result(&self, result: Value) -> Value {
+        let json_str = serde_json::to_string(&result).unwrap_or_else(|_| "{}".to_string());
+
+        if json_str.len() <= self.max_output_size {
+            result
+        } else {
+            serde_json::json!({
+                "truncated": true,
+                "original_size_bytes": json_str.len(),
+                "max_allowed_bytes": self.max_output_size,
+                "message": "The tool output was too large and has been truncated. The rest of the output was too long.",
+                "note": "Output exceeded the maximum size limit to prevent excessive token usage in the conversation."
+            })
+        }
+    }
+
+    /// GET `url` without following redirects, connecting only to addresses the policy accepted
+    async fn request(&self, url: &Url) -> Result<reqwest::Response, String> {
+        let addrs = self.policy.resolve(url).await?;
+        let host = url.host_str().unwrap_or_default();
+        let client = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .resolve_to_addrs(host, &addrs)
+            .timeout(FETCH_TIMEOUT)
+            .user_agent(concat!("grok-code/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+
+        client
+            .get(url.clone())
+            .send()
+            .await
+            .map_err(|e| format!("Failed to fetch {}: {}", url, e))
+    }
+
+    pub async fn execute_fetch(&self, id: String, args: Value) -> Result<(), String> {
+        let _ = self.execute_fetch_with_result(id, args).await?;
+        Ok(())
+    }
+
+    pub async fn execute_fetch_with_result(&self, id: String, args: Value) -> Result<Value, String> {
+        let args: HttpFetchArgs = serde_json::from_value(args)
+            .map_err(|e| format!("Invalid HttpFetch arguments: {}", e))?;
+
+        let mut url = Url::parse(&args.url).map_err(|e| format!("Invalid URL {}: {}", args.url, e))?;
+        self.policy.check(&url)?;
+        let max_bytes = args.max_bytes.unwrap_or(DEFAULT_FETCH_MAX_BYTES);
+
+        self.event_sender.send(AppEvent::ToolProgress {
+            id: id.clone(),
+            message: format!("Fetching {}", url),
+        }).map_err(|e| format!("Failed to send progress event: {}", e))?;
+
+        // Redirects are followed by hand so each hop is resolved, checked and pinned
+        let mut hops = 0;
+        let mut response = loop {
+            let response = self.request(&url).await?;
+            if !response.status().is_redirection() {
+                break response;
+            }
+            let Some(location) = response
+                .headers()
+                .get(reqwest::header::LOCATION)
+                .and_then(|v| v.to_str().ok())
+            else {
+                break response;
+            };
+            hops += 1;
+            if hops > MAX_REDIRECTS {
+                return Err(format!("Too many redirects (more than {})", MAX_REDIRECTS));
+            }
+            url = url
+                .join(location)
+                .map_err(|e| format!("Invalid redirect from {} to {}: {}", url, location, e))?;
+        };
+
+        let status = response.status().as_u16();
+        let final_url = url.to_string();
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+
+        // Read only as much of the body as the cap allows
+        let mut bytes = Vec::new();
+        let mut truncated = false;
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| format!("Failed to read response from {}: {}", url, e))?
+        {
+            let room = (max_bytes as usize).saturating_sub(bytes.len());
+            if chunk.len() > room {
+                bytes.extend_from_slice(&chunk[..room]);
+                truncated = true;
+                break;
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+
+        let raw = String::from_utf8_lossy(&bytes);
+        let body = if content_type.as_deref().is_some_and(is_html) {
+            html_to_text(&raw)
+        } else {
+            raw.into_owned()
+        };
+
+        let result = HttpFetchResult {
+            url: final_url,
+            status,
+            content_type,
+            body,
+            bytes_read: bytes.len() as u64,
+            truncated,
+        };
+
+        let result_value = serde_json::to_value(result).unwrap();
+        let truncated_result = self.truncate_result(result_value.clone());
+
+        self.event_sender.send(AppEvent::ToolResult {
+            id,
+            payload: result_value,
+        }).map_err(|e| format!("Failed to send result event: {}", e))?;
+
+        Ok(truncated_result)
+    }
+}
+
+fn is_html(content_type: &str) -> bool {
+    let mime = content_type.split(';').next().unwrap_or("").trim().to_ascii_lowercase();
+    mime == "text/html" || mime == "application/xhtml+xml"
+}
@@ -0,0 +1,190 @@
+//! HTML to plain text for `http.fetch`
+//!
+//! A small tag scanner rather than a real parser: it drops scripts, styles and comments,
+//! turns block-level tags into line breaks, keeps `<pre>` whitespace and decodes the
+//! common entities. Good enough to read documentation pages, not to render them.
+
+/// Elements whose contents are never shown
+const HIDDEN_ELEMENTS: &[&str] = &["script", "style", "noscript", "template", "svg", "head"];
+
+/// Elements that start or end a line
+const LINE_ELEMENTS: &[&str] = &["br", "dd", "div", "dt", "li", "tr"];
+
+/// Elements set off from their surroundings by a blank line
+const PARAGRAPH_ELEMENTS: &[&str] = &[
+    "address", "article", "aside", "blockquote", "dl", "figcaption", "figure", "footer", "form",
+    "h1", "h2", "h3", "h4", "h5", "h6", "header", "hr", "main", "nav", "ol", "p", "pre",
+    "section", "table", "ul",
+];
+
+/// Readable text of an HTML document
+pub(crate) fn html_to_text(html: &str) -> String {
+    let mut out = TextBuilder::default();
+    let mut hidden: Option<String> = None;
+    let mut pre_depth = 0usize;
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        let Some(lt) = rest.find('<') else {
+            if hidden.is_none() {
+                out.text(&decode_entities(rest), pre_depth > 0);
+            }
+            break;
+        };
+        if hidden.is_none() && lt > 0 {
+            out.text(&decode_entities(&rest[..lt]), pre_depth > 0);
+        }
+        rest = &rest[lt..];
+
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+        let Some(gt) = rest.find('>') else {
+            // An unterminated tag swallows the rest of the document
+            break;
+        };
+        let tag = &rest[1..gt];
+        rest = &rest[gt + 1..];
+
+        let (closing, name) = match tag.strip_prefix('/') {
+            Some(name) => (true, tag_name(name)),
+            None => (false, tag_name(tag)),
+        };
+        if name.is_empty() {
+            continue;
+        }
+
+        if let Some(open) = &hidden {
+            if closing && *open == name {
+                hidden = None;
+            }
+            continue;
+        }
+        if !closing && HIDDEN_ELEMENTS.contains(&name.as_str()) && !tag.ends_with('/') {
+            hidden = Some(name);
+            continue;
+        }
+
+        if name == "pre" {
+            pre_depth = if closing { pre_depth.saturating_sub(1) } else { pre_depth + 1 };
+        }
+        if PARAGRAPH_ELEMENTS.contains(&name.as_str()) {
+            out.break_lines(2);
+        } else if LINE_ELEMENTS.contains(&name.as_str()) {
+            out.break_lines(1);
+            if name == "li" && !closing {
+                out.text("- ", true);
+            }
+        } else if name == "td" || name == "th" {
+            out.text(" ", false);
+        }
+    }
+
+    out.finish()
+}
+
+/// Lowercased element name at the start of a tag body
+fn tag_name(tag: &str) -> String {
+    tag.chars()
+        .take_while(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_lowercase()
+}
+
+/// Replace character references with the characters they name
+fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let decoded = rest[1..]
+            .find(';')
+            .filter(|&end| end <= 10)
+            .and_then(|end| decode_entity(&rest[1..end + 1]).map(|c| (c, end + 2)));
+        match decoded {
+            Some((c, len)) => {
+                out.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn decode_entity(name: &str) -> Option<char> {
+    if let Some(num) = name.strip_prefix('#') {
+        let code = match num.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => num.parse().ok()?,
+        };
+        return char::from_u32(code);
+    }
+    Some(match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => ' ',
+        "mdash" => '—',
+        "ndash" => '–',
+        "hellip" => '…',
+        "copy" => '©',
+        _ => return None,
+    })
+}
+
+/// Accumulates text, collapsing whitespace outside `<pre>` and limiting blank lines
+#[derive(Default)]
+struct TextBuilder {
+    out: String,
+}
+
+impl TextBuilder {
+    fn text(&mut self, text: &str, preformatted: bool) {
+        if preformatted {
+            self.out.push_str(text);
+            return;
+        }
+        for c in text.chars() {
+            if c.is_whitespace() {
+                if !self.out.is_empty() && !self.out.ends_with([' ', '\n']) {
+                    self.out.push(' ');
+                }
+            } else {
+                self.out.push(c);
+            }
+        }
+    }
+
+    /// End the current line so that at least `count` line breaks precede what comes next
+    fn break_lines(&mut self, count: usize) {
+        while self.out.ends_with(' ') {
+            self.out.pop();
+        }
+        if self.out.is_empty() {
+            return;
+        }
+        let existing = self.out.len() - self.out.trim_end_matches('\n').len();
+        for _ in existing..count {
+            self.out.push('\n');
+        }
+    }
+
+    fn finish(self) -> String {
+        self.out
+            .lines()
+            .map(str::trim_end)
+            .collect::<Vec<_>>()
+            .join("\n")
+            .trim()
+            .to_string()
+    }
+}
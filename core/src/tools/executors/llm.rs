@@ -1,14 +1,22 @@
 use crate::events::{AppEvent, EventSender};
 use crate::tools::types::*;
 use serde_json::{json, Value};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 use walkdir::WalkDir;
 
+/// Most files `large_context_fetch` reads for analysis
+const MAX_ANALYZED_FILES: u32 = 500;
+
+/// Most files `large_context_fetch` hands back to the conversation
+const MAX_RETURNED_FILES: u32 = 50;
+
 /// LLM-powered tool executor
 pub struct LlmExecutor {
     event_sender: EventSender,
     max_output_size: usize,
+    /// Relative paths resolve against this instead of the process working directory
+    root: Option<PathBuf>,
 }
 
 impl LlmExecutor {
@@ -16,9 +24,20 @@ impl LlmExecutor {
         Self {
             event_sender,
             max_output_size,
+            root: None,
         }
     }
 
+    /// Resolve relative paths against `root` (the session's working directory)
+    pub fn with_root(mut self, root: Option<PathBuf>) -> Self {
+        self.root = super::root::normalize(root);
+        self
+    }
+
+    fn resolve(&self, path: &str) -> String {
+        super::root::resolve(self.root.as_deref(), path)
+    }
+
     /// Truncate a JSON value if it exceeds the maximum output size
     fn truncate_result(&self, result: Value) -> Value {
         let json_str = serde_json::to_string(&result).unwrap_or_else(|_| "{}".to_string());
@@ -56,9 +75,12 @@ impl LlmExecutor {
 
         // Step 1: Gather all code files (no size truncation; include full contents)
         let base_path = args.base_path.as_deref().unwrap_or(".");
-        let max_files = args.max_files.unwrap_or(200); // still keep a sane cap on the count
+        let walk_root = self.resolve(base_path);
+        let max_files = args.max_files.unwrap_or(200).clamp(1, MAX_ANALYZED_FILES);
+        let max_return_files = args.max_return_files.unwrap_or(10).clamp(1, MAX_RETURNED_FILES);
 
         let code_files = self.gather_code_files(
+            &walk_root,
             base_path,
             &args.include_extensions,
             &args.exclude_patterns,
@@ -75,7 +97,8 @@ impl LlmExecutor {
             message: format!("Sending {} files to LLM for relevance reasoning (structured outputs)...", code_files.len()),
         }).map_err(|e| format!("Failed to send progress event: {}", e))?;
 
-        let llm_json = self.request_llm_structured_output(&args.user_query, &code_files).await?;
+        let llm_json = self.request_llm_structured_output(&args.user_query, &code_files, max_return_files).await?;
+        let llm_json = cap_relevant_files(llm_json, max_return_files as usize);
 
         // Optional: include diagnostics (timing) if you want, but request asked to return exactly the LLM JSON.
         let _execution_time_ms = start.elapsed().as_millis() as u64;
@@ -89,14 +112,16 @@ impl LlmExecutor {
         Ok(llm_json)
     }
 
+    /// Walk `walk_root`, reporting paths relative to `base_path` as the caller named it
     fn gather_code_files(
         &self,
+        walk_root: &str,
         base_path: &str,
         include_extensions: &Option<Vec<String>>,
         exclude_patterns: &Option<Vec<String>>,
         max_files: u32,
     ) -> Result<Vec<CodeFile>, String> {
-        let path = Path::new(base_path);
+        let path = Path::new(walk_root);
         if !path.exists() {
             return Err(format!("Path does not exist: {}", base_path));
         }
@@ -139,6 +164,7 @@ impl LlmExecutor {
             }
 
             let entry = entry.map_err(|e| format!("Error walking directory: {}", e))?;
+            let shown = super::root::as_shown(entry.path(), Path::new(walk_root), base_path);
             let path = entry.path();
 
             // Skip directories
@@ -147,12 +173,11 @@ impl LlmExecutor {
             }
 
             // Check if path should be excluded
-            let path_str = path.to_string_lossy();
+            let path_str = shown.as_str();
             if exclude_patterns.iter().any(|pattern| {
                 if pattern.contains('*') {
                     // Simple glob matching for patterns like "*.lock"
-                    if pattern.starts_with("*.") {
-                        let ext = &pattern[2..];
+                    if let Some(ext) = pattern.strip_prefix("*.") {
                         path_str.ends_with(ext)
                     } else {
                         path_str.contains(pattern.trim_start_matches('*'))
@@ -185,7 +210,7 @@ impl LlmExecutor {
                         .map(|ext| ext.to_lowercase());
 
                     code_files.push(CodeFile {
-                        path: path.to_string_lossy().to_string(), // full path
+                        path: shown,
                         contents,                                  // full contents
                         language,
                         size_bytes: file_size,
@@ -209,6 +234,7 @@ impl LlmExecutor {
         &self,
         user_query: &str,
         code_files: &[CodeFile],
+        max_return_files: u32,
     ) -> Result<Value, String> {
         // API configuration
         let api_key = std::env::var("OPENROUTER_API_KEY")
@@ -235,13 +261,14 @@ impl LlmExecutor {
         }).collect();
 
         // System/user prompts
-        let system_prompt = r#"You are a code analysis assistant.
-Given a user query and a list of files ({ file_path, content }), identify which files are relevant.
+        let system_prompt = format!(r#"You are a code analysis assistant.
+Given a user query and a list of files ({{ file_path, content }}), identify which files are relevant.
+Return at most {max_return_files} files, most relevant first.
 Return ONLY a strict JSON array of objects, each with:
 - "file_path": string (the exact path provided to you)
 - "reason": string (brief explanation why this file is relevant)
 Do not include any other fields or wrapper keys.
-NO prose, NO markdown, NO code fences—just valid JSON."#;
+NO prose, NO markdown, NO code fences—just valid JSON."#);
 
         let user_message = json!({
             "user_query": user_query,
@@ -329,5 +356,12 @@ NO prose, NO markdown, NO code fences—just valid JSON."#;
 
         Ok(result)
     }
+}
 
+/// Keep the first `max` entries of the ranked array; the model may ignore the limit in its prompt
+pub(crate) fn cap_relevant_files(mut result: Value, max: usize) -> Value {
+    if let Some(files) = result.as_array_mut() {
+        files.truncate(max);
+    }
+    result
 }
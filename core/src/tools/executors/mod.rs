@@ -2,8 +2,11 @@ pub mod fs;
 pub mod shell;
 pub mod code;
 pub mod llm;
+pub mod http;
+mod root;
 
 pub use fs::*;
 pub use shell::*;
 pub use code::*;
 pub use llm::*;
+pub use http::*;
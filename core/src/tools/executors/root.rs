@@ -0,0 +1,41 @@
+//! Resolving tool paths against the session's workspace root
+//!
+//! Paths in tool results are relative to the workspace root with no leading `./`
+//! (`src/main.rs`), so they can be passed straight back to another tool. Paths the
+//! caller gave as absolute, or under an absolute base path, stay absolute.
+
+use std::path::{Path, PathBuf};
+
+/// Join a relative `path` onto `root`. Absolute paths, and every path when no
+/// root is set, are used as given.
+pub(crate) fn resolve(root: Option<&Path>, path: &str) -> String {
+    match root {
+        Some(root) if !Path::new(path).is_absolute() => root.join(path).to_string_lossy().into_owned(),
+        _ => path.to_string(),
+    }
+}
+
+/// Report `path`, found while walking `walked`, relative to how the caller named the
+/// walk's starting point, so results read the same with or without a root
+pub(crate) fn as_shown(path: &Path, walked: &Path, shown: &str) -> String {
+    let joined = match path.strip_prefix(walked) {
+        Ok(rest) if rest.as_os_str().is_empty() => shown.to_string(),
+        Ok(rest) => Path::new(shown).join(rest).to_string_lossy().into_owned(),
+        Err(_) => path.to_string_lossy().into_owned(),
+    };
+    strip_dot_prefix(&joined).to_string()
+}
+
+/// Drop leading `./` components; `.` itself is kept
+pub(crate) fn strip_dot_prefix(path: &str) -> &str {
+    let mut path = path;
+    while let Some(rest) = path.strip_prefix("./") {
+        path = rest.trim_start_matches('/');
+    }
+    if path.is_empty() { "." } else { path }
+}
+
+/// Builder shared by the executors: `None` means the process working directory
+pub(crate) fn normalize(root: Option<PathBuf>) -> Option<PathBuf> {
+    root.filter(|r| !r.as_os_str().is_empty() && r != Path::new("."))
+}
@@ -1,16 +1,26 @@
 use crate::events::{AppEvent, EventSender};
 use crate::tools::types::*;
 use serde_json::Value;
+use std::path::PathBuf;
 use std::time::{Duration, Instant};
 use tokio::io::{AsyncBufReadExt, BufReader as AsyncBufReader};
 use tokio::process::Command;
 use tokio::time::timeout;
 use std::process::Stdio;
 
+/// Timeout for a `shell.exec` call that doesn't set `timeout_ms`
+pub const DEFAULT_SHELL_TIMEOUT_MS: u64 = 30_000;
+
+/// How long `execute` keeps reading output after the command exits. A background
+/// process that inherited the pipes would otherwise keep the readers open forever.
+const OUTPUT_DRAIN_MS: u64 = 500;
+
 /// Shell execution executor
 pub struct ShellExecutor {
     event_sender: EventSender,
     max_output_size: usize,
+    /// Relative paths resolve against this instead of the process working directory
+    root: Option<PathBuf>,
 }
 
 impl ShellExecutor {
@@ -18,6 +28,21 @@ impl ShellExecutor {
         Self {
             event_sender,
             max_output_size,
+            root: None,
+        }
+    }
+
+    /// Resolve relative paths against `root` (the session's working directory)
+    pub fn with_root(mut self, root: Option<PathBuf>) -> Self {
+        self.root = super::root::normalize(root);
+        self
+    }
+
+    /// `cwd` resolved against the root, or the root itself when no `cwd` is given
+    fn working_dir(&self, args: &ShellExecArgs) -> Option<String> {
+        match &args.cwd {
+            Some(cwd) => Some(super::root::resolve(self.root.as_deref(), cwd)),
+            None => self.root.as_ref().map(|root| root.to_string_lossy().into_owned()),
         }
     }
 
@@ -54,16 +79,17 @@ impl ShellExecutor {
         }).ok();
 
         let start = Instant::now();
-        let timeout_duration = Duration::from_millis(args.timeout_ms.unwrap_or(30000));
+        let timeout_duration = Duration::from_millis(args.timeout_ms.unwrap_or(DEFAULT_SHELL_TIMEOUT_MS));
 
         // Setup command
-        let mut command = Command::new(&args.command[0]);
-        if args.command.len() > 1 {
-            command.args(&args.command[1..]);
+        let argv = prepare_argv(&args);
+        let mut command = Command::new(&argv[0]);
+        if argv.len() > 1 {
+            command.args(&argv[1..]);
         }
 
         // Set working directory
-        if let Some(cwd) = &args.cwd {
+        if let Some(cwd) = self.working_dir(&args) {
             command.current_dir(cwd);
         }
 
@@ -115,20 +141,25 @@ impl ShellExecutor {
         // Wait for process with timeout
         let wait_result = timeout(timeout_duration, child.wait()).await;
 
-        // Cancel reading tasks
-        stdout_task.abort();
-        stderr_task.abort();
-
         let exit_status = match wait_result {
             Ok(Ok(status)) => status,
             Ok(Err(e)) => return Err(format!("Process wait error: {}", e)),
             Err(_) => {
-                // Timeout - kill the process
+                // Timeout - kill the process and stop reading
+                stdout_task.abort();
+                stderr_task.abort();
                 let _ = child.kill().await;
                 return Err("Command timed out".to_string());
             }
         };
 
+        // Drain remaining output so trailing lines still reach the UI
+        for mut task in [stdout_task, stderr_task] {
+            if timeout(Duration::from_millis(OUTPUT_DRAIN_MS), &mut task).await.is_err() {
+                task.abort();
+            }
+        }
+
         // Ensure duration is at least 1ms for tests that assert > 0
         let duration_ms = (start.elapsed().as_millis() as u64).max(1);
         let exit_code = exit_status.code().unwrap_or(-1);
@@ -155,27 +186,51 @@ impl ShellExecutor {
         let args: ShellExecArgs = serde_json::from_value(args)
             .map_err(|e| format!("Invalid ShellExec arguments: {}", e))?;
 
+        let result = self.run(&id, &args).await?;
+        let exit_code = result.exit_code;
+
+        let result_value = serde_json::to_value(result).unwrap();
+        let truncated_result = self.truncate_result(result_value.clone());
+
+        // Send result event for UI
+        self.event_sender.send(AppEvent::ToolResult {
+            id,
+            payload: result_value,
+        }).ok();
+
+        if exit_code != 0 {
+            return Err(format!("Command failed with exit code: {}", exit_code));
+        }
+
+        Ok(truncated_result)
+    }
+
+    /// Spawn the command, streaming its output as ToolStdout/ToolStderr events under `id`.
+    /// A non-zero exit is not an error here and no ToolResult is sent, so other tools can
+    /// build on it and report their own result.
+    pub(crate) async fn run(&self, id: &str, args: &ShellExecArgs) -> Result<ShellExecResult, String> {
         if args.command.is_empty() {
             return Err("Empty command".to_string());
         }
 
         // Send progress event
         self.event_sender.send(AppEvent::ToolProgress {
-            id: id.clone(),
+            id: id.to_string(),
             message: format!("Executing: {}", args.command.join(" ")),
         }).ok();
 
         let start = Instant::now();
-        let timeout_duration = Duration::from_millis(args.timeout_ms.unwrap_or(30000));
+        let timeout_duration = Duration::from_millis(args.timeout_ms.unwrap_or(DEFAULT_SHELL_TIMEOUT_MS));
 
         // Setup command
-        let mut command = Command::new(&args.command[0]);
-        if args.command.len() > 1 {
-            command.args(&args.command[1..]);
+        let argv = prepare_argv(args);
+        let mut command = Command::new(&argv[0]);
+        if argv.len() > 1 {
+            command.args(&argv[1..]);
         }
 
         // Set working directory
-        if let Some(cwd) = &args.cwd {
+        if let Some(cwd) = self.working_dir(args) {
             command.current_dir(cwd);
         }
 
@@ -202,7 +257,7 @@ impl ShellExecutor {
         let mut stderr_reader = AsyncBufReader::new(stderr).lines();
 
         // Read output concurrently
-        let id_clone = id.clone();
+        let id_clone = id.to_string();
         let sender_clone = self.event_sender.clone();
         let stdout_task = tokio::spawn(async move {
             let mut lines = Vec::new();
@@ -217,7 +272,7 @@ impl ShellExecutor {
             lines
         });
 
-        let id_clone = id.clone();
+        let id_clone = id.to_string();
         let sender_clone = self.event_sender.clone();
         let stderr_task = tokio::spawn(async move {
             let mut lines = Vec::new();
@@ -263,19 +318,65 @@ impl ShellExecutor {
             stderr: stderr_output,
         };
 
-        let result_value = serde_json::to_value(result).unwrap();
-        let truncated_result = self.truncate_result(result_value.clone());
+        Ok(result)
+    }
+}
 
-        // Send result event for UI
-        self.event_sender.send(AppEvent::ToolResult {
-            id,
-            payload: result_value,
-        }).ok();
+/// The command line to spawn, with env expansion applied when requested
+fn prepare_argv(args: &ShellExecArgs) -> Vec<String> {
+    if !args.expand_env.unwrap_or(false) {
+        return args.command.clone();
+    }
 
-        if exit_code != 0 {
-            return Err(format!("Command failed with exit code: {}", exit_code));
+    // Per-call overrides shadow the inherited environment, matching what the child sees
+    let lookup = |name: &str| {
+        args.env
+            .as_ref()
+            .and_then(|vars| vars.iter().rev().find(|(key, _)| key == name).map(|(_, value)| value.clone()))
+            .or_else(|| std::env::var(name).ok())
+    };
+    let empty_undefined = args.undefined_env_empty.unwrap_or(false);
+
+    args.command.iter().map(|arg| expand_env_vars(arg, &lookup, empty_undefined)).collect()
+}
+
+/// Expand `$VAR` and `${VAR}` references. Undefined variables are kept as written
+/// unless `empty_undefined` is set; a `$` not followed by a name is always literal.
+fn expand_env_vars(arg: &str, lookup: &dyn Fn(&str) -> Option<String>, empty_undefined: bool) -> String {
+    let mut out = String::with_capacity(arg.len());
+    let mut rest = arg;
+
+    while let Some(pos) = rest.find('$') {
+        out.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+
+        let (name, consumed) = if let Some(braced) = after.strip_prefix('{') {
+            match braced.find('}') {
+                Some(end) => (&braced[..end], end + 2),
+                None => ("", 0),
+            }
+        } else {
+            let end = after
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(after.len());
+            (&after[..end], end)
+        };
+
+        let valid = !name.is_empty() && !name.starts_with(|c: char| c.is_ascii_digit());
+        if !valid {
+            out.push('$');
+            rest = after;
+            continue;
         }
 
-        Ok(truncated_result)
+        match lookup(name) {
+            Some(value) => out.push_str(&value),
+            None if empty_undefined => {}
+            None => out.push_str(&rest[pos..pos + 1 + consumed]),
+        }
+        rest = &after[consumed..];
     }
+
+    out.push_str(rest);
+    out
 }
@@ -2,6 +2,9 @@ pub mod types;
 pub mod executor;
 pub mod registry;
 pub mod executors;
+pub mod url_safety;
+#[cfg(debug_assertions)]
+mod schema;
 
 #[cfg(test)]
 pub mod tests;
@@ -9,3 +12,4 @@ pub mod tests;
 pub use types::*;
 pub use executor::ToolExecutor;
 pub use registry::ToolRegistry;
+pub use url_safety::UrlPolicy;
@@ -1,6 +1,7 @@
 use crate::events::{ToolName, ToolSpec};
 use serde_json::{json, Value};
 use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
 
 /// Registry for managing available tools and their specifications
 pub struct ToolRegistry {
@@ -17,6 +18,12 @@ impl ToolRegistry {
         registry
     }
 
+    /// Process-wide registry, built on first use so spec JSON is only constructed once
+    pub fn shared() -> Arc<ToolRegistry> {
+        static SHARED: OnceLock<Arc<ToolRegistry>> = OnceLock::new();
+        SHARED.get_or_init(|| Arc::new(ToolRegistry::new())).clone()
+    }
+
     /// Register all built-in tools
     fn register_builtin_tools(&mut self) {
         // fs.read
@@ -34,7 +41,9 @@ impl ToolRegistry {
                         },
                         "description": "Optional byte range to read"
                     },
-                    "encoding": { "type": "string", "description": "File encoding (default: utf-8)" }
+                    "encoding": { "type": "string", "description": "File encoding (default: utf-8)" },
+                    "summary_only": { "type": "boolean", "description": "Return line count, byte size and binary flag instead of contents" },
+                    "max_bytes": { "type": "integer", "minimum": 0, "description": "Most bytes of contents to return (default: 1 MiB); 0 lifts the limit up to the tool output cap. Use for large files only when the context budget allows" }
                 },
                 "required": ["path"]
             }),
@@ -43,12 +52,31 @@ impl ToolRegistry {
                 "properties": {
                     "contents": { "type": "string" },
                     "encoding": { "type": "string" },
-                    "truncated": { "type": "boolean" }
+                    "truncated": { "type": "boolean" },
+                    "is_empty": { "type": "boolean", "description": "The file exists but has no bytes" },
+                    "whitespace_only": { "type": "boolean", "description": "The file exists but holds only whitespace" },
+                    "summary": {
+                        "type": "object",
+                        "properties": {
+                            "line_count": { "type": "integer" },
+                            "byte_size": { "type": "integer" },
+                            "is_binary": { "type": "boolean" }
+                        }
+                    },
+                    "adjusted_range": {
+                        "type": "object",
+                        "properties": {
+                            "start": { "type": "integer" },
+                            "end": { "type": "integer" }
+                        },
+                        "description": "Byte range actually returned when a cut fell inside a multi-byte character"
+                    }
                 },
                 "required": ["contents", "encoding", "truncated"]
             }),
             streaming: false,
             side_effects: false,
+            needs_approval: false,
             timeout_ms: Some(5000),
         });
 
@@ -67,7 +95,13 @@ impl ToolRegistry {
                     "max_results": { "type": "integer", "minimum": 1, "description": "Maximum results" },
                     "regex": { "type": "boolean", "description": "Use regex search" },
                     "case_insensitive": { "type": "boolean", "description": "Case insensitive search" },
-                    "multiline": { "type": "boolean", "description": "Multiline search" }
+                    "multiline": { "type": "boolean", "description": "Multiline search" },
+                    "whole_word": { "type": "boolean", "description": "Match literal queries only as whole words" },
+                    "continue_from": { "type": "string", "description": "continuation token from a previous call, to fetch the next page" },
+                    "max_file_bytes": { "type": "integer", "minimum": 1, "description": "Skip files larger than this many bytes (default: 10 MiB)" },
+                    "rank": { "type": "boolean", "description": "Sort files by relevance: more matches first, source dirs above test/vendor dirs (default: false, path order)" },
+                    "include_columns": { "type": "boolean", "description": "Add each line's match positions as `columns`: [{ start, end }] byte offsets, end exclusive (default: false)" },
+                    "max_line_bytes": { "type": "integer", "minimum": 1, "description": "Cut longer matched lines to a window around the match, marked with … (default: 1000)" }
                 },
                 "required": ["query"]
             }),
@@ -79,24 +113,37 @@ impl ToolRegistry {
                         "items": {
                             "type": "object",
                             "properties": {
-                                "path": { "type": "string" },
+                                "path": { "type": "string", "description": "Relative to the workspace root, without a leading ./" },
                                 "lines": {
                                     "type": "array",
                                     "items": {
                                         "type": "object",
                                         "properties": {
                                             "ln": { "type": "integer" },
-                                            "text": { "type": "string" }
+                                            "text": { "type": "string" },
+                                            "columns": {
+                                                "type": "array",
+                                                "items": {
+                                                    "type": "object",
+                                                    "properties": {
+                                                        "start": { "type": "integer" },
+                                                        "end": { "type": "integer" }
+                                                    }
+                                                }
+                                            }
                                         }
                                     }
                                 }
                             }
                         }
-                    }
+                    },
+                    "continuation": { "type": "string", "description": "Present when more matches exist" },
+                    "skipped_large_files": { "type": "integer", "description": "Files skipped for exceeding max_file_bytes" }
                 }
             }),
             streaming: false,
             side_effects: false,
+            needs_approval: false,
             timeout_ms: Some(10000),
         });
 
@@ -109,23 +156,83 @@ impl ToolRegistry {
                     "path": { "type": "string", "description": "File path to write" },
                     "contents": { "type": "string", "description": "File contents" },
                     "create_if_missing": { "type": "boolean", "default": true, "description": "Create file and parent directories if they don't exist (default: true)" },
-                    "overwrite": { "type": "boolean", "default": false, "description": "Overwrite existing file (default: false)" }
+                    "overwrite": { "type": "boolean", "default": false, "description": "Overwrite existing file (default: false)" },
+                    "max_write_bytes": { "type": "integer", "minimum": 0, "description": "Largest write accepted (default: 5 MiB); raise only for intentionally large files" }
                 },
                 "required": ["path", "contents"]
             }),
             output_schema: json!({
                 "type": "object",
                 "properties": {
-                    "bytes_written": { "type": "integer" }
+                    "bytes_written": { "type": "integer" },
+                    "overwrite": {
+                        "type": "object",
+                        "description": "Present when an existing file with different contents was replaced",
+                        "properties": {
+                            "previous_lines": { "type": "integer" },
+                            "new_lines": { "type": "integer" },
+                            "lines_added": { "type": "integer" },
+                            "lines_removed": { "type": "integer" }
+                        }
+                    }
                 },
                 "required": ["bytes_written"]
             }),
             streaming: false,
             side_effects: true,
+            needs_approval: true,
             timeout_ms: Some(5000),
         });
 
-    
+        // fs.apply_patch
+        self.specs.insert(ToolName::FsApplyPatch, ToolSpec {
+            name: ToolName::FsApplyPatch,
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "dry_run": { "type": "boolean", "default": false, "description": "Plan the edits without writing anything (default: false)" },
+                    "ops": {
+                        "type": "array",
+                        "description": "Edit operations applied in order; all succeed or nothing is written",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "type": {
+                                    "type": "string",
+                                    "enum": ["set_file", "replace_once", "insert_before", "insert_after", "delete_file", "rename_file", "create_empty", "create_dir"]
+                                },
+                                "path": { "type": "string", "description": "Target file path (directory path for create_dir)" },
+                                "contents": { "type": "string", "description": "New file contents (set_file)" },
+                                "find": { "type": "string", "description": "Text that must occur exactly once (replace_once)" },
+                                "replace": { "type": "string", "description": "Replacement text (replace_once)" },
+                                "anchor": { "type": "string", "description": "Text that must occur exactly once (insert_before/insert_after)" },
+                                "insert": { "type": "string", "description": "Text to insert (insert_before/insert_after)" },
+                                "to": { "type": "string", "description": "Destination path (rename_file)" }
+                            },
+                            "required": ["type", "path"]
+                        }
+                    }
+                },
+                "required": ["ops"]
+            }),
+            output_schema: json!({
+                "type": "object",
+                "properties": {
+                    "success": { "type": "boolean" },
+                    "rejected_hunks": {
+                        "type": "array",
+                        "items": { "type": "string" }
+                    },
+                    "summary": { "type": "string" },
+                    "diff": { "type": "string", "description": "Unified diff of the changes, present when any file content changed" }
+                },
+                "required": ["success", "summary"]
+            }),
+            streaming: false,
+            side_effects: true,
+            needs_approval: true,
+            timeout_ms: Some(10000),
+        });
 
         // fs.set_file
         self.specs.insert(ToolName::FsSetFile, ToolSpec {
@@ -148,6 +255,7 @@ impl ToolRegistry {
             }),
             streaming: false,
             side_effects: true,
+            needs_approval: true,
             timeout_ms: Some(5000),
         });
 
@@ -172,6 +280,7 @@ impl ToolRegistry {
             }),
             streaming: false,
             side_effects: true,
+            needs_approval: true,
             timeout_ms: Some(5000),
         });
 
@@ -196,6 +305,7 @@ impl ToolRegistry {
             }),
             streaming: false,
             side_effects: true,
+            needs_approval: true,
             timeout_ms: Some(5000),
         });
 
@@ -220,6 +330,7 @@ impl ToolRegistry {
             }),
             streaming: false,
             side_effects: true,
+            needs_approval: true,
             timeout_ms: Some(5000),
         });
 
@@ -229,19 +340,21 @@ impl ToolRegistry {
             input_schema: json!({
                 "type": "object",
                 "properties": {
-                    "path": { "type": "string", "description": "File path to delete" }
+                    "path": { "type": "string", "description": "File path to delete (moved to the session trash unless GROK_HARD_DELETE is set)" }
                 },
                 "required": ["path"]
             }),
             output_schema: json!({
                 "type": "object",
                 "properties": {
-                    "success": { "type": "boolean" }
+                    "success": { "type": "boolean" },
+                    "trash_path": { "type": "string", "description": "Where the deleted file was moved, if kept" }
                 },
                 "required": ["success"]
             }),
             streaming: false,
             side_effects: true,
+            needs_approval: true,
             timeout_ms: Some(5000),
         });
 
@@ -265,6 +378,7 @@ impl ToolRegistry {
             }),
             streaming: false,
             side_effects: true,
+            needs_approval: true,
             timeout_ms: Some(5000),
         });
 
@@ -276,7 +390,8 @@ impl ToolRegistry {
                 "properties": {
                     "pattern": { "type": "string", "description": "File or directory name pattern to search for" },
                     "base_path": { "type": "string", "description": "Base directory to search from (default: current directory)" },
-                    "fuzzy": { "type": "boolean", "description": "Enable fuzzy matching (default: true)" },
+                    "fuzzy": { "type": "boolean", "description": "Enable fuzzy matching (default: true, false when regex is set)" },
+                    "regex": { "type": "boolean", "description": "Treat pattern as a regex matched against file names, or full paths when it contains '/' (e.g. ^test_.*\\.rs$); cannot be combined with fuzzy (default: false)" },
                     "case_sensitive": { "type": "boolean", "description": "Case sensitive search (default: false)" },
                     "file_type": { 
                         "type": "string", 
@@ -287,8 +402,9 @@ impl ToolRegistry {
                     "ignore_patterns": {
                         "type": "array",
                         "items": { "type": "string" },
-                        "description": "Gitignore-style patterns to exclude from search"
-                    }
+                        "description": "Gitignore-style patterns relative to base_path to exclude (e.g. *.tmp, target/, /build); ignored directories are skipped entirely"
+                    },
+                    "continue_from": { "type": "string", "description": "continuation token from a previous call, to fetch the next page" }
                 },
                 "required": ["pattern"]
             }),
@@ -300,20 +416,51 @@ impl ToolRegistry {
                         "items": {
                             "type": "object",
                             "properties": {
-                                "path": { "type": "string" },
+                                "path": { "type": "string", "description": "Relative to the workspace root (or as given for an absolute base_path), without a leading ./" },
                                 "score": { "type": "number" },
                                 "match_type": { "type": "string" }
                             }
                         }
                     },
-                    "search_time_ms": { "type": "integer" }
+                    "search_time_ms": { "type": "integer" },
+                    "continuation": { "type": "string", "description": "Present when more matches exist" }
                 },
                 "required": ["matches", "search_time_ms"]
             }),
             streaming: false,
             side_effects: false,
+            needs_approval: false,
             timeout_ms: Some(10000),
         });
+
+        // fs.tree
+        self.specs.insert(ToolName::FsTree, ToolSpec {
+            name: ToolName::FsTree,
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string", "description": "Root directory (default: current directory)" },
+                    "max_depth": { "type": "integer", "minimum": 1, "description": "Maximum depth to expand (default: 3)" },
+                    "max_nodes": { "type": "integer", "minimum": 1, "description": "Maximum entries to list (default: 500)" }
+                },
+                "required": []
+            }),
+            output_schema: json!({
+                "type": "object",
+                "properties": {
+                    "tree": { "type": "string" },
+                    "total_dirs": { "type": "integer" },
+                    "total_files": { "type": "integer" },
+                    "truncated": { "type": "boolean" }
+                },
+                "required": ["tree", "total_dirs", "total_files", "truncated"]
+            }),
+            streaming: false,
+            side_effects: false,
+            needs_approval: false,
+            timeout_ms: Some(10000),
+        });
+
         // code.symbols
         self.specs.insert(ToolName::CodeSymbols, ToolSpec {
             name: ToolName::CodeSymbols,
@@ -356,9 +503,83 @@ impl ToolRegistry {
             }),
             streaming: false,
             side_effects: false,
+            needs_approval: false,
             timeout_ms: Some(5000),
         });
 
+        // code.test
+        self.specs.insert(ToolName::CodeTest, ToolSpec {
+            name: ToolName::CodeTest,
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string", "description": "Project root containing Cargo.toml, package.json, go.mod or a pytest config (default: current directory)" },
+                    "filter": { "type": "string", "description": "Only run tests whose names match this filter" },
+                    "timeout_ms": { "type": "integer", "description": "Timeout for the whole test run in milliseconds (default: 300000)" }
+                }
+            }),
+            output_schema: json!({
+                "type": "object",
+                "properties": {
+                    "project_type": { "type": "string", "enum": ["cargo", "npm", "pytest", "go"] },
+                    "command": { "type": "array", "items": { "type": "string" } },
+                    "exit_code": { "type": "integer" },
+                    "duration_ms": { "type": "integer" },
+                    "passed": { "type": ["integer", "null"] },
+                    "failed": { "type": ["integer", "null"] },
+                    "failing_tests": { "type": "array", "items": { "type": "string" } },
+                    "raw_output": { "type": "string", "description": "Combined output, present only when the counts could not be parsed" }
+                },
+                "required": ["project_type", "command", "exit_code", "duration_ms", "failing_tests"]
+            }),
+            streaming: true,
+            side_effects: true,
+            needs_approval: true,
+            timeout_ms: Some(300000),
+        });
+
+        // code.build
+        self.specs.insert(ToolName::CodeBuild, ToolSpec {
+            name: ToolName::CodeBuild,
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string", "description": "Project root containing Cargo.toml, package.json or go.mod (default: current directory)" },
+                    "timeout_ms": { "type": "integer", "description": "Timeout for the build in milliseconds (default: 300000)" }
+                }
+            }),
+            output_schema: json!({
+                "type": "object",
+                "properties": {
+                    "project_type": { "type": "string", "enum": ["cargo", "npm", "go"] },
+                    "command": { "type": "array", "items": { "type": "string" } },
+                    "exit_code": { "type": "integer" },
+                    "duration_ms": { "type": "integer" },
+                    "success": { "type": "boolean" },
+                    "diagnostics": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "file": { "type": "string" },
+                                "line": { "type": "integer" },
+                                "column": { "type": "integer" },
+                                "severity": { "type": "string" },
+                                "message": { "type": "string" }
+                            },
+                            "required": ["file", "line", "column", "severity", "message"]
+                        }
+                    },
+                    "raw_output": { "type": "string", "description": "Combined output, present only when a failed build produced no parseable diagnostics" }
+                },
+                "required": ["project_type", "command", "exit_code", "duration_ms", "success", "diagnostics"]
+            }),
+            streaming: true,
+            side_effects: true,
+            needs_approval: true,
+            timeout_ms: Some(300000),
+        });
+
         // shell.exec
         self.specs.insert(ToolName::ShellExec, ToolSpec {
             name: ToolName::ShellExec,
@@ -383,7 +604,9 @@ impl ToolRegistry {
                     },
                     "timeout_ms": { "type": "integer", "description": "Timeout in milliseconds" },
                     "with_escalated_permissions": { "type": "boolean", "description": "Run with elevated permissions" },
-                    "justification": { "type": "string", "description": "Justification for escalated permissions" }
+                    "justification": { "type": "string", "description": "Justification for escalated permissions" },
+                    "expand_env": { "type": "boolean", "description": "Expand $VAR and ${VAR} in arguments (no shell is involved otherwise)" },
+                    "undefined_env_empty": { "type": "boolean", "description": "With expand_env, undefined variables become empty instead of staying literal" }
                 },
                 "required": ["command"]
             }),
@@ -399,6 +622,7 @@ impl ToolRegistry {
             }),
             streaming: true,
             side_effects: true,
+            needs_approval: true,
             timeout_ms: Some(30000),
         });
 
@@ -422,6 +646,12 @@ impl ToolRegistry {
                         "maximum": 500,
                         "description": "Maximum number of files to analyze (default: 200)" 
                     },
+                    "max_return_files": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": 50,
+                        "description": "Maximum number of relevant files to return, most relevant first (default: 10)"
+                    },
                     "include_extensions": {
                         "type": "array",
                         "items": { "type": "string" },
@@ -460,8 +690,38 @@ impl ToolRegistry {
             }),
             streaming: false,
             side_effects: false,
+            needs_approval: false,
             timeout_ms: Some(60000), // 60 seconds for LLM call
         });
+
+        // http.fetch
+        self.specs.insert(ToolName::HttpFetch, ToolSpec {
+            name: ToolName::HttpFetch,
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "url": { "type": "string", "description": "http or https URL to GET; local and private addresses are refused" },
+                    "max_bytes": { "type": "integer", "minimum": 1, "description": "Most bytes of the response body to download (default: 524288)" }
+                },
+                "required": ["url"]
+            }),
+            output_schema: json!({
+                "type": "object",
+                "properties": {
+                    "url": { "type": "string", "description": "Final URL after redirects" },
+                    "status": { "type": "integer" },
+                    "content_type": { "type": ["string", "null"] },
+                    "body": { "type": "string", "description": "Response text, with HTML converted to plain text" },
+                    "bytes_read": { "type": "integer" },
+                    "truncated": { "type": "boolean" }
+                },
+                "required": ["url", "status", "content_type", "body", "bytes_read", "truncated"]
+            }),
+            streaming: false,
+            side_effects: false,
+            needs_approval: true,
+            timeout_ms: Some(30000),
+        });
     }
 
     /// Get all tool specifications
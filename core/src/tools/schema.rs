@@ -0,0 +1,100 @@
+//! Minimal JSON Schema checking for tool outputs
+//!
+//! Only the subset the registry uses is understood: `type` (single or list),
+//! `properties`, `required`, `items` and `enum`. Anything else is ignored, so the
+//! check errs on the side of accepting a value.
+
+use crate::events::ToolName;
+use crate::tools::ToolRegistry;
+use serde_json::Value;
+
+/// Validate a tool's returned value against its registry `output_schema`
+pub(crate) fn validate_tool_output(tool: &ToolName, value: &Value) -> Result<(), String> {
+    // Oversized results are replaced by a generic envelope; it has its own shape
+    if is_truncation_envelope(value) {
+        return Ok(());
+    }
+    let registry = ToolRegistry::shared();
+    let Some(spec) = registry.get_spec(tool) else {
+        return Ok(());
+    };
+    validate(value, &spec.output_schema, "$")
+        .map_err(|e| format!("{} output does not match its schema: {}", tool.wire_name(), e))
+}
+
+/// Validate `value` against `schema`, reporting the first mismatch with a JSON path
+pub(crate) fn validate(value: &Value, schema: &Value, path: &str) -> Result<(), String> {
+    if let Some(expected) = schema.get("type") {
+        let allowed: Vec<&str> = match expected {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(types) => types.iter().filter_map(|t| t.as_str()).collect(),
+            _ => Vec::new(),
+        };
+        if !allowed.is_empty() && !allowed.iter().any(|t| matches_type(value, t)) {
+            return Err(format!("{}: expected {}, found {}", path, allowed.join(" | "), type_name(value)));
+        }
+    }
+
+    if let Some(Value::Array(options)) = schema.get("enum") {
+        if !options.contains(value) {
+            return Err(format!("{}: {} is not one of the allowed values", path, value));
+        }
+    }
+
+    if let Value::Object(map) = value {
+        if let Some(Value::Array(required)) = schema.get("required") {
+            for key in required.iter().filter_map(|k| k.as_str()) {
+                if !map.contains_key(key) {
+                    return Err(format!("{}: missing required field '{}'", path, key));
+                }
+            }
+        }
+        if let Some(Value::Object(properties)) = schema.get("properties") {
+            for (key, property_schema) in properties {
+                // Optional fields are serialized as null when unset
+                match map.get(key) {
+                    Some(Value::Null) | None => {}
+                    Some(field) => validate(field, property_schema, &format!("{}.{}", path, key))?,
+                }
+            }
+        }
+    }
+
+    if let (Value::Array(items), Some(item_schema)) = (value, schema.get("items")) {
+        for (i, item) in items.iter().enumerate() {
+            validate(item, item_schema, &format!("{}[{}]", path, i))?;
+        }
+    }
+
+    Ok(())
+}
+
+fn is_truncation_envelope(value: &Value) -> bool {
+    value.get("truncated") == Some(&Value::Bool(true)) && value.get("original_size_bytes").is_some()
+}
+
+fn matches_type(value: &Value, expected: &str) -> bool {
+    match expected {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "null" => value.is_null(),
+        // Unknown type keywords are not ours to reject
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
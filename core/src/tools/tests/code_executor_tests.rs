@@ -204,6 +204,111 @@ if __name__ == "__main__":
     assert_eq!(count_progress_events(&events), 1);
 }
 
+/// Run code.symbols over `content` and return (name, scope) pairs
+async fn symbol_scopes(file_name: &str, content: &str) -> Vec<(String, Option<String>)> {
+    let temp_dir = create_temp_dir().await;
+    let file_path = create_temp_file(temp_dir.path(), file_name, content).await;
+
+    let (sender, _receiver) = setup_event_bus();
+    let executor = CodeExecutor::new(sender, 1024 * 1024);
+    let args = json!({ "path": file_path.to_string_lossy() });
+
+    let result = executor.execute_symbols_with_result("test_id".to_string(), args).await;
+    let symbols_result: CodeSymbolsResult = serde_json::from_value(result.unwrap()).unwrap();
+    symbols_result.symbols.into_iter().map(|s| (s.name, s.scope)).collect()
+}
+
+#[tokio::test]
+async fn test_code_symbols_rust_scopes() {
+    let scopes = symbol_scopes("scopes.rs", r#"
+pub struct Foo;
+
+impl Foo {
+    pub fn bar(&self) -> char {
+        let brace = '{';
+        brace
+    }
+}
+
+impl<T: Clone> std::fmt::Display for Wrapper<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{{}}")
+    }
+}
+
+fn bar() {}
+"#).await;
+
+    assert!(scopes.contains(&("Foo".to_string(), None)));
+    assert!(scopes.contains(&("bar".to_string(), Some("Foo".to_string()))));
+    assert!(scopes.contains(&("fmt".to_string(), Some("Wrapper".to_string()))));
+    // The free function after both impl blocks has no parent
+    assert_eq!(scopes.last(), Some(&("bar".to_string(), None)));
+}
+
+#[tokio::test]
+async fn test_code_symbols_rust_constants_and_types() {
+    let temp_dir = create_temp_dir().await;
+    let rust_content = r#"
+pub const MAX_ITEMS: usize = 10;
+static mut COUNTER: u32 = 0;
+pub(crate) type Result<T> = std::result::Result<T, Error>;
+pub const fn limit() -> usize { MAX_ITEMS }
+"#;
+    let file_path = create_temp_file(temp_dir.path(), "items.rs", rust_content).await;
+
+    let (sender, _receiver) = setup_event_bus();
+    let executor = CodeExecutor::new(sender, 1024 * 1024);
+    let args = json!({ "path": file_path.to_string_lossy() });
+
+    let result = executor.execute_symbols_with_result("test_id".to_string(), args).await;
+    let symbols_result: CodeSymbolsResult = serde_json::from_value(result.unwrap()).unwrap();
+    let find = |name: &str| symbols_result.symbols.iter().find(|s| s.name == name).unwrap();
+
+    let constant = find("MAX_ITEMS");
+    assert_eq!(constant.symbol_type, "constant");
+    assert_eq!(constant.visibility.as_deref(), Some("public"));
+
+    let counter = find("COUNTER");
+    assert_eq!(counter.symbol_type, "static");
+    assert_eq!(counter.visibility.as_deref(), Some("private"));
+
+    let alias = find("Result");
+    assert_eq!(alias.symbol_type, "type");
+    assert_eq!(alias.visibility.as_deref(), Some("restricted"));
+
+    // A const fn is only reported as a function
+    let limits: Vec<&str> = symbols_result.symbols.iter()
+        .filter(|s| s.name == "limit")
+        .map(|s| s.symbol_type.as_str())
+        .collect();
+    assert_eq!(limits, vec!["function"]);
+}
+
+#[tokio::test]
+async fn test_code_symbols_python_scopes() {
+    let scopes = symbol_scopes("scopes.py", r#"
+class Greeter:
+    def greet(self):
+
+        return "hi"
+
+    # a comment at class level
+    def wave(self):
+        pass
+
+def greet():
+    pass
+"#).await;
+
+    assert_eq!(scopes, vec![
+        ("Greeter".to_string(), None),
+        ("greet".to_string(), Some("Greeter".to_string())),
+        ("wave".to_string(), Some("Greeter".to_string())),
+        ("greet".to_string(), None),
+    ]);
+}
+
 #[tokio::test]
 async fn test_code_symbols_java_file() {
     let temp_dir = create_temp_dir().await;
@@ -487,3 +592,104 @@ async fn test_language_detection_from_extension() {
         let _events = collect_events(&mut receiver, 2).await;
     }
 }
+
+#[tokio::test]
+async fn test_code_test_cargo_project_counts() {
+    let temp_dir = create_temp_dir().await;
+    create_temp_file(temp_dir.path(), "Cargo.toml", r#"[package]
+name = "code_test_fixture"
+version = "0.1.0"
+edition = "2021"
+
+[workspace]
+"#).await;
+    std::fs::create_dir(temp_dir.path().join("src")).unwrap();
+    create_temp_file(temp_dir.path(), "src/lib.rs", r#"
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn passes() {
+        assert_eq!(1 + 1, 2);
+    }
+
+    #[test]
+    fn fails() {
+        assert_eq!(1 + 1, 3);
+    }
+}
+"#).await;
+
+    let (sender, _receiver) = setup_event_bus();
+    let executor = CodeExecutor::new(sender, 1024 * 1024);
+
+    let args = json!({
+        "path": temp_dir.path().to_string_lossy()
+    });
+
+    let result = executor.execute_test_with_result("test_id".to_string(), args).await;
+    assert!(result.is_ok(), "code.test failed: {:?}", result);
+
+    let test_result: CodeTestResult = serde_json::from_value(result.unwrap()).unwrap();
+    assert_eq!(test_result.project_type, "cargo");
+    assert_ne!(test_result.exit_code, 0);
+    assert_eq!(test_result.passed, Some(1));
+    assert_eq!(test_result.failed, Some(1));
+    assert_eq!(test_result.failing_tests, vec!["tests::fails".to_string()]);
+    assert!(test_result.raw_output.is_none());
+}
+
+#[tokio::test]
+async fn test_code_test_unknown_project() {
+    let temp_dir = create_temp_dir().await;
+    let (sender, _receiver) = setup_event_bus();
+    let executor = CodeExecutor::new(sender, 1024 * 1024);
+
+    let args = json!({
+        "path": temp_dir.path().to_string_lossy()
+    });
+
+    let result = executor.execute_test_with_result("test_id".to_string(), args).await;
+    assert!(result.unwrap_err().contains("No Cargo.toml"));
+}
+
+#[tokio::test]
+async fn test_code_build_reports_cargo_diagnostics() {
+    let temp_dir = create_temp_dir().await;
+    create_temp_file(temp_dir.path(), "Cargo.toml", r#"[package]
+name = "code_build_fixture"
+version = "0.1.0"
+edition = "2021"
+
+[workspace]
+"#).await;
+    std::fs::create_dir(temp_dir.path().join("src")).unwrap();
+    create_temp_file(temp_dir.path(), "src/lib.rs", r#"pub fn answer() -> u32 {
+    "forty-two"
+}
+"#).await;
+
+    let (sender, _receiver) = setup_event_bus();
+    let executor = CodeExecutor::new(sender, 1024 * 1024);
+
+    let args = json!({
+        "path": temp_dir.path().to_string_lossy()
+    });
+
+    let result = executor.execute_build_with_result("test_id".to_string(), args).await;
+    assert!(result.is_ok(), "code.build failed: {:?}", result);
+
+    let build_result: CodeBuildResult = serde_json::from_value(result.unwrap()).unwrap();
+    assert_eq!(build_result.project_type, "cargo");
+    assert!(!build_result.success);
+
+    let error = build_result
+        .diagnostics
+        .iter()
+        .find(|d| d.severity == "error")
+        .expect("expected a compile error diagnostic");
+    assert_eq!(error.file, "src/lib.rs");
+    assert_eq!(error.line, 2);
+    assert_eq!(error.column, 5);
+    assert!(error.message.contains("mismatched types"));
+    assert!(build_result.raw_output.is_none());
+}
@@ -31,6 +31,65 @@ async fn test_fs_read_success() {
     assert!(find_tool_result_event(&events).is_some());
 }
 
+#[tokio::test]
+async fn test_fs_read_summary_only() {
+    let temp_dir = create_temp_dir().await;
+    let test_content = "line one\nline two\nline three";
+    let file_path = create_temp_file(temp_dir.path(), "summary.txt", test_content).await;
+
+    let (sender, _receiver) = setup_event_bus();
+    let executor = FsExecutor::new(sender, 1024 * 1024);
+
+    let args = json!({
+        "path": file_path.to_string_lossy(),
+        "summary_only": true
+    });
+
+    let result = executor.execute_read_with_result("test_id".to_string(), args).await;
+    let fs_result: FsReadResult = serde_json::from_value(result.unwrap()).unwrap();
+
+    assert!(fs_result.contents.is_empty());
+    let summary = fs_result.summary.expect("summary should be present");
+    assert_eq!(summary.line_count, 3);
+    assert_eq!(summary.byte_size, test_content.len() as u64);
+    assert!(!summary.is_binary);
+}
+
+#[tokio::test]
+async fn test_fs_read_empty_file() {
+    let temp_dir = create_temp_dir().await;
+    let file_path = create_temp_file(temp_dir.path(), "empty.txt", "").await;
+
+    let (sender, _receiver) = setup_event_bus();
+    let executor = FsExecutor::new(sender, 1024 * 1024);
+
+    let args = json!({ "path": file_path.to_string_lossy() });
+    let result = executor.execute_read_with_result("test_id".to_string(), args).await;
+    let fs_result: FsReadResult = serde_json::from_value(result.unwrap()).unwrap();
+
+    assert!(fs_result.contents.is_empty());
+    assert!(fs_result.is_empty);
+    assert!(!fs_result.whitespace_only);
+}
+
+#[tokio::test]
+async fn test_fs_read_whitespace_only_file() {
+    let temp_dir = create_temp_dir().await;
+    let file_path = create_temp_file(temp_dir.path(), "blank.txt", "  \n\t\n").await;
+
+    let (sender, _receiver) = setup_event_bus();
+    let executor = FsExecutor::new(sender, 1024 * 1024);
+
+    let args = json!({ "path": file_path.to_string_lossy() });
+    let result = executor.execute_read_with_result("test_id".to_string(), args).await;
+    let fs_result: FsReadResult = serde_json::from_value(result.unwrap()).unwrap();
+
+    // The whitespace is returned untouched, just flagged
+    assert_eq!(fs_result.contents, "  \n\t\n");
+    assert!(!fs_result.is_empty);
+    assert!(fs_result.whitespace_only);
+}
+
 #[tokio::test]
 async fn test_fs_read_file_not_found() {
     let (sender, mut receiver) = setup_event_bus();
@@ -76,6 +135,89 @@ async fn test_fs_read_with_range() {
     assert_eq!(count_progress_events(&events), 1);
 }
 
+#[tokio::test]
+async fn test_fs_read_max_bytes_override_lifts_default_cut() {
+    let temp_dir = create_temp_dir().await;
+    let big = "x".repeat(1024 * 1024 + 500_000);
+    let file_path = create_temp_file(temp_dir.path(), "big.txt", &big).await;
+
+    let (sender, _receiver) = setup_event_bus();
+    let read = |executor: FsExecutor, max_bytes: Option<u64>| {
+        let path = file_path.to_string_lossy().to_string();
+        async move {
+            let args = json!({ "path": path, "max_bytes": max_bytes });
+            let value = executor.execute_read_with_result("test_id".to_string(), args).await.unwrap();
+            serde_json::from_value::<FsReadResult>(value).unwrap()
+        }
+    };
+
+    // Without the override the 1 MiB cut applies
+    let result = read(FsExecutor::new(sender.clone(), 4 * 1024 * 1024), None).await;
+    assert_eq!(result.contents.len(), 1024 * 1024);
+    assert!(result.truncated);
+
+    // 0 lifts it when the output cap has room
+    let result = read(FsExecutor::new(sender.clone(), 4 * 1024 * 1024), Some(0)).await;
+    assert_eq!(result.contents.len(), big.len());
+    assert!(!result.truncated);
+
+    // The output cap still bounds the contents
+    let cap = 1024 * 1024 + 100_000;
+    let result = read(FsExecutor::new(sender, cap), Some(0)).await;
+    assert!(result.truncated);
+    assert!(result.contents.len() > 1024 * 1024 && result.contents.len() < cap);
+}
+
+#[tokio::test]
+async fn test_fs_read_max_bytes_override_fits_escaped_output() {
+    let temp_dir = create_temp_dir().await;
+    let content = "\"quoted\"\t\\\n".repeat(20_000);
+    let file_path = create_temp_file(temp_dir.path(), "escapes.txt", &content).await;
+
+    let (sender, _receiver) = setup_event_bus();
+    let cap = 64 * 1024;
+    let executor = FsExecutor::new(sender, cap);
+    let args = json!({ "path": file_path.to_string_lossy(), "max_bytes": 0 });
+    let value = executor.execute_read_with_result("test_id".to_string(), args).await.unwrap();
+
+    // Escaping nearly doubles the contents, yet the result still fits instead of
+    // being replaced by the oversized-output stub
+    assert!(serde_json::to_string(&value).unwrap().len() <= cap);
+    let result: FsReadResult = serde_json::from_value(value).unwrap();
+    assert!(result.truncated);
+    assert!(!result.contents.is_empty());
+    assert!(content.starts_with(&result.contents));
+}
+
+#[tokio::test]
+async fn test_fs_read_range_inside_multibyte_char() {
+    let temp_dir = create_temp_dir().await;
+    // "é" is two bytes, "🦀" four: byte 2 is inside "é", byte 6 inside "🦀"
+    let file_path = create_temp_file(temp_dir.path(), "utf8.txt", "aé🦀z").await;
+
+    let (sender, _receiver) = setup_event_bus();
+    let executor = FsExecutor::new(sender, 1024 * 1024);
+
+    let args = json!({
+        "path": file_path.to_string_lossy(),
+        "range": { "start": 2, "end": 6 }
+    });
+    let result = executor.execute_read_with_result("test_id".to_string(), args).await.unwrap();
+    let fs_result: FsReadResult = serde_json::from_value(result).unwrap();
+    assert_eq!(fs_result.contents, "é");
+    assert_eq!(fs_result.adjusted_range, Some(1..3));
+
+    // Cuts that already sit on boundaries are not reported
+    let args = json!({
+        "path": file_path.to_string_lossy(),
+        "range": { "start": 1, "end": 7 }
+    });
+    let result = executor.execute_read_with_result("test_id".to_string(), args).await.unwrap();
+    let fs_result: FsReadResult = serde_json::from_value(result).unwrap();
+    assert_eq!(fs_result.contents, "é🦀");
+    assert!(fs_result.adjusted_range.is_none());
+}
+
 #[tokio::test]
 async fn test_fs_write_success() {
     let temp_dir = create_temp_dir().await;
@@ -163,107 +305,823 @@ async fn test_fs_write_with_overwrite() {
 }
 
 #[tokio::test]
-async fn test_fs_write_create_directories() {
+async fn test_fs_write_overwrite_reports_line_changes() {
+    let temp_dir = create_temp_dir().await;
+    let original: String = (1..=200).map(|i| format!("line {}\n", i)).collect();
+    let file_path = create_temp_file(temp_dir.path(), "big.txt", &original).await;
+
+    let (sender, _receiver) = setup_event_bus();
+    let executor = FsExecutor::new(sender, 1024 * 1024);
+
+    let new_content = "line 1\nline 2\nshort\nfile\nnow\n";
+    let args = json!({
+        "path": file_path.to_string_lossy(),
+        "contents": new_content,
+        "create_if_missing": true,
+        "overwrite": true
+    });
+    let result = executor.execute_write_with_result("test_id".to_string(), args).await.unwrap();
+    let fs_result: FsWriteResult = serde_json::from_value(result).unwrap();
+
+    let stats = fs_result.overwrite.expect("overwrite stats should be reported");
+    assert_eq!(stats.previous_lines, 200);
+    assert_eq!(stats.new_lines, 5);
+    assert_eq!(stats.lines_added, 3);
+    assert_eq!(stats.lines_removed, 198);
+    assert_eq!(stats.describe(), "Overwrote 200 lines with 5 (+3 -198)");
+
+    // Writing the same contents again changes nothing, so there is nothing to warn about
+    let args = json!({
+        "path": file_path.to_string_lossy(),
+        "contents": new_content,
+        "create_if_missing": true,
+        "overwrite": true
+    });
+    let result = executor.execute_write_with_result("test_id".to_string(), args).await.unwrap();
+    let fs_result: FsWriteResult = serde_json::from_value(result).unwrap();
+    assert!(fs_result.overwrite.is_none());
+}
+
+#[tokio::test]
+async fn test_fs_write_rejects_writes_over_limit() {
+    let temp_dir = create_temp_dir().await;
+    let file_path = temp_dir.path().join("out.txt");
+
+    let (sender, _receiver) = setup_event_bus();
+    let executor = FsExecutor::new(sender, 1024 * 1024);
+
+    let args = json!({
+        "path": file_path.to_string_lossy(),
+        "contents": "x".repeat(64),
+        "max_write_bytes": 32
+    });
+    let err = executor.execute_write_with_result("test_id".to_string(), args).await.unwrap_err();
+    assert!(err.contains("Refusing to write 64 bytes"), "{}", err);
+    assert!(err.contains("32-byte write limit"), "{}", err);
+    assert!(!file_path.exists());
+
+    // Within the limit the write goes through
+    let args = json!({
+        "path": file_path.to_string_lossy(),
+        "contents": "x".repeat(32),
+        "max_write_bytes": 32
+    });
+    let result = executor.execute_write_with_result("test_id".to_string(), args).await.unwrap();
+    let fs_result: FsWriteResult = serde_json::from_value(result).unwrap();
+    assert_eq!(fs_result.bytes_written, 32);
+    assert_eq!(tokio::fs::read_to_string(&file_path).await.unwrap().len(), 32);
+}
+
+#[tokio::test]
+async fn test_fs_write_create_directories() {
+    let temp_dir = create_temp_dir().await;
+    let nested_path = temp_dir.path().join("some").join("nested").join("dirs").join("file.txt");
+    
+    let (sender, mut receiver) = setup_event_bus();
+    let executor = FsExecutor::new(sender, 1024 * 1024);
+    
+    let args = json!({
+        "path": nested_path.to_string_lossy(),
+        "contents": "nested file content",
+        "create_if_missing": true,
+        "overwrite": false
+    });
+    
+    let result = executor.execute_write_with_result("test_id".to_string(), args).await;
+    assert!(result.is_ok());
+    
+    // Verify directories were created and file exists
+    assert!(nested_path.exists());
+    let content = tokio::fs::read_to_string(&nested_path).await.unwrap();
+    assert_eq!(content, "nested file content");
+    
+    let events = collect_events(&mut receiver, 2).await;
+    assert_eq!(count_progress_events(&events), 1);
+}
+
+#[tokio::test]
+async fn test_fs_search_success() {
+    // Create test files in current directory since fs_search searches from "."
+    let test_file = "temp_test_file1.rs";
+    let test_content = "fn search_success_marker() {\n    println!(\"Hello, world!\");\n}";
+    tokio::fs::write(test_file, test_content).await.expect("Failed to create test file");
+    
+    let (sender, mut receiver) = setup_event_bus();
+    let executor = FsExecutor::new(sender, 1024 * 1024);
+    
+    // Use a distinctive query so matches elsewhere in the crate can't exhaust max_results
+    let args = json!({
+        "query": "fn search_success_marker",
+        "globs": ["*.rs"],
+        "regex": false,
+        "case_insensitive": false,
+        "multiline": false,
+        "max_results": 10
+    });
+    
+    let result = executor.execute_search_with_result("test_id".to_string(), args).await;
+    assert!(result.is_ok());
+    
+    let result_value = result.unwrap();
+    let search_result: FsSearchResult = serde_json::from_value(result_value).unwrap();
+    
+    // Should find "fn" in the test file
+    assert!(!search_result.matches.is_empty());
+    let found_file = search_result.matches.iter()
+        .any(|m| m.path.contains(test_file));
+    assert!(found_file);
+    
+    // Cleanup
+    let _ = tokio::fs::remove_file(test_file).await;
+    
+    let events = collect_events(&mut receiver, 2).await;
+    assert_eq!(count_progress_events(&events), 1);
+}
+
+#[tokio::test]
+async fn test_fs_search_regex() {
+    // Create test file in current directory
+    let test_file = "temp_test_emails.txt";
+    let test_content = "Email: test@example.com\nAnother: user@domain.org\nNot an email: example.com";
+    tokio::fs::write(test_file, test_content).await.expect("Failed to create test file");
+    
+    let (sender, mut receiver) = setup_event_bus();
+    let executor = FsExecutor::new(sender, 1024 * 1024);
+    
+    let args = json!({
+        "query": r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Z|a-z]{2,}\b",
+        "regex": true,
+        "case_insensitive": false,
+        "multiline": false,
+        "max_results": 10
+    });
+    
+    let result = executor.execute_search_with_result("test_id".to_string(), args).await;
+    assert!(result.is_ok());
+    
+    let result_value = result.unwrap();
+    let search_result: FsSearchResult = serde_json::from_value(result_value).unwrap();
+    
+    // Should find email addresses
+    assert!(!search_result.matches.is_empty());
+    if !search_result.matches.is_empty() {
+        let file_match = &search_result.matches[0];
+        // Should have at least one email line
+        assert!(!file_match.lines.is_empty());
+    }
+    
+    // Cleanup
+    let _ = tokio::fs::remove_file(test_file).await;
+    
+    let events = collect_events(&mut receiver, 2).await;
+    assert_eq!(count_progress_events(&events), 1);
+}
+
+#[test]
+fn test_fs_search_skip_extensions_merge_override() {
+    use crate::tools::executors::fs::search_skip_extensions;
+
+    let defaults = search_skip_extensions([]);
+    assert!(defaults.contains(&"png".to_string()));
+
+    let exts = search_skip_extensions("grokskip, .OtherSkip,,".split(','));
+    assert_eq!(&exts[..defaults.len()], &defaults[..]);
+    assert_eq!(&exts[defaults.len()..], ["grokskip", "otherskip"]);
+}
+
+#[tokio::test]
+async fn test_fs_search_skips_configured_extensions() {
+    let temp_dir = create_temp_dir().await;
+    create_temp_file(temp_dir.path(), "kept.txt", "skip_ext_marker").await;
+    create_temp_file(temp_dir.path(), "dropped.grokskip", "skip_ext_marker").await;
+
+    let (sender, _receiver) = setup_event_bus();
+    let executor = FsExecutor::new(sender, 1024 * 1024)
+        .with_root(Some(temp_dir.path().to_path_buf()))
+        .with_search_skip_exts(vec![".GrokSkip".to_string()]);
+    let args = json!({
+        "query": "skip_ext_marker",
+        "regex": false,
+        "case_insensitive": false,
+        "multiline": false
+    });
+
+    let result = executor.execute_search_with_result("test_id".to_string(), args).await;
+    let result: FsSearchResult = serde_json::from_value(result.unwrap()).unwrap();
+    let paths: Vec<&str> = result.matches.iter().map(|m| m.path.as_str()).collect();
+    assert_eq!(paths, ["kept.txt"]);
+}
+
+#[tokio::test]
+async fn test_fs_search_and_find_skip_excludes() {
+    let temp_dir = create_temp_dir().await;
+    tokio::fs::create_dir_all(temp_dir.path().join("vendor/dep")).await.unwrap();
+    tokio::fs::create_dir_all(temp_dir.path().join("src")).await.unwrap();
+    create_temp_file(temp_dir.path(), "lib.rs", "exclude_marker").await;
+    create_temp_file(&temp_dir.path().join("src"), "app.min.js", "exclude_marker").await;
+    create_temp_file(&temp_dir.path().join("vendor/dep"), "lib.rs", "exclude_marker").await;
+
+    let (sender, _receiver) = setup_event_bus();
+    let executor = FsExecutor::new(sender, 1024 * 1024)
+        .with_root(Some(temp_dir.path().to_path_buf()))
+        .with_excludes(vec!["vendor/".to_string(), "*.min.js".to_string()]);
+
+    let search = json!({
+        "query": "exclude_marker",
+        "regex": false,
+        "case_insensitive": false,
+        "multiline": false
+    });
+    let result = executor.execute_search_with_result("test_id".to_string(), search).await;
+    let result: FsSearchResult = serde_json::from_value(result.unwrap()).unwrap();
+    let paths: Vec<&str> = result.matches.iter().map(|m| m.path.as_str()).collect();
+    assert_eq!(paths, ["lib.rs"]);
+
+    // Excludes stay relative to the root when the find starts deeper
+    let find = json!({ "pattern": "*", "base_path": "src" });
+    let result = executor.execute_find_with_result("test_id".to_string(), find).await;
+    let result: FsFindResult = serde_json::from_value(result.unwrap()).unwrap();
+    assert!(result.matches.is_empty(), "{:?}", result.matches);
+}
+
+#[tokio::test]
+async fn test_fs_search_skips_files_over_max_bytes() {
+    let large_file = "temp_max_bytes_test_large.txt";
+    let small_file = "temp_max_bytes_test_small.txt";
+    let large_content = format!("max_bytes_marker\n{}", "x".repeat(4096));
+    tokio::fs::write(large_file, &large_content).await.expect("Failed to create test file");
+    tokio::fs::write(small_file, "max_bytes_marker").await.expect("Failed to create test file");
+
+    let (sender, _receiver) = setup_event_bus();
+    let executor = FsExecutor::new(sender, 1024 * 1024);
+
+    let args = json!({
+        "query": "max_bytes_marker",
+        "regex": false,
+        "case_insensitive": false,
+        "multiline": false,
+        "globs": ["temp_max_bytes_test_*"],
+        "max_results": 10,
+        "max_file_bytes": 1024
+    });
+
+    let result = executor.execute_search_with_result("test_id".to_string(), args).await;
+    let _ = tokio::fs::remove_file(large_file).await;
+    let _ = tokio::fs::remove_file(small_file).await;
+
+    let search_result: FsSearchResult = serde_json::from_value(result.unwrap()).unwrap();
+    assert_eq!(search_result.skipped_large_files, 1);
+    assert!(search_result.matches.iter().any(|m| m.path.contains(small_file)));
+    assert!(!search_result.matches.iter().any(|m| m.path.contains(large_file)));
+}
+
+#[tokio::test]
+async fn test_fs_search_streams_large_file() {
+    let test_file = "temp_streaming_search_test.txt";
+    let mut content = Vec::new();
+    for i in 1..=50_000 {
+        if i == 3 {
+            // Invalid UTF-8 is skipped without losing the lines around it
+            content.extend_from_slice(b"streaming_marker \xff\xfe\n");
+        } else if i % 10_000 == 0 || i == 4 {
+            content.extend_from_slice(format!("streaming_marker at {}\n", i).as_bytes());
+        } else {
+            content.extend_from_slice(format!("filler line {}\n", i).as_bytes());
+        }
+    }
+    tokio::fs::write(test_file, &content).await.expect("Failed to create test file");
+
+    let (sender, _receiver) = setup_event_bus();
+    let executor = FsExecutor::new(sender, 1024 * 1024);
+    let search = |max_results: u64| json!({
+        "query": "streaming_marker",
+        "regex": false,
+        "case_insensitive": false,
+        "multiline": false,
+        "globs": ["temp_streaming_search_test.*"],
+        "max_results": max_results
+    });
+
+    let all = executor.execute_search_with_result("test_id".to_string(), search(100)).await;
+    let capped = executor.execute_search_with_result("test_id".to_string(), search(2)).await;
+    let _ = tokio::fs::remove_file(test_file).await;
+
+    let all: FsSearchResult = serde_json::from_value(all.unwrap()).unwrap();
+    let lines: Vec<u64> = all.matches[0].lines.iter().map(|l| l.ln).collect();
+    assert_eq!(lines, vec![4, 10_000, 20_000, 30_000, 40_000, 50_000]);
+    assert_eq!(all.matches[0].lines[1].text, "streaming_marker at 10000");
+    assert!(all.continuation.is_none());
+
+    let capped: FsSearchResult = serde_json::from_value(capped.unwrap()).unwrap();
+    let lines: Vec<u64> = capped.matches[0].lines.iter().map(|l| l.ln).collect();
+    assert_eq!(lines, vec![4, 10_000]);
+    assert!(capped.continuation.is_some());
+}
+
+#[tokio::test]
+async fn test_fs_search_rank_prefers_source_over_vendored() {
+    let temp_dir = create_temp_dir().await;
+    tokio::fs::create_dir_all(temp_dir.path().join("node_modules/dep")).await.unwrap();
+    tokio::fs::create_dir_all(temp_dir.path().join("web")).await.unwrap();
+    create_temp_file(&temp_dir.path().join("node_modules/dep"), "index.js", "rank_marker\n").await;
+    create_temp_file(&temp_dir.path().join("web"), "main.js", "rank_marker\nrank_marker\nrank_marker\n").await;
+
+    let (sender, _receiver) = setup_event_bus();
+    let executor = FsExecutor::new(sender, 1024 * 1024).with_root(Some(temp_dir.path().to_path_buf()));
+    let search = |rank: bool| json!({
+        "query": "rank_marker",
+        "regex": false,
+        "case_insensitive": false,
+        "multiline": false,
+        "rank": rank
+    });
+
+    let unranked = executor.execute_search_with_result("test_id".to_string(), search(false)).await;
+    let unranked: FsSearchResult = serde_json::from_value(unranked.unwrap()).unwrap();
+    assert_eq!(unranked.matches[0].path, "node_modules/dep/index.js");
+
+    let ranked = executor.execute_search_with_result("test_id".to_string(), search(true)).await;
+    let ranked: FsSearchResult = serde_json::from_value(ranked.unwrap()).unwrap();
+    let paths: Vec<&str> = ranked.matches.iter().map(|m| m.path.as_str()).collect();
+    assert_eq!(paths, vec!["web/main.js", "node_modules/dep/index.js"]);
+}
+
+#[tokio::test]
+async fn test_fs_search_rank_looks_past_max_results() {
+    let temp_dir = create_temp_dir().await;
+    for name in ["a.txt", "b.txt", "c.txt"] {
+        create_temp_file(temp_dir.path(), name, "rank_page_marker\n").await;
+    }
+    // Walked last, but the best match by far
+    tokio::fs::create_dir_all(temp_dir.path().join("src")).await.unwrap();
+    create_temp_file(&temp_dir.path().join("src"), "zz.rs", &"rank_page_marker\n".repeat(3)).await;
+
+    let (sender, _receiver) = setup_event_bus();
+    let executor = FsExecutor::new(sender, 1024 * 1024).with_root(Some(temp_dir.path().to_path_buf()));
+    let search = |rank: bool, continue_from: Option<String>| json!({
+        "query": "rank_page_marker",
+        "regex": false,
+        "case_insensitive": false,
+        "multiline": false,
+        "max_results": 2,
+        "rank": rank,
+        "continue_from": continue_from
+    });
+
+    let ranked = executor.execute_search_with_result("test_id".to_string(), search(true, None)).await;
+    let ranked: FsSearchResult = serde_json::from_value(ranked.unwrap()).unwrap();
+    assert_eq!(ranked.matches.len(), 1);
+    assert_eq!(ranked.matches[0].path, "src/zz.rs");
+    assert_eq!(ranked.matches[0].lines.len(), 2);
+
+    // The next ranked page carries on in ranked order
+    let token = ranked.continuation.expect("more ranked matches remain");
+    let next = executor.execute_search_with_result("test_id".to_string(), search(true, Some(token.clone()))).await;
+    let next: FsSearchResult = serde_json::from_value(next.unwrap()).unwrap();
+    let paths: Vec<&str> = next.matches.iter().map(|m| m.path.as_str()).collect();
+    assert_eq!(paths, vec!["src/zz.rs", "a.txt"]);
+
+    // A ranked cursor doesn't resume an unranked search
+    let mismatched = executor.execute_search_with_result("test_id".to_string(), search(false, Some(token))).await;
+    assert!(mismatched.unwrap_err().contains("different query"));
+}
+
+#[tokio::test]
+async fn test_fs_search_skips_binary_content() {
+    let test_file = "temp_binary_content_test.dat";
+    tokio::fs::write(test_file, b"binary_content_marker\0\x01\x02").await.expect("Failed to create test file");
+
+    let (sender, _receiver) = setup_event_bus();
+    let executor = FsExecutor::new(sender, 1024 * 1024);
+
+    let args = json!({
+        "query": "binary_content_marker",
+        "regex": false,
+        "case_insensitive": false,
+        "multiline": false,
+        "globs": ["temp_binary_content_test.*"],
+        "max_results": 10
+    });
+
+    let result = executor.execute_search_with_result("test_id".to_string(), args).await;
+    let _ = tokio::fs::remove_file(test_file).await;
+
+    let search_result: FsSearchResult = serde_json::from_value(result.unwrap()).unwrap();
+    assert!(search_result.matches.is_empty());
+}
+
+#[tokio::test]
+async fn test_fs_search_whole_word() {
+    let test_file = "temp_whole_word_test.txt";
+    let test_content = "latest wwtest\nwwtest here\nwwtesting\ncall(wwtest)";
+    tokio::fs::write(test_file, test_content).await.expect("Failed to create test file");
+
+    let (sender, _receiver) = setup_event_bus();
+    let executor = FsExecutor::new(sender, 1024 * 1024);
+
+    let search = |whole_word: bool| json!({
+        "query": "wwtest",
+        "globs": ["temp_whole_word_test.txt"],
+        "regex": false,
+        "case_insensitive": false,
+        "multiline": false,
+        "whole_word": whole_word,
+        "max_results": 10
+    });
+
+    let whole = executor.execute_search_with_result("test_id".to_string(), search(true)).await;
+    let substring = executor.execute_search_with_result("test_id".to_string(), search(false)).await;
+    let _ = tokio::fs::remove_file(test_file).await;
+
+    let whole: FsSearchResult = serde_json::from_value(whole.unwrap()).unwrap();
+    let lines: Vec<u64> = whole.matches[0].lines.iter().map(|l| l.ln).collect();
+    assert_eq!(lines, vec![1, 2, 4]);
+
+    let substring: FsSearchResult = serde_json::from_value(substring.unwrap()).unwrap();
+    assert_eq!(substring.matches[0].lines.len(), 4);
+}
+
+#[tokio::test]
+async fn test_fs_search_whole_word_rejects_partial() {
+    let test_file = "temp_whole_word_partial.txt";
+    tokio::fs::write(test_file, "latest\ntesting\n").await.expect("Failed to create test file");
+
+    let (sender, _receiver) = setup_event_bus();
+    let executor = FsExecutor::new(sender, 1024 * 1024);
+
+    let args = json!({
+        "query": "test",
+        "globs": ["temp_whole_word_partial.txt"],
+        "regex": false,
+        "case_insensitive": true,
+        "multiline": false,
+        "whole_word": true,
+        "max_results": 10
+    });
+
+    let result = executor.execute_search_with_result("test_id".to_string(), args).await;
+    let _ = tokio::fs::remove_file(test_file).await;
+
+    let search_result: FsSearchResult = serde_json::from_value(result.unwrap()).unwrap();
+    assert!(search_result.matches.is_empty());
+}
+
+#[tokio::test]
+async fn test_fs_search_and_find_paths_have_no_dot_prefix() {
+    let temp_dir = create_temp_dir().await;
+    tokio::fs::create_dir_all(temp_dir.path().join("src/nested")).await.unwrap();
+    create_temp_file(&temp_dir.path().join("src/nested"), "deep.rs", "const NORMALIZED: u8 = 1;\n").await;
+    create_temp_file(temp_dir.path(), "top.rs", "const NORMALIZED: u8 = 2;\n").await;
+
+    let (sender, _receiver) = setup_event_bus();
+    let executor = FsExecutor::new(sender, 1024 * 1024).with_root(Some(temp_dir.path().to_path_buf()));
+
+    let search = |globs: Value| json!({
+        "query": "NORMALIZED",
+        "globs": globs,
+        "regex": false,
+        "case_insensitive": false,
+        "multiline": false
+    });
+
+    let result = executor.execute_search_with_result("test_id".to_string(), search(json!(["*.rs"]))).await;
+    let result: FsSearchResult = serde_json::from_value(result.unwrap()).unwrap();
+    let paths: Vec<&str> = result.matches.iter().map(|m| m.path.as_str()).collect();
+    assert_eq!(paths, vec!["src/nested/deep.rs", "top.rs"]);
+
+    // A `./`-prefixed glob still matches the normalized paths
+    let result = executor.execute_search_with_result("test_id".to_string(), search(json!(["./src/**/*.rs"]))).await;
+    let result: FsSearchResult = serde_json::from_value(result.unwrap()).unwrap();
+    assert_eq!(result.matches.len(), 1);
+    assert_eq!(result.matches[0].path, "src/nested/deep.rs");
+
+    let found = executor.execute_find_with_result(
+        "test_id".to_string(),
+        json!({ "pattern": "deep", "base_path": "./src" }),
+    ).await.unwrap();
+    assert_eq!(found["matches"][0]["path"], "src/nested/deep.rs");
+}
+
+#[tokio::test]
+async fn test_fs_search_literal_columns() {
+    let temp_dir = create_temp_dir().await;
+    create_temp_file(temp_dir.path(), "cols.txt", "let foo = Foo::new(foo);\nnothing here\n").await;
+
+    let (sender, _receiver) = setup_event_bus();
+    let executor = FsExecutor::new(sender, 1024 * 1024).with_root(Some(temp_dir.path().to_path_buf()));
+
+    let search = |include_columns: bool| json!({
+        "query": "foo",
+        "regex": false,
+        "case_insensitive": true,
+        "multiline": false,
+        "include_columns": include_columns
+    });
+
+    let result = executor.execute_search_with_result("test_id".to_string(), search(true)).await;
+    let result: FsSearchResult = serde_json::from_value(result.unwrap()).unwrap();
+    let line = &result.matches[0].lines[0];
+    let spans: Vec<(usize, usize)> = line.columns.as_ref().unwrap().iter().map(|s| (s.start, s.end)).collect();
+    assert_eq!(spans, vec![(4, 7), (10, 13), (19, 22)]);
+    assert_eq!(&line.text[10..13], "Foo");
+
+    // Off by default, and absent from the JSON
+    let result = executor.execute_search_with_result("test_id".to_string(), search(false)).await.unwrap();
+    assert!(result["matches"][0]["lines"][0].get("columns").is_none());
+}
+
+#[tokio::test]
+async fn test_fs_search_regex_columns() {
+    let temp_dir = create_temp_dir().await;
+    create_temp_file(temp_dir.path(), "cols.rs", "fn alpha() {}\n    fn beta_2(x: u8) { gamma(); }\n").await;
+
+    let (sender, _receiver) = setup_event_bus();
+    let executor = FsExecutor::new(sender, 1024 * 1024).with_root(Some(temp_dir.path().to_path_buf()));
+
+    let args = json!({
+        "query": r"[a-z_0-9]+\(",
+        "regex": true,
+        "case_insensitive": false,
+        "multiline": false,
+        "include_columns": true
+    });
+
+    let result = executor.execute_search_with_result("test_id".to_string(), args).await;
+    let result: FsSearchResult = serde_json::from_value(result.unwrap()).unwrap();
+    let lines = &result.matches[0].lines;
+    assert_eq!(lines[0].columns.as_deref(), Some(&[MatchSpan { start: 3, end: 9 }][..]));
+    assert_eq!(
+        lines[1].columns.as_deref(),
+        Some(&[MatchSpan { start: 7, end: 14 }, MatchSpan { start: 23, end: 29 }][..])
+    );
+    assert_eq!(&lines[1].text[23..29], "gamma(");
+}
+
+#[tokio::test]
+async fn test_fs_search_cuts_long_lines_around_match() {
+    let temp_dir = create_temp_dir().await;
+    // A minified-style line: 200 KB with the match deep inside
+    let long_line = format!("{}needle_here{}", "a;".repeat(60_000), "b;".repeat(40_000));
+    create_temp_file(temp_dir.path(), "bundle.min.js", &format!("{}\nshort needle_here\n", long_line)).await;
+
+    let (sender, _receiver) = setup_event_bus();
+    let executor = FsExecutor::new(sender, 10 * 1024 * 1024).with_root(Some(temp_dir.path().to_path_buf()));
+
+    let args = json!({
+        "query": "needle_here",
+        "regex": false,
+        "case_insensitive": false,
+        "multiline": false,
+        "max_line_bytes": 200,
+        "include_columns": true
+    });
+
+    let result = executor.execute_search_with_result("test_id".to_string(), args).await;
+    let result: FsSearchResult = serde_json::from_value(result.unwrap()).unwrap();
+    let lines = &result.matches[0].lines;
+
+    let text = &lines[0].text;
+    assert!(text.starts_with('…') && text.ends_with('…'));
+    assert_eq!(text.len(), 200 + 2 * '…'.len_utf8());
+    let span = lines[0].columns.as_ref().unwrap()[0];
+    assert_eq!(&text[span.start..span.end], "needle_here");
+    // The match sits in the middle of the window
+    assert!(span.start > 50 && span.end < 160);
+
+    // Short lines are untouched
+    assert_eq!(lines[1].text, "short needle_here");
+}
+
+#[tokio::test]
+async fn test_fs_search_multiline_regex() {
+    let test_file = "temp_multiline_search.rs";
+    let test_content = "// header\npub fn multiline_marker(\n    arg: u32,\n) {}\nfn other() {}\n";
+    tokio::fs::write(test_file, test_content).await.expect("Failed to create test file");
+
+    let (sender, _receiver) = setup_event_bus();
+    let executor = FsExecutor::new(sender, 1024 * 1024);
+
+    let args = json!({
+        "query": r"multiline_marker\(\n\s+arg",
+        "globs": ["temp_multiline_search.rs"],
+        "regex": true,
+        "case_insensitive": false,
+        "multiline": true,
+        "max_results": 10
+    });
+
+    let result = executor.execute_search_with_result("test_id".to_string(), args).await;
+    let _ = tokio::fs::remove_file(test_file).await;
+
+    let search_result: FsSearchResult = serde_json::from_value(result.unwrap()).unwrap();
+    assert_eq!(search_result.matches.len(), 1);
+    let lines = &search_result.matches[0].lines;
+    assert_eq!(lines.iter().map(|l| l.ln).collect::<Vec<_>>(), vec![2, 3]);
+    assert_eq!(lines[0].text, "pub fn multiline_marker(");
+    assert_eq!(lines[1].text, "    arg: u32,");
+}
+
+#[tokio::test]
+async fn test_fs_search_multiline_pages_by_line() {
+    let temp_dir = create_temp_dir().await;
+    // Two matches per line, so matches outnumber the lines they report
+    create_temp_file(temp_dir.path(), "pairs.txt", &"ab ab\n".repeat(6)).await;
+
+    let (sender, _receiver) = setup_event_bus();
+    let executor = FsExecutor::new(sender, 1024 * 1024).with_root(Some(temp_dir.path().to_path_buf()));
+    let search = |continue_from: Option<String>| json!({
+        "query": "ab",
+        "regex": true,
+        "case_insensitive": false,
+        "multiline": true,
+        "max_results": 3,
+        "continue_from": continue_from
+    });
+
+    let first = executor.execute_search_with_result("test_id".to_string(), search(None)).await;
+    let first: FsSearchResult = serde_json::from_value(first.unwrap()).unwrap();
+    let lines: Vec<u64> = first.matches[0].lines.iter().map(|l| l.ln).collect();
+    assert_eq!(lines, vec![1, 2, 3]);
+    let token = first.continuation.expect("three lines remain");
+
+    let second = executor.execute_search_with_result("test_id".to_string(), search(Some(token))).await;
+    let second: FsSearchResult = serde_json::from_value(second.unwrap()).unwrap();
+    let lines: Vec<u64> = second.matches[0].lines.iter().map(|l| l.ln).collect();
+    assert_eq!(lines, vec![4, 5, 6]);
+    assert!(second.continuation.is_none());
+}
+
+#[tokio::test]
+async fn test_fs_search_pages_with_continuation() {
+    let test_files = ["temp_paging_a.txt", "temp_paging_b.txt"];
+    tokio::fs::write(test_files[0], "pgmark 1\nskip\npgmark 2\npgmark 3\n").await.unwrap();
+    tokio::fs::write(test_files[1], "pgmark 4\npgmark 5\n").await.unwrap();
+
+    let (sender, _receiver) = setup_event_bus();
+    let executor = FsExecutor::new(sender, 1024 * 1024);
+
+    let search = |max_results: u32, continue_from: Option<String>| json!({
+        "query": "pgmark",
+        "globs": ["temp_paging_*.txt"],
+        "regex": false,
+        "case_insensitive": false,
+        "multiline": false,
+        "max_results": max_results,
+        "continue_from": continue_from
+    });
+    let flatten = |result: &FsSearchResult| -> Vec<(String, u64)> {
+        result.matches.iter()
+            .flat_map(|m| m.lines.iter().map(move |l| (m.path.clone(), l.ln)))
+            .collect()
+    };
+
+    let full = executor.execute_search_with_result("test_id".to_string(), search(100, None)).await;
+    let first = executor.execute_search_with_result("test_id".to_string(), search(2, None)).await;
+    let first: FsSearchResult = serde_json::from_value(first.unwrap()).unwrap();
+    let token = first.continuation.clone().expect("first page should have a continuation");
+    let second = executor.execute_search_with_result("test_id".to_string(), search(10, Some(token.clone()))).await;
+    // Tokens are tied to the query that produced them
+    let mut other_query = search(10, Some(token));
+    other_query["query"] = json!("different");
+    let mismatched = executor.execute_search_with_result("test_id".to_string(), other_query).await;
+    for file in &test_files {
+        let _ = tokio::fs::remove_file(file).await;
+    }
+
+    let full: FsSearchResult = serde_json::from_value(full.unwrap()).unwrap();
+    let second: FsSearchResult = serde_json::from_value(second.unwrap()).unwrap();
+    assert!(full.continuation.is_none());
+    assert!(second.continuation.is_none());
+    assert_eq!(flatten(&first).len(), 2);
+
+    let mut paged = flatten(&first);
+    paged.extend(flatten(&second));
+    assert_eq!(paged, flatten(&full));
+    assert_eq!(paged.len(), 5);
+
+    assert!(mismatched.unwrap_err().contains("different query"));
+}
+
+#[tokio::test]
+async fn test_fs_find_pages_with_continuation() {
+    let temp_dir = create_temp_dir().await;
+    for name in ["page_a.rs", "page_b.rs", "page_c.rs", "other.txt"] {
+        create_temp_file(temp_dir.path(), name, "").await;
+    }
+
+    let (sender, _receiver) = setup_event_bus();
+    let executor = FsExecutor::new(sender, 1024 * 1024);
+
+    let find = |max_results: u32, continue_from: Option<String>| json!({
+        "pattern": "page_*.rs",
+        "base_path": temp_dir.path().to_string_lossy(),
+        "fuzzy": false,
+        "file_type": "file",
+        "max_results": max_results,
+        "continue_from": continue_from
+    });
+    let paths = |value: Value| -> (Vec<String>, Option<String>) {
+        let result: FsFindResult = serde_json::from_value(value).unwrap();
+        (result.matches.into_iter().map(|m| m.path).collect(), result.continuation)
+    };
+
+    let (full, full_token) = paths(executor.execute_find_with_result("test_id".to_string(), find(50, None)).await.unwrap());
+    let (mut first, token) = paths(executor.execute_find_with_result("test_id".to_string(), find(2, None)).await.unwrap());
+    let (second, last_token) = paths(executor.execute_find_with_result("test_id".to_string(), find(2, token)).await.unwrap());
+
+    assert!(full_token.is_none());
+    assert!(last_token.is_none());
+    assert_eq!(first.len(), 2);
+    first.extend(second);
+    assert_eq!(first, full);
+    assert_eq!(full.len(), 3);
+}
+
+#[tokio::test]
+async fn test_fs_find_regex_matches_anchored_names() {
     let temp_dir = create_temp_dir().await;
-    let nested_path = temp_dir.path().join("some").join("nested").join("dirs").join("file.txt");
-    
-    let (sender, mut receiver) = setup_event_bus();
+    for name in ["test_parser.rs", "test_lexer.rs", "my_test_utils.rs", "test_notes.txt"] {
+        create_temp_file(temp_dir.path(), name, "").await;
+    }
+
+    let (sender, _receiver) = setup_event_bus();
     let executor = FsExecutor::new(sender, 1024 * 1024);
-    
-    let args = json!({
-        "path": nested_path.to_string_lossy(),
-        "contents": "nested file content",
-        "create_if_missing": true,
-        "overwrite": false
-    });
-    
-    let result = executor.execute_write_with_result("test_id".to_string(), args).await;
-    assert!(result.is_ok());
-    
-    // Verify directories were created and file exists
-    assert!(nested_path.exists());
-    let content = tokio::fs::read_to_string(&nested_path).await.unwrap();
-    assert_eq!(content, "nested file content");
-    
-    let events = collect_events(&mut receiver, 2).await;
-    assert_eq!(count_progress_events(&events), 1);
+    let find = |extra: Value| {
+        let mut args = json!({
+            "pattern": r"^test_.*\.rs$",
+            "base_path": temp_dir.path().to_string_lossy(),
+            "regex": true,
+        });
+        args.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+        executor.execute_find_with_result("test_id".to_string(), args)
+    };
+
+    let result: FsFindResult = serde_json::from_value(find(json!({})).await.unwrap()).unwrap();
+    let mut names: Vec<_> = result.matches.iter()
+        .map(|m| m.path.rsplit('/').next().unwrap().to_string())
+        .collect();
+    names.sort();
+    assert_eq!(names, vec!["test_lexer.rs", "test_parser.rs"]);
+    assert!(result.matches.iter().all(|m| m.match_type == "regex"));
+
+    let error = find(json!({ "fuzzy": true })).await.unwrap_err();
+    assert!(error.contains("cannot both be set"), "{}", error);
+    assert!(find(json!({ "pattern": "(" })).await.unwrap_err().contains("Invalid regex"));
 }
 
 #[tokio::test]
-async fn test_fs_search_success() {
-    // Create test files in current directory since fs_search searches from "."
-    let test_file = "temp_test_file1.rs";
-    let test_content = "fn main() {\n    println!(\"Hello, world!\");\n}";
-    tokio::fs::write(test_file, test_content).await.expect("Failed to create test file");
-    
-    let (sender, mut receiver) = setup_event_bus();
+async fn test_fs_find_ignore_patterns_are_gitignore_globs() {
+    let temp_dir = create_temp_dir().await;
+    tokio::fs::create_dir_all(temp_dir.path().join("target/debug")).await.unwrap();
+    tokio::fs::create_dir_all(temp_dir.path().join("src")).await.unwrap();
+    for name in ["a.tmp", "src/b.tmp", "tmpfile.rs", "target/debug/out.rs", "src/target_info.rs", "targets.rs"] {
+        create_temp_file(temp_dir.path(), name, "").await;
+    }
+
+    let (sender, _receiver) = setup_event_bus();
     let executor = FsExecutor::new(sender, 1024 * 1024);
-    
     let args = json!({
-        "query": "fn",
-        "globs": ["*.rs"],
-        "regex": false,
-        "case_insensitive": false,
-        "multiline": false,
-        "max_results": 10
+        "pattern": "*",
+        "base_path": temp_dir.path().to_string_lossy(),
+        "fuzzy": false,
+        "file_type": "file",
+        "ignore_patterns": ["*.tmp", "target/"],
     });
-    
-    let result = executor.execute_search_with_result("test_id".to_string(), args).await;
-    assert!(result.is_ok());
-    
-    let result_value = result.unwrap();
-    let search_result: FsSearchResult = serde_json::from_value(result_value).unwrap();
-    
-    // Should find "fn" in the test file
-    assert!(!search_result.matches.is_empty());
-    let found_file = search_result.matches.iter()
-        .any(|m| m.path.contains(test_file));
-    assert!(found_file);
-    
-    // Cleanup
-    let _ = tokio::fs::remove_file(test_file).await;
-    
-    let events = collect_events(&mut receiver, 2).await;
-    assert_eq!(count_progress_events(&events), 1);
+
+    let result: FsFindResult = serde_json::from_value(
+        executor.execute_find_with_result("test_id".to_string(), args).await.unwrap()
+    ).unwrap();
+    let base = temp_dir.path().to_string_lossy().to_string();
+    let mut found: Vec<_> = result.matches.iter()
+        .map(|m| m.path.strip_prefix(&base).unwrap().trim_start_matches('/').to_string())
+        .collect();
+    found.sort();
+    assert_eq!(found, vec!["src/target_info.rs", "targets.rs", "tmpfile.rs"]);
 }
 
 #[tokio::test]
-async fn test_fs_search_regex() {
-    // Create test file in current directory
-    let test_file = "temp_test_emails.txt";
-    let test_content = "Email: test@example.com\nAnother: user@domain.org\nNot an email: example.com";
-    tokio::fs::write(test_file, test_content).await.expect("Failed to create test file");
-    
-    let (sender, mut receiver) = setup_event_bus();
+async fn test_fs_find_glob_is_case_insensitive_by_default() {
+    let temp_dir = create_temp_dir().await;
+    create_temp_file(temp_dir.path(), "MAIN.RS", "").await;
+
+    let (sender, _receiver) = setup_event_bus();
     let executor = FsExecutor::new(sender, 1024 * 1024);
-    
-    let args = json!({
-        "query": r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Z|a-z]{2,}\b",
-        "regex": true,
-        "case_insensitive": false,
-        "multiline": false,
-        "max_results": 10
-    });
-    
-    let result = executor.execute_search_with_result("test_id".to_string(), args).await;
-    assert!(result.is_ok());
-    
-    let result_value = result.unwrap();
-    let search_result: FsSearchResult = serde_json::from_value(result_value).unwrap();
-    
-    // Should find email addresses
-    assert!(!search_result.matches.is_empty());
-    if !search_result.matches.is_empty() {
-        let file_match = &search_result.matches[0];
-        // Should have at least one email line
-        assert!(!file_match.lines.is_empty());
-    }
-    
-    // Cleanup
-    let _ = tokio::fs::remove_file(test_file).await;
-    
-    let events = collect_events(&mut receiver, 2).await;
-    assert_eq!(count_progress_events(&events), 1);
+    let find = |pattern: &str, case_sensitive: bool| executor.execute_find_with_result("test_id".to_string(), json!({
+        "pattern": pattern,
+        "base_path": temp_dir.path().to_string_lossy(),
+        "fuzzy": false,
+        "case_sensitive": case_sensitive,
+    }));
+    let matches = |value: Value| serde_json::from_value::<FsFindResult>(value).unwrap().matches;
+
+    let exact = matches(find("main.rs", false).await.unwrap());
+    assert_eq!(exact.len(), 1);
+    assert!(exact[0].path.ends_with("MAIN.RS"));
+    assert_eq!(exact[0].match_type, "exact");
+
+    // A real glob, which the substring fallback can't rescue
+    assert_eq!(matches(find("m*.rs", false).await.unwrap()).len(), 1);
+    assert!(matches(find("m*.rs", true).await.unwrap()).is_empty());
 }
 
 #[tokio::test]
@@ -343,6 +1201,14 @@ async fn test_fs_apply_patch_dry_run() {
     assert!(patch_result.success);
     assert!(patch_result.summary.contains("Dry run"));
 
+    // The preview diff shows the change without the file being touched
+    let path = file_path.to_string_lossy();
+    let expected = format!(
+        "--- a/{path}\n+++ b/{path}\n@@ -1,3 +1,3 @@\n fn main() {{\n-    println!(\"Hello\");\n+    println!(\"Hello, World!\");\n }}\n"
+    );
+    assert_eq!(patch_result.diff.as_deref(), Some(expected.as_str()));
+    assert!(std::fs::read_to_string(&file_path).unwrap().contains("println!(\"Hello\");"));
+
     let events = collect_events(&mut receiver, 3).await; // 2 progress + 1 result
     assert_eq!(count_progress_events(&events), 2);
 }
@@ -438,12 +1304,132 @@ async fn test_fs_apply_patch_real_modification() {
     assert!(modified_content.contains("greet_user"), "New function should be added");
     assert!(modified_content.contains("Welcome to Rust programming!"), "New function body should be present");
 
-    let events = collect_events(&mut receiver, 3).await;
-    assert_eq!(count_progress_events(&events), 2);
+    let events = collect_events(&mut receiver, 4).await;
+    assert_eq!(count_progress_events(&events), 3);
     assert!(find_tool_result_event(&events).is_some());
 }
 
 
+#[tokio::test]
+async fn test_fs_apply_patch_commits_only_approved_ops() {
+    let temp_dir = create_temp_dir().await;
+    let kept = create_temp_file(temp_dir.path(), "kept.txt", "old kept\n").await;
+    let skipped = create_temp_file(temp_dir.path(), "skipped.txt", "old skipped\n").await;
+
+    let (sender, mut receiver) = setup_event_bus();
+    let executor = FsExecutor::new(sender, 1024 * 1024).with_patch_approval(true);
+
+    let spec = FsApplyPatchArgs {
+        dry_run: false,
+        ops: vec![
+            SimpleEditOp::ReplaceOnce {
+                path: kept.to_string_lossy().to_string(),
+                find: "old".to_string(),
+                replace: "new".to_string(),
+            },
+            SimpleEditOp::ReplaceOnce {
+                path: skipped.to_string_lossy().to_string(),
+                find: "old".to_string(),
+                replace: "new".to_string(),
+            },
+        ],
+    };
+    let args = serde_json::to_value(spec).unwrap();
+
+    let approve_first = async {
+        loop {
+            if let Some(AppEvent::ApprovalRequest(request)) = receiver.recv().await {
+                assert_eq!(request.ops.len(), 2);
+                assert!(request.ops[0].diff.contains("+new kept"));
+                assert!(request.ops[1].description.starts_with("replace_once"));
+                request.respond(vec![true, false]);
+                break;
+            }
+        }
+    };
+    let (result, _) = tokio::join!(
+        executor.execute_apply_patch_with_result("test_id".to_string(), args),
+        approve_first,
+    );
+
+    let patch_result: FsApplyPatchResult = serde_json::from_value(result.unwrap()).unwrap();
+    assert!(patch_result.success, "{}", patch_result.summary);
+    assert_eq!(patch_result.rejected_hunks.unwrap().len(), 1);
+    assert_eq!(tokio::fs::read_to_string(&kept).await.unwrap(), "new kept\n");
+    assert_eq!(tokio::fs::read_to_string(&skipped).await.unwrap(), "old skipped\n");
+}
+
+#[tokio::test]
+async fn test_fs_apply_patch_without_approval_sends_no_request() {
+    let temp_dir = create_temp_dir().await;
+    let file = create_temp_file(temp_dir.path(), "file.txt", "old\n").await;
+
+    let (sender, mut receiver) = setup_event_bus();
+    let executor = FsExecutor::new(sender, 1024 * 1024).with_patch_approval(false);
+    let spec = FsApplyPatchArgs {
+        dry_run: false,
+        ops: vec![SimpleEditOp::ReplaceOnce {
+            path: file.to_string_lossy().to_string(),
+            find: "old".to_string(),
+            replace: "new".to_string(),
+        }],
+    };
+
+    let result = executor.execute_apply_patch_with_result("test_id".to_string(), serde_json::to_value(spec).unwrap()).await;
+    let patch_result: FsApplyPatchResult = serde_json::from_value(result.unwrap()).unwrap();
+    assert!(patch_result.success, "{}", patch_result.summary);
+    assert_eq!(tokio::fs::read_to_string(&file).await.unwrap(), "new\n");
+    while let Ok(event) = receiver.try_recv() {
+        assert!(!matches!(event, AppEvent::ApprovalRequest(_)), "no approval should be requested");
+    }
+}
+
+#[tokio::test]
+async fn test_fs_apply_patch_reports_progress_per_file() {
+    let temp_dir = create_temp_dir().await;
+    let existing = create_temp_file(temp_dir.path(), "existing.txt", "old\n").await;
+    let doomed = create_temp_file(temp_dir.path(), "doomed.txt", "bye\n").await;
+
+    let (sender, mut receiver) = setup_event_bus();
+    // Rooted in the temp dir so the trash lands there too, not in the system temp dir
+    let executor = FsExecutor::new(sender, 1024 * 1024).with_root(Some(temp_dir.path().to_path_buf()));
+
+    let spec = FsApplyPatchArgs {
+        dry_run: false,
+        ops: vec![
+            SimpleEditOp::ReplaceOnce {
+                path: existing.to_string_lossy().to_string(),
+                find: "old".to_string(),
+                replace: "new".to_string(),
+            },
+            SimpleEditOp::SetFile {
+                path: temp_dir.path().join("created.txt").to_string_lossy().to_string(),
+                contents: "hi\n".to_string(),
+            },
+            SimpleEditOp::DeleteFile { path: doomed.to_string_lossy().to_string() },
+        ],
+    };
+    let args = serde_json::to_value(spec).unwrap();
+
+    let result = executor.execute_apply_patch_with_result("test_id".to_string(), args).await;
+    assert!(result.is_ok());
+
+    // Planning, one per file, finished, then the result
+    let events = collect_events(&mut receiver, 6).await;
+    let file_messages: Vec<String> = events
+        .iter()
+        .filter_map(|e| match e {
+            AppEvent::ToolProgress { message, .. } if message.contains(" file ") => Some(message.clone()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(file_messages.len(), 3, "{:?}", file_messages);
+    assert!(file_messages[0].starts_with("Writing file 1/3: "));
+    assert!(file_messages.iter().any(|m| m.starts_with("Deleting file ") && m.ends_with("doomed.txt")));
+    assert!(file_messages[2].contains("3/3"));
+    assert!(find_tool_result_event(&events).is_some());
+}
+
 #[tokio::test]
 async fn test_fs_apply_patch_create_new_file() {
     let temp_dir = create_temp_dir().await;
@@ -485,10 +1471,77 @@ hello_world()
     assert!(content.contains("Hello from a new Python file!"));
     assert!(content.contains("hello_world()"));
 
-    let events = collect_events(&mut receiver, 3).await;
-    assert_eq!(count_progress_events(&events), 2);
+    let events = collect_events(&mut receiver, 4).await;
+    assert_eq!(count_progress_events(&events), 3);
+}
+
+
+#[tokio::test]
+async fn test_fs_apply_patch_create_empty_file() {
+    let temp_dir = create_temp_dir().await;
+    let init_path = temp_dir.path().join("pkg/sub/__init__.py");
+
+    let (sender, _receiver) = setup_event_bus();
+    let executor = FsExecutor::new(sender, 1024 * 1024);
+
+    let spec = FsApplyPatchArgs {
+        dry_run: false,
+        ops: vec![SimpleEditOp::CreateEmpty { path: init_path.to_string_lossy().to_string() }],
+    };
+    let args = serde_json::to_value(spec).unwrap();
+
+    let result = executor.execute_apply_patch_with_result("test_id".to_string(), args.clone()).await.unwrap();
+    let patch_result: FsApplyPatchResult = serde_json::from_value(result).unwrap();
+    assert!(patch_result.success, "Patch should succeed: {}", patch_result.summary);
+    assert!(patch_result.summary.contains(&format!("Created files: {}", init_path.display())));
+    assert!(patch_result.summary.contains("create_empty"));
+    assert_eq!(std::fs::metadata(&init_path).unwrap().len(), 0);
+
+    // A second create of the same path is refused
+    let result = executor.execute_apply_patch_with_result("test_id".to_string(), args).await.unwrap();
+    let patch_result: FsApplyPatchResult = serde_json::from_value(result).unwrap();
+    assert!(!patch_result.success);
+    assert!(patch_result.rejected_hunks.unwrap()[0].contains("already exists"));
 }
 
+#[tokio::test]
+async fn test_fs_apply_patch_create_dir() {
+    let temp_dir = create_temp_dir().await;
+    let nested = temp_dir.path().join("src/modules/empty");
+    let file_path = create_temp_file(temp_dir.path(), "file.txt", "not a dir").await;
+
+    let (sender, _receiver) = setup_event_bus();
+    let executor = FsExecutor::new(sender, 1024 * 1024);
+
+    let spec = FsApplyPatchArgs {
+        dry_run: false,
+        ops: vec![SimpleEditOp::CreateDir { path: nested.to_string_lossy().to_string() }],
+    };
+    let args = serde_json::to_value(spec).unwrap();
+
+    let result = executor.execute_apply_patch_with_result("test_id".to_string(), args.clone()).await.unwrap();
+    let patch_result: FsApplyPatchResult = serde_json::from_value(result).unwrap();
+    assert!(patch_result.success, "Patch should succeed: {}", patch_result.summary);
+    assert!(patch_result.summary.contains(&format!("Created directories: {}", nested.display())));
+    assert!(nested.is_dir());
+
+    // Creating it again is a no-op
+    let result = executor.execute_apply_patch_with_result("test_id".to_string(), args).await.unwrap();
+    let patch_result: FsApplyPatchResult = serde_json::from_value(result).unwrap();
+    assert!(patch_result.success);
+    assert!(!patch_result.summary.contains("Created directories"));
+
+    // A file in the way is an error
+    let spec = FsApplyPatchArgs {
+        dry_run: false,
+        ops: vec![SimpleEditOp::CreateDir { path: file_path.to_string_lossy().to_string() }],
+    };
+    let result = executor.execute_apply_patch_with_result("test_id".to_string(), serde_json::to_value(spec).unwrap()).await.unwrap();
+    let patch_result: FsApplyPatchResult = serde_json::from_value(result).unwrap();
+    assert!(!patch_result.success);
+    assert!(patch_result.rejected_hunks.unwrap()[0].contains("Not a directory"));
+    assert!(file_path.is_file());
+}
 
 #[tokio::test]
 async fn test_fs_apply_patch_delete_file() {
@@ -497,7 +1550,8 @@ async fn test_fs_apply_patch_delete_file() {
     let file_path = create_temp_file(temp_dir.path(), "to_delete.txt", file_content).await;
 
     let (sender, mut receiver) = setup_event_bus();
-    let executor = FsExecutor::new(sender, 1024 * 1024);
+    // Rooted in the temp dir so the trash lands there too, not in the system temp dir
+    let executor = FsExecutor::new(sender, 1024 * 1024).with_root(Some(temp_dir.path().to_path_buf()));
 
     // Verify file exists initially
     assert!(file_path.exists());
@@ -522,8 +1576,73 @@ async fn test_fs_apply_patch_delete_file() {
     // Verify the file was actually deleted
     assert!(!file_path.exists(), "File should have been deleted");
 
-    let events = collect_events(&mut receiver, 3).await;
-    assert_eq!(count_progress_events(&events), 2);
+    let events = collect_events(&mut receiver, 4).await;
+    assert_eq!(count_progress_events(&events), 3);
+}
+
+#[tokio::test]
+async fn test_fs_search_and_find_skip_trash() {
+    let temp_dir = create_temp_dir().await;
+    create_temp_file(temp_dir.path(), "trashed_marker.rs", "trashed_marker_text").await;
+
+    let (sender, _receiver) = setup_event_bus();
+    let executor = FsExecutor::new(sender, 1024 * 1024).with_root(Some(temp_dir.path().to_path_buf()));
+    executor
+        .execute_delete_file_with_result("test_id".to_string(), json!({ "path": "trashed_marker.rs" }))
+        .await
+        .unwrap();
+
+    let search: FsSearchResult = serde_json::from_value(executor
+        .execute_search_with_result("test_id".to_string(), json!({ "query": "trashed_marker_text", "regex": false, "case_insensitive": false, "multiline": false }))
+        .await
+        .unwrap()).unwrap();
+    assert!(search.matches.is_empty(), "{:?}", search.matches);
+
+    let find: FsFindResult = serde_json::from_value(executor
+        .execute_find_with_result("test_id".to_string(), json!({ "pattern": "trashed_marker" }))
+        .await
+        .unwrap()).unwrap();
+    assert!(find.matches.is_empty(), "{:?}", find.matches);
+}
+
+#[tokio::test]
+async fn test_fs_delete_moves_file_to_trash() {
+    let temp_dir = create_temp_dir().await;
+    let content = "keep a copy of me";
+    tokio::fs::create_dir(temp_dir.path().join("src")).await.unwrap();
+    let file_path = create_temp_file(temp_dir.path(), "src/old.rs", content).await;
+
+    let (sender, _receiver) = setup_event_bus();
+    let executor = FsExecutor::new(sender, 1024 * 1024).with_root(Some(temp_dir.path().to_path_buf()));
+
+    let result = executor
+        .execute_delete_file_with_result("test_id".to_string(), json!({ "path": "src/old.rs" }))
+        .await
+        .unwrap();
+    let op_result: FsSimpleOpResult = serde_json::from_value(result).unwrap();
+    assert!(!file_path.exists());
+
+    // The file keeps its workspace-relative path inside this session's trash
+    let trash_path = std::path::PathBuf::from(op_result.trash_path.expect("deleted file should be trashed"));
+    assert!(trash_path.starts_with(temp_dir.path().join(crate::tools::executors::TRASH_DIR)));
+    assert!(trash_path.ends_with("src/old.rs"));
+    assert_eq!(tokio::fs::read_to_string(&trash_path).await.unwrap(), content);
+
+    // Deleting a second file at the same path keeps both copies, and the patch summary says where
+    create_temp_file(temp_dir.path(), "src/old.rs", "second").await;
+    let spec = FsApplyPatchArgs {
+        dry_run: false,
+        ops: vec![SimpleEditOp::DeleteFile { path: "src/old.rs".to_string() }],
+    };
+    let result = executor
+        .execute_apply_patch_with_result("test_id".to_string(), serde_json::to_value(spec).unwrap())
+        .await
+        .unwrap();
+    let patch_result: FsApplyPatchResult = serde_json::from_value(result).unwrap();
+    let second = trash_path.with_file_name("old.rs.1");
+    assert!(patch_result.summary.contains(&format!("-> {}", second.display())), "{}", patch_result.summary);
+    assert_eq!(tokio::fs::read_to_string(&second).await.unwrap(), "second");
+    assert_eq!(tokio::fs::read_to_string(&trash_path).await.unwrap(), content);
 }
 
 #[tokio::test]
@@ -567,3 +1686,63 @@ async fn test_output_truncation() {
     let events = collect_events(&mut receiver, 2).await;
     assert_eq!(count_progress_events(&events), 1);
 }
+
+#[tokio::test]
+async fn test_fs_tree_structure_and_depth() {
+    let temp_dir = create_temp_dir().await;
+    let root = temp_dir.path();
+    std::fs::create_dir_all(root.join("src/nested/deeper")).unwrap();
+    std::fs::create_dir_all(root.join("target/debug")).unwrap();
+    std::fs::write(root.join("Cargo.toml"), "").unwrap();
+    std::fs::write(root.join("src/main.rs"), "").unwrap();
+    std::fs::write(root.join("src/nested/mod.rs"), "").unwrap();
+    std::fs::write(root.join("src/nested/deeper/hidden.rs"), "").unwrap();
+    std::fs::write(root.join("debug.log"), "").unwrap();
+    std::fs::write(root.join(".gitignore"), "*.log\n").unwrap();
+
+    let (sender, _receiver) = setup_event_bus();
+    let executor = FsExecutor::new(sender, 1024 * 1024);
+
+    let args = json!({
+        "path": root.to_string_lossy(),
+        "max_depth": 2
+    });
+
+    let result = executor.execute_tree_with_result("test_id".to_string(), args).await;
+    let tree_result: FsTreeResult = serde_json::from_value(result.unwrap()).unwrap();
+    let lines: Vec<&str> = tree_result.tree.lines().skip(1).collect();
+
+    assert_eq!(lines, vec![
+        "├── src/ (2 entries)",
+        "│   ├── nested/ (2 entries)",
+        "│   └── main.rs",
+        "├── .gitignore",
+        "└── Cargo.toml",
+    ]);
+    assert!(!tree_result.truncated);
+    assert!(!tree_result.tree.contains("target"));
+    assert!(!tree_result.tree.contains("debug.log"));
+}
+
+#[tokio::test]
+async fn test_fs_tree_node_limit() {
+    let temp_dir = create_temp_dir().await;
+    for i in 0..10 {
+        std::fs::write(temp_dir.path().join(format!("file{}.txt", i)), "").unwrap();
+    }
+
+    let (sender, _receiver) = setup_event_bus();
+    let executor = FsExecutor::new(sender, 1024 * 1024);
+
+    let args = json!({
+        "path": temp_dir.path().to_string_lossy(),
+        "max_nodes": 3
+    });
+
+    let result = executor.execute_tree_with_result("test_id".to_string(), args).await;
+    let tree_result: FsTreeResult = serde_json::from_value(result.unwrap()).unwrap();
+
+    assert!(tree_result.truncated);
+    assert_eq!(tree_result.total_files, 3);
+    assert_eq!(tree_result.tree.lines().count(), 4);
+}
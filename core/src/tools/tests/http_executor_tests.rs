@@ -0,0 +1,91 @@
+use super::*;
+use crate::tools::executors::HttpExecutor;
+use serde_json::json;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// Serve one HTTP response on a local port and return the URL to request
+async fn serve_once(content_type: &'static str, body: &'static str) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut request = [0u8; 4096];
+        let _ = socket.read(&mut request).await;
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            content_type,
+            body.len(),
+            body
+        );
+        socket.write_all(response.as_bytes()).await.unwrap();
+    });
+    format!("http://{}/docs", addr)
+}
+
+#[tokio::test]
+async fn test_http_fetch_converts_html_to_text() {
+    let url = serve_once(
+        "text/html; charset=utf-8",
+        "<html><head><title>Docs</title><style>p { color: red; }</style></head>\
+         <body><h1>Getting &amp; Started</h1><script>alert('hi')</script>\
+         <p>Install   the <b>crate</b>.</p><ul><li>one</li><li>two</li></ul></body></html>",
+    )
+    .await;
+
+    let (sender, mut receiver) = setup_event_bus();
+    let executor = HttpExecutor::new(sender, 1024 * 1024).with_private_hosts_allowed();
+
+    let result = executor
+        .execute_fetch_with_result("test_id".to_string(), json!({ "url": url }))
+        .await
+        .unwrap();
+
+    let fetch: HttpFetchResult = serde_json::from_value(result).unwrap();
+    assert_eq!(fetch.status, 200);
+    assert_eq!(fetch.content_type.as_deref(), Some("text/html; charset=utf-8"));
+    assert_eq!(fetch.body, "Getting & Started\n\nInstall the crate.\n\n- one\n- two");
+    assert!(!fetch.truncated);
+
+    let events = collect_events(&mut receiver, 2).await;
+    assert_eq!(count_progress_events(&events), 1);
+    assert!(find_tool_result_event(&events).is_some());
+}
+
+#[tokio::test]
+async fn test_http_fetch_caps_body_at_max_bytes() {
+    let url = serve_once("text/plain", "0123456789abcdef").await;
+
+    let (sender, _receiver) = setup_event_bus();
+    let executor = HttpExecutor::new(sender, 1024 * 1024).with_private_hosts_allowed();
+
+    let result = executor
+        .execute_fetch_with_result("test_id".to_string(), json!({ "url": url, "max_bytes": 10 }))
+        .await
+        .unwrap();
+
+    let fetch: HttpFetchResult = serde_json::from_value(result).unwrap();
+    assert_eq!(fetch.body, "0123456789");
+    assert_eq!(fetch.bytes_read, 10);
+    assert!(fetch.truncated);
+}
+
+#[tokio::test]
+async fn test_http_fetch_rejects_unsafe_urls() {
+    let (sender, _receiver) = setup_event_bus();
+    let executor = HttpExecutor::new(sender, 1024 * 1024);
+
+    for url in [
+        "file:///etc/passwd",
+        "http://localhost:8080/",
+        "http://127.0.0.1/",
+        "http://10.0.0.5/admin",
+        "http://169.254.169.254/latest/meta-data/",
+        "http://[::1]/",
+    ] {
+        let result = executor
+            .execute_fetch_with_result("test_id".to_string(), json!({ "url": url }))
+            .await;
+        assert!(result.is_err(), "{} should be refused", url);
+    }
+}
@@ -2,8 +2,12 @@ pub mod executor_tests;
 pub mod fs_executor_tests;
 pub mod shell_executor_tests;
 pub mod code_executor_tests;
+pub mod http_executor_tests;
 pub mod registry_tests;
 pub mod types_tests;
+pub mod url_safety_tests;
+#[cfg(debug_assertions)]
+pub mod schema_tests;
 
 // Test utilities
 use crate::events::{AppEvent, EventBus};
@@ -0,0 +1,49 @@
+use crate::events::ToolName;
+use crate::tools::schema::{validate, validate_tool_output};
+use serde_json::json;
+
+#[test]
+fn test_schema_accepts_matching_output() {
+    let value = json!({ "bytes_written": 12 });
+    assert!(validate_tool_output(&ToolName::FsWrite, &value).is_ok());
+}
+
+#[test]
+fn test_schema_detects_mismatched_output() {
+    // bytes_written renamed, as a careless refactor might do
+    let value = json!({ "written": 12 });
+    let err = validate_tool_output(&ToolName::FsWrite, &value).unwrap_err();
+    assert!(err.contains("fs.write"));
+    assert!(err.contains("bytes_written"));
+
+    let value = json!({ "bytes_written": "12" });
+    let err = validate_tool_output(&ToolName::FsWrite, &value).unwrap_err();
+    assert!(err.contains("$.bytes_written: expected integer, found string"));
+}
+
+#[test]
+fn test_schema_checks_nested_items() {
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "matches": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": { "line": { "type": "integer" } },
+                    "required": ["line"]
+                }
+            }
+        }
+    });
+
+    assert!(validate(&json!({ "matches": [{ "line": 1 }] }), &schema, "$").is_ok());
+    let err = validate(&json!({ "matches": [{ "line": 1 }, {}] }), &schema, "$").unwrap_err();
+    assert!(err.starts_with("$.matches[1]"));
+}
+
+#[test]
+fn test_schema_skips_truncation_envelope() {
+    let value = json!({ "truncated": true, "original_size_bytes": 4096, "max_allowed_bytes": 1024 });
+    assert!(validate_tool_output(&ToolName::FsRead, &value).is_ok());
+}
@@ -216,6 +216,24 @@ async fn test_shell_exec_legacy_method() {
     assert!(find_tool_result_event(&events).is_some());
 }
 
+#[tokio::test]
+async fn test_shell_exec_legacy_method_returns_with_background_child() {
+    let (sender, _receiver) = setup_event_bus();
+    let executor = ShellExecutor::new(sender, 1024 * 1024);
+
+    // The backgrounded sleep inherits stdout, so the pipe stays open after sh exits
+    let args = json!({
+        "command": ["sh", "-c", "echo done; sleep 10 &"],
+        "timeout_ms": 5000
+    });
+
+    let result = tokio::time::timeout(
+        std::time::Duration::from_secs(5),
+        executor.execute("test_id".to_string(), args),
+    ).await;
+    assert!(result.expect("execute should not wait on the background process").is_ok());
+}
+
 #[tokio::test]
 async fn test_shell_exec_invalid_args() {
     let (sender, _receiver) = setup_event_bus();
@@ -280,3 +298,63 @@ async fn test_shell_exec_complex_command() {
     let events = collect_events(&mut receiver, 3).await;
     assert_eq!(count_progress_events(&events), 1);
 }
+
+#[tokio::test]
+async fn test_shell_exec_expands_defined_env_var() {
+    let (sender, _receiver) = setup_event_bus();
+    let executor = ShellExecutor::new(sender, 1024 * 1024);
+
+    let args = json!({
+        "command": ["echo", "$GROK_EXPAND_TEST/${GROK_EXPAND_TEST}_x"],
+        "env": [["GROK_EXPAND_TEST", "value"]],
+        "expand_env": true,
+        "timeout_ms": 5000
+    });
+
+    let result = executor.execute_with_result("test_id".to_string(), args).await.unwrap();
+    let shell_result: ShellExecResult = serde_json::from_value(result).unwrap();
+    assert_eq!(shell_result.stdout.trim(), "value/value_x");
+}
+
+#[tokio::test]
+async fn test_shell_exec_keeps_undefined_env_var_literal() {
+    let (sender, _receiver) = setup_event_bus();
+    let executor = ShellExecutor::new(sender, 1024 * 1024);
+
+    let args = json!({
+        "command": ["echo", "${GROK_SURELY_UNDEFINED_VAR} costs $5"],
+        "expand_env": true,
+        "timeout_ms": 5000
+    });
+
+    let result = executor.execute_with_result("test_id".to_string(), args).await.unwrap();
+    let shell_result: ShellExecResult = serde_json::from_value(result).unwrap();
+    assert_eq!(shell_result.stdout.trim(), "${GROK_SURELY_UNDEFINED_VAR} costs $5");
+
+    // Opting in replaces undefined variables with nothing
+    let args = json!({
+        "command": ["echo", "[$GROK_SURELY_UNDEFINED_VAR]"],
+        "expand_env": true,
+        "undefined_env_empty": true,
+        "timeout_ms": 5000
+    });
+    let result = executor.execute_with_result("test_id".to_string(), args).await.unwrap();
+    let shell_result: ShellExecResult = serde_json::from_value(result).unwrap();
+    assert_eq!(shell_result.stdout.trim(), "[]");
+}
+
+#[tokio::test]
+async fn test_shell_exec_without_expand_env_is_literal() {
+    let (sender, _receiver) = setup_event_bus();
+    let executor = ShellExecutor::new(sender, 1024 * 1024);
+
+    let args = json!({
+        "command": ["echo", "$GROK_EXPAND_TEST"],
+        "env": [["GROK_EXPAND_TEST", "value"]],
+        "timeout_ms": 5000
+    });
+
+    let result = executor.execute_with_result("test_id".to_string(), args).await.unwrap();
+    let shell_result: ShellExecResult = serde_json::from_value(result).unwrap();
+    assert_eq!(shell_result.stdout.trim(), "$GROK_EXPAND_TEST");
+}
@@ -7,6 +7,8 @@ fn test_fs_read_args_serialization() {
         path: "/test/path.txt".to_string(),
         range: Some(10..20),
         encoding: Some("utf-8".to_string()),
+        summary_only: None,
+        max_bytes: None,
     };
     
     let serialized = to_value(&args).unwrap();
@@ -23,6 +25,8 @@ fn test_fs_read_args_minimal() {
         path: "/test/path.txt".to_string(),
         range: None,
         encoding: None,
+        summary_only: None,
+        max_bytes: None,
     };
     
     let serialized = to_value(&args).unwrap();
@@ -39,9 +43,14 @@ fn test_fs_read_result_serialization() {
         contents: "file contents".to_string(),
         encoding: "utf-8".to_string(),
         truncated: false,
+        is_empty: false,
+        whitespace_only: false,
+        summary: None,
+        adjusted_range: None,
     };
     
     let serialized = to_value(&result).unwrap();
+    assert!(serialized.get("summary").is_none());
     let deserialized: FsReadResult = from_value(serialized).unwrap();
     
     assert_eq!(deserialized.contents, result.contents);
@@ -58,6 +67,12 @@ fn test_fs_search_args_serialization() {
         regex: true,
         case_insensitive: false,
         multiline: true,
+        whole_word: Some(true),
+        continue_from: None,
+        max_file_bytes: None,
+        rank: None,
+        include_columns: None,
+        max_line_bytes: None,
     };
     
     let serialized = to_value(&args).unwrap();
@@ -69,6 +84,7 @@ fn test_fs_search_args_serialization() {
     assert_eq!(deserialized.regex, args.regex);
     assert_eq!(deserialized.case_insensitive, args.case_insensitive);
     assert_eq!(deserialized.multiline, args.multiline);
+    assert_eq!(deserialized.whole_word, args.whole_word);
 }
 
 #[test]
@@ -79,16 +95,20 @@ fn test_fs_search_result_serialization() {
             SearchLine {
                 ln: 10,
                 text: "fn test() {".to_string(),
+                columns: None,
             },
             SearchLine {
                 ln: 15,
                 text: "    // test comment".to_string(),
+                columns: None,
             },
         ],
     };
     
     let result = FsSearchResult {
         matches: vec![search_match],
+        continuation: None,
+        skipped_large_files: 0,
     };
     
     let serialized = to_value(&result).unwrap();
@@ -108,6 +128,7 @@ fn test_fs_write_args_serialization() {
         contents: "file contents to write".to_string(),
         create_if_missing: true,
         overwrite: false,
+        max_write_bytes: None,
     };
     
     let serialized = to_value(&args).unwrap();
@@ -123,6 +144,7 @@ fn test_fs_write_args_serialization() {
 fn test_fs_write_result_serialization() {
     let result = FsWriteResult {
         bytes_written: 1024,
+        overwrite: None,
     };
     
     let serialized = to_value(&result).unwrap();
@@ -162,6 +184,7 @@ fn test_fs_apply_patch_result_serialization() {
         success: true,
         rejected_hunks: None,
         summary: "Patch applied successfully".to_string(),
+        diff: None,
     };
     
     let serialized = to_value(&result).unwrap();
@@ -181,6 +204,7 @@ fn test_fs_apply_patch_result_with_errors() {
             "Hunk 3 context mismatch".to_string(),
         ]),
         summary: "Patch failed with 2 rejected hunks".to_string(),
+        diff: None,
     };
     
     let serialized = to_value(&result).unwrap();
@@ -197,6 +221,7 @@ fn test_fs_find_args_serialization() {
         pattern: "*.rs".to_string(),
         base_path: Some("/project/src".to_string()),
         fuzzy: Some(true),
+        regex: None,
         case_sensitive: Some(false),
         file_type: Some("file".to_string()),
         max_results: Some(50),
@@ -204,6 +229,7 @@ fn test_fs_find_args_serialization() {
             "target/".to_string(),
             "*.tmp".to_string(),
         ]),
+        continue_from: None,
     };
     
     let serialized = to_value(&args).unwrap();
@@ -236,6 +262,7 @@ fn test_fs_find_result_serialization() {
     let result = FsFindResult {
         matches: file_matches,
         search_time_ms: 42,
+        continuation: None,
     };
     
     let serialized = to_value(&result).unwrap();
@@ -317,6 +344,8 @@ fn test_shell_exec_args_serialization() {
         timeout_ms: Some(30000),
         with_escalated_permissions: Some(false),
         justification: Some("Listing files for analysis".to_string()),
+        expand_env: None,
+        undefined_env_empty: None,
     };
     
     let serialized = to_value(&args).unwrap();
@@ -372,18 +401,20 @@ fn test_complex_nested_structures() {
             SearchMatch {
                 path: "/project/src/main.rs".to_string(),
                 lines: vec![
-                    SearchLine { ln: 1, text: "use std::collections::HashMap;".to_string() },
-                    SearchLine { ln: 15, text: "fn main() {".to_string() },
-                    SearchLine { ln: 25, text: "    let mut map = HashMap::new();".to_string() },
+                    SearchLine { ln: 1, text: "use std::collections::HashMap;".to_string(), columns: None },
+                    SearchLine { ln: 15, text: "fn main() {".to_string(), columns: None },
+                    SearchLine { ln: 25, text: "    let mut map = HashMap::new();".to_string(), columns: None },
                 ],
             },
             SearchMatch {
                 path: "/project/src/lib.rs".to_string(),
                 lines: vec![
-                    SearchLine { ln: 8, text: "pub fn create_map() -> HashMap<String, i32> {".to_string() },
+                    SearchLine { ln: 8, text: "pub fn create_map() -> HashMap<String, i32> {".to_string(), columns: None },
                 ],
             },
         ],
+        continuation: None,
+        skipped_large_files: 0,
     };
     
     let serialized = to_value(&complex_result).unwrap();
@@ -402,10 +433,12 @@ fn test_optional_fields_none() {
         pattern: "test".to_string(),
         base_path: None,
         fuzzy: None,
+        regex: None,
         case_sensitive: None,
         file_type: None,
         max_results: None,
         ignore_patterns: None,
+        continue_from: None,
     };
     
     let serialized = to_value(&minimal_find_args).unwrap();
@@ -442,14 +475,20 @@ fn test_default_values() {
         regex: false,
         case_insensitive: true,
         multiline: false,
+        whole_word: None,
+        continue_from: None,
+        max_file_bytes: None,
+        rank: None,
+        include_columns: None,
+        max_line_bytes: None,
     };
     
     let serialized = to_value(&search_args).unwrap();
     let deserialized: FsSearchArgs = from_value(serialized).unwrap();
     
-    assert_eq!(deserialized.regex, false);
-    assert_eq!(deserialized.case_insensitive, true);
-    assert_eq!(deserialized.multiline, false);
+    assert!(!deserialized.regex);
+    assert!(deserialized.case_insensitive);
+    assert!(!deserialized.multiline);
 }
 
 #[test]
@@ -457,6 +496,8 @@ fn test_empty_collections() {
     // Test with empty vectors
     let result = FsSearchResult {
         matches: vec![],
+        continuation: None,
+        skipped_large_files: 0,
     };
     
     let serialized = to_value(&result).unwrap();
@@ -472,6 +513,8 @@ fn test_empty_collections() {
         timeout_ms: None,
         with_escalated_permissions: None,
         justification: None,
+        expand_env: None,
+        undefined_env_empty: None,
     };
     
     let serialized = to_value(&shell_args).unwrap();
@@ -519,8 +562,8 @@ fn test_fs_write_args_defaults() {
     let args: FsWriteArgs = from_value(args_missing_bools).unwrap();
     assert_eq!(args.path, "/test/file.txt");
     assert_eq!(args.contents, "test content");
-    assert_eq!(args.create_if_missing, true, "create_if_missing should default to true");
-    assert_eq!(args.overwrite, false, "overwrite should default to false");
+    assert!(args.create_if_missing, "create_if_missing should default to true");
+    assert!(!args.overwrite, "overwrite should default to false");
     
     // Test with explicit boolean fields
     let args_with_bools = json!({
@@ -531,6 +574,6 @@ fn test_fs_write_args_defaults() {
     });
     
     let args: FsWriteArgs = from_value(args_with_bools).unwrap();
-    assert_eq!(args.create_if_missing, false);
-    assert_eq!(args.overwrite, true);
+    assert!(!args.create_if_missing);
+    assert!(args.overwrite);
 }
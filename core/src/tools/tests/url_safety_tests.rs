@@ -0,0 +1,32 @@
+use crate::tools::url_safety::UrlPolicy;
+use reqwest::Url;
+use std::net::SocketAddr;
+
+fn url(s: &str) -> Url {
+    Url::parse(s).unwrap()
+}
+
+#[tokio::test]
+async fn test_url_policy_blocks_loopback() {
+    let policy = UrlPolicy::default();
+    let err = policy.resolve(&url("http://127.0.0.1:8080/admin")).await.unwrap_err();
+    assert!(err.contains("local or private"), "{}", err);
+    assert!(policy.check(&url("http://[::ffff:127.0.0.1]/")).is_err());
+    assert!(policy.check(&url("http://localhost/")).is_err());
+    assert!(policy.check(&url("file:///etc/passwd")).is_err());
+}
+
+#[tokio::test]
+async fn test_url_policy_allows_public_domain_on_allowlist() {
+    let policy = UrlPolicy {
+        allowed_domains: Some(vec!["docs.rs".to_string(), "rust-lang.org".to_string()]),
+        allow_private: false,
+    };
+    assert!(policy.check(&url("https://docs.rs/serde")).is_ok());
+    assert!(policy.check(&url("https://doc.rust-lang.org/std/")).is_ok());
+    assert!(policy.check(&url("https://example.com/")).is_err());
+
+    // A public address passes resolution without needing a DNS lookup
+    let addrs = UrlPolicy::default().resolve(&url("https://1.1.1.1/")).await.unwrap();
+    assert_eq!(addrs, vec!["1.1.1.1:443".parse::<SocketAddr>().unwrap()]);
+}
@@ -7,6 +7,12 @@ pub struct FsReadArgs {
     pub path: String,
     pub range: Option<Range<u64>>,
     pub encoding: Option<String>,
+    /// Return size information instead of contents
+    #[serde(default)]
+    pub summary_only: Option<bool>,
+    /// Most bytes of contents to return (default 1 MiB); 0 for no limit beyond the tool output cap
+    #[serde(default)]
+    pub max_bytes: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,6 +20,26 @@ pub struct FsReadResult {
     pub contents: String,
     pub encoding: String,
     pub truncated: bool,
+    /// The file has no bytes at all, so empty `contents` is not a failed read
+    #[serde(default)]
+    pub is_empty: bool,
+    /// The file holds only whitespace; `contents` keeps it as-is
+    #[serde(default)]
+    pub whitespace_only: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<FsReadSummary>,
+    /// Byte range actually returned, when a cut fell inside a multi-byte character
+    /// and was moved back to that character's start
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub adjusted_range: Option<Range<u64>>,
+}
+
+/// Cheap file statistics returned by `fs.read` with `summary_only`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FsReadSummary {
+    pub line_count: u64,
+    pub byte_size: u64,
+    pub is_binary: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,12 +50,41 @@ pub struct FsSearchArgs {
     pub regex: bool,
     pub case_insensitive: bool,
     pub multiline: bool,
+    /// Literal queries only match at word boundaries (ignored for regex queries)
+    #[serde(default)]
+    pub whole_word: Option<bool>,
+    /// Token from a previous result's `continuation` to fetch the next page
+    #[serde(default)]
+    pub continue_from: Option<String>,
+    /// Files larger than this are skipped without being read (default: GROK_SEARCH_MAX_FILE_BYTES or 10 MiB)
+    #[serde(default)]
+    pub max_file_bytes: Option<u64>,
+    /// Order files by relevance (match count, source dirs over vendored/test dirs) instead of by path
+    #[serde(default)]
+    pub rank: Option<bool>,
+    /// Report where each match sits within its line
+    #[serde(default)]
+    pub include_columns: Option<bool>,
+    /// Longer matched lines are cut to a window around the match (default: GROK_SEARCH_MAX_LINE_BYTES or 1000)
+    #[serde(default)]
+    pub max_line_bytes: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchLine {
     pub ln: u64,
+    /// The matched line; an overlong line is cut around its first match, with `…` marking cut ends
     pub text: String,
+    /// Every match on the line, when `include_columns` is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub columns: Option<Vec<MatchSpan>>,
+}
+
+/// Byte offsets of one match within `SearchLine::text`: `start` inclusive, `end` exclusive, from 0
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatchSpan {
+    pub start: usize,
+    pub end: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,6 +96,12 @@ pub struct SearchMatch {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FsSearchResult {
     pub matches: Vec<SearchMatch>,
+    /// Present when more matches exist; pass back as `continue_from`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub continuation: Option<String>,
+    /// Files passed over because they exceeded `max_file_bytes`
+    #[serde(default)]
+    pub skipped_large_files: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -51,6 +112,9 @@ pub struct FsWriteArgs {
     pub create_if_missing: bool,
     #[serde(default)]
     pub overwrite: bool,
+    /// Largest write accepted (default: GROK_MAX_WRITE_BYTES or 5 MiB)
+    #[serde(default)]
+    pub max_write_bytes: Option<u64>,
 }
 
 fn default_create_if_missing() -> bool {
@@ -60,6 +124,28 @@ fn default_create_if_missing() -> bool {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FsWriteResult {
     pub bytes_written: u64,
+    /// Set when an existing file with different contents was replaced
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overwrite: Option<FsOverwriteStats>,
+}
+
+/// How far an overwrite moved a file from what it held before
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FsOverwriteStats {
+    pub previous_lines: u64,
+    pub new_lines: u64,
+    pub lines_added: u64,
+    pub lines_removed: u64,
+}
+
+impl FsOverwriteStats {
+    /// Warning line for the tools panel, e.g. "Overwrote 200 lines with 5 (+3 -198)"
+    pub fn describe(&self) -> String {
+        format!(
+            "Overwrote {} lines with {} (+{} -{})",
+            self.previous_lines, self.new_lines, self.lines_added, self.lines_removed
+        )
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -71,6 +157,26 @@ pub enum SimpleEditOp {
     InsertAfter { path: String, anchor: String, insert: String },
     DeleteFile { path: String },
     RenameFile { path: String, to: String },
+    /// Create an empty file (and its parent directories); fails if the path exists
+    CreateEmpty { path: String },
+    /// Create a directory and its parents; an existing directory is left as is
+    CreateDir { path: String },
+}
+
+impl SimpleEditOp {
+    /// Op kind and path(s), as listed in summaries and approval prompts
+    pub fn describe(&self) -> String {
+        match self {
+            SimpleEditOp::SetFile { path, .. } => format!("set_file {}", path),
+            SimpleEditOp::ReplaceOnce { path, .. } => format!("replace_once {}", path),
+            SimpleEditOp::InsertBefore { path, .. } => format!("insert_before {}", path),
+            SimpleEditOp::InsertAfter { path, .. } => format!("insert_after {}", path),
+            SimpleEditOp::DeleteFile { path } => format!("delete_file {}", path),
+            SimpleEditOp::RenameFile { path, to } => format!("rename_file {} -> {}", path, to),
+            SimpleEditOp::CreateEmpty { path } => format!("create_empty {}", path),
+            SimpleEditOp::CreateDir { path } => format!("create_dir {}", path),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -85,6 +191,9 @@ pub struct FsApplyPatchResult {
     pub success: bool,
     pub rejected_hunks: Option<Vec<String>>,
     pub summary: String,
+    /// Unified diff of the planned changes (also for dry runs); absent when nothing changed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diff: Option<String>,
 }
 
 // Individual operation argument types
@@ -132,6 +241,9 @@ pub struct FsRenameFileArgs {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FsSimpleOpResult {
     pub success: bool,
+    /// Where `fs.delete_file` moved the file, unless hard delete is on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trash_path: Option<String>,
 }
 
 // File finding tool types
@@ -140,10 +252,16 @@ pub struct FsFindArgs {
     pub pattern: String,
     pub base_path: Option<String>,
     pub fuzzy: Option<bool>,
+    /// Treat `pattern` as a regex over file names (full paths when it contains `/`); excludes `fuzzy`
+    #[serde(default)]
+    pub regex: Option<bool>,
     pub case_sensitive: Option<bool>,
     pub file_type: Option<String>, // "file", "dir", "both"
     pub max_results: Option<u32>,
     pub ignore_patterns: Option<Vec<String>>, // gitignore-style patterns
+    /// Token from a previous result's `continuation` to fetch the next page
+    #[serde(default)]
+    pub continue_from: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -157,6 +275,25 @@ pub struct FileMatch {
 pub struct FsFindResult {
     pub matches: Vec<FileMatch>,
     pub search_time_ms: u64,
+    /// Present when more matches exist; pass back as `continue_from`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub continuation: Option<String>,
+}
+
+// Directory tree tool types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FsTreeArgs {
+    pub path: Option<String>,
+    pub max_depth: Option<usize>,
+    pub max_nodes: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FsTreeResult {
+    pub tree: String,
+    pub total_dirs: u64,
+    pub total_files: u64,
+    pub truncated: bool,
 }
 
 // Code analysis tool types
@@ -183,6 +320,56 @@ pub struct CodeSymbolsResult {
     pub language: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodeTestArgs {
+    pub path: Option<String>, // project root, defaults to the working directory
+    pub filter: Option<String>, // only run tests matching this name
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodeTestResult {
+    pub project_type: String, // "cargo", "npm", "pytest" or "go"
+    pub command: Vec<String>,
+    pub exit_code: i32,
+    pub duration_ms: u64,
+    /// `None` when the runner's output format isn't understood
+    pub passed: Option<u64>,
+    pub failed: Option<u64>,
+    pub failing_tests: Vec<String>,
+    /// Combined stdout and stderr, only included when the counts couldn't be parsed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw_output: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodeBuildArgs {
+    pub path: Option<String>, // project root, defaults to the working directory
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CodeDiagnostic {
+    pub file: String,
+    pub line: u32,
+    pub column: u32,
+    pub severity: String, // "error", "warning", "note", ...
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodeBuildResult {
+    pub project_type: String,
+    pub command: Vec<String>,
+    pub exit_code: i32,
+    pub duration_ms: u64,
+    pub success: bool,
+    pub diagnostics: Vec<CodeDiagnostic>,
+    /// Combined stdout and stderr, only included when a failed build produced no parseable diagnostics
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw_output: Option<String>,
+}
+
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CodeFile {
@@ -202,6 +389,12 @@ pub struct ShellExecArgs {
     pub timeout_ms: Option<u64>,
     pub with_escalated_permissions: Option<bool>,
     pub justification: Option<String>,
+    /// Expand `$VAR` / `${VAR}` in each argument before spawning
+    #[serde(default)]
+    pub expand_env: Option<bool>,
+    /// With `expand_env`, replace undefined variables with "" instead of leaving them as written
+    #[serde(default)]
+    pub undefined_env_empty: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -218,6 +411,8 @@ pub struct LargeContextFetchArgs {
     pub user_query: String,
     pub base_path: Option<String>,
     pub max_files: Option<u32>,
+    /// Most relevant files to return (default 10)
+    pub max_return_files: Option<u32>,
     pub include_extensions: Option<Vec<String>>,
     pub exclude_patterns: Option<Vec<String>>,
 }
@@ -230,3 +425,25 @@ pub struct LargeContextFetchResult {
     pub total_files_returned: u32,
     pub execution_time_ms: u64,
 }
+
+// HTTP fetch tool types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpFetchArgs {
+    pub url: String,
+    /// Most bytes of the response body to download (default 512 KiB)
+    pub max_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpFetchResult {
+    /// URL the body came from, after redirects
+    pub url: String,
+    pub status: u16,
+    pub content_type: Option<String>,
+    /// Response text; HTML pages are converted to plain text
+    pub body: String,
+    /// Bytes of the body downloaded before conversion
+    pub bytes_read: u64,
+    /// The body was cut off at `max_bytes`
+    pub truncated: bool,
+}
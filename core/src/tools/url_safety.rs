@@ -0,0 +1,144 @@
+//! Checks for URLs the model asks a tool to request
+//!
+//! Network tools act on the model's behalf, so a URL is only requested when it uses http(s),
+//! its host is on the optional `GROK_FETCH_ALLOW_DOMAINS` allowlist, and every address the
+//! host resolves to is public. Callers connect to the addresses returned by
+//! [`UrlPolicy::resolve`] rather than looking the host up again, so a DNS answer that changes
+//! between the check and the request (DNS rebinding) can't redirect it to a private address.
+
+use reqwest::Url;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+/// What outbound URLs a tool may request
+#[derive(Debug, Clone, Default)]
+pub struct UrlPolicy {
+    /// Only these domains and their subdomains, when set
+    pub allowed_domains: Option<Vec<String>>,
+    /// Permit loopback, private and link-local addresses (tests against a local server)
+    pub allow_private: bool,
+}
+
+impl UrlPolicy {
+    /// Private addresses blocked, allowlist read from `GROK_FETCH_ALLOW_DOMAINS`
+    pub fn from_env() -> Self {
+        Self {
+            allowed_domains: std::env::var("GROK_FETCH_ALLOW_DOMAINS")
+                .ok()
+                .and_then(|raw| parse_domains(&raw)),
+            allow_private: false,
+        }
+    }
+
+    /// Reject `url` on its scheme, an allowlist miss, or a literal local/private host.
+    /// Hostnames still need [`UrlPolicy::resolve`] before they are safe to request.
+    pub fn check(&self, url: &Url) -> Result<(), String> {
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(format!("Only http and https URLs can be fetched, got: {}", url.scheme()));
+        }
+        let host = normalized_host(url)?;
+
+        if let Some(allowed) = &self.allowed_domains {
+            let permitted = allowed
+                .iter()
+                .any(|domain| host == *domain || host.ends_with(&format!(".{}", domain)));
+            if !permitted {
+                return Err(format!("Host {} is not in GROK_FETCH_ALLOW_DOMAINS", host));
+            }
+        }
+
+        if !self.allow_private {
+            let blocked = match literal_ip(&host) {
+                Some(ip) => is_private_ip(ip),
+                None => host == "localhost" || host.ends_with(".localhost"),
+            };
+            if blocked {
+                return Err(format!("Refusing to fetch from a local or private address: {}", host));
+            }
+        }
+        Ok(())
+    }
+
+    /// [`UrlPolicy::check`] `url`, then resolve its host and reject it if any address is
+    /// local or private. Returns the addresses the request must connect to.
+    pub async fn resolve(&self, url: &Url) -> Result<Vec<SocketAddr>, String> {
+        self.check(url)?;
+        let host = normalized_host(url)?;
+        let port = url.port_or_known_default().unwrap_or(80);
+
+        let addrs: Vec<SocketAddr> = match literal_ip(&host) {
+            Some(ip) => vec![SocketAddr::new(ip, port)],
+            None => tokio::net::lookup_host((host.as_str(), port))
+                .await
+                .map_err(|e| format!("Failed to resolve {}: {}", host, e))?
+                .collect(),
+        };
+        if addrs.is_empty() {
+            return Err(format!("{} did not resolve to any address", host));
+        }
+
+        if !self.allow_private {
+            if let Some(addr) = addrs.iter().find(|addr| is_private_ip(addr.ip())) {
+                return Err(format!(
+                    "Refusing to fetch from {}: it resolves to a local or private address ({})",
+                    host,
+                    addr.ip()
+                ));
+            }
+        }
+        Ok(addrs)
+    }
+}
+
+/// Comma-separated domains, lowercased, with leading dots dropped
+fn parse_domains(raw: &str) -> Option<Vec<String>> {
+    let domains: Vec<String> = raw
+        .split(',')
+        .map(|d| d.trim().trim_start_matches('.').to_ascii_lowercase())
+        .filter(|d| !d.is_empty())
+        .collect();
+    (!domains.is_empty()).then_some(domains)
+}
+
+fn normalized_host(url: &Url) -> Result<String, String> {
+    Ok(url
+        .host_str()
+        .ok_or_else(|| format!("URL has no host: {}", url))?
+        .trim_end_matches('.')
+        .to_ascii_lowercase())
+}
+
+/// The address a host names directly; IPv6 literals keep their brackets in `host_str`
+fn literal_ip(host: &str) -> Option<IpAddr> {
+    host.trim_start_matches('[').trim_end_matches(']').parse().ok()
+}
+
+/// Loopback, private, link-local, unspecified and other non-public addresses
+pub fn is_private_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_private_ipv4(ip),
+        IpAddr::V6(ip) => {
+            if let Some(v4) = ip.to_ipv4_mapped() {
+                return is_private_ipv4(v4);
+            }
+            let first = ip.segments()[0];
+            ip.is_loopback()
+                || ip.is_unspecified()
+                // Unique local (fc00::/7) and link-local (fe80::/10)
+                || (first & 0xfe00) == 0xfc00
+                || (first & 0xffc0) == 0xfe80
+        }
+    }
+}
+
+fn is_private_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        // "This network" (0.0.0.0/8) and carrier-grade NAT (100.64.0.0/10)
+        || a == 0
+        || (a == 100 && (64..128).contains(&b))
+}
+
@@ -1,16 +1,16 @@
 use anyhow::Result;
 use crossterm::event;
 use grok_core::{AppEvent, Session};
-use ratatui::{backend::Backend, Frame, Terminal};
+use ratatui::{backend::Backend, Frame};
 use std::time::Duration;
 use tokio::sync::mpsc;
 use tracing::info;
 
 use crate::{
-    components::{ChatComponent, InputComponent, ToolsComponent, StatusComponent, CommandPaletteComponent},
+    components::{ChatComponent, InputComponent, ToolsComponent, StatusComponent, CommandPaletteComponent, ToastComponent, ApprovalComponent},
     handlers::{InputHandler, EventHandler},
     state::AppState,
-    utils::{layout, terminal},
+    utils::{layout, terminal::{self, TerminalGuard}},
 };
 
 /// Main application
@@ -28,31 +28,30 @@ impl App {
             .unwrap_or_else(|| std::path::Path::new("."))
             .join("chats");
         Self {
-            state: AppState::new(session, event_receiver, chats_dir),
+            // The approval modal answers `fs.apply_patch` requests
+            state: AppState::new(session.with_patch_approval(true), event_receiver, chats_dir),
         }
     }
     
     /// Run the application main loop
     pub async fn run(&mut self) -> Result<()> {
-        // Setup terminal
-        let mut terminal = terminal::setup()?;
+        // A panic mid-render would otherwise leave the shell in raw mode
+        terminal::install_panic_hook();
+
+        // Restored when the guard drops, however the loop exits
+        let mut terminal = TerminalGuard::new()?;
         
         info!("TUI initialized, starting main loop");
         
-        // Main application loop
-        let result = self.run_app(&mut terminal).await;
-        
-        // Restore terminal
-        terminal::restore(&mut terminal)?;
-        
-        result
+        self.run_app(&mut terminal).await
     }
     
     /// Main application loop
-    async fn run_app<B: Backend + std::io::Write>(&mut self, terminal: &mut Terminal<B>) -> Result<()> {
+    async fn run_app<B: Backend + std::io::Write>(&mut self, terminal: &mut TerminalGuard<B>) -> Result<()> {
         loop {
             // Update cursor blinking
             self.state.update_cursor_blink();
+            self.state.toasts.expire(std::time::Instant::now());
 
             // Draw UI
             terminal.draw(|f| self.ui(f))?;
@@ -86,6 +85,26 @@ impl App {
             if self.state.should_quit {
                 break;
             }
+
+            if let Some(path) = self.state.pending_edit.take() {
+                self.edit_file(terminal, &path)?;
+            }
+
+            if let Some((number, block)) = self.state.pending_copy.take() {
+                match crate::clipboard::copy(&block) {
+                    Ok(()) => self.state.session.add_system_message(format!(
+                        "Copied code block {} ({} lines) to the clipboard.",
+                        number,
+                        block.lines().count()
+                    )),
+                    Err(e) => self.state.session.add_error_message(format!("Failed to copy code block {}: {}", number, e)),
+                }
+                self.state.scroll_chat_to_bottom();
+            }
+        }
+
+        if let Some(log) = self.state.event_log.as_mut() {
+            log.flush();
         }
 
         // Auto-save on exit if there's history
@@ -96,6 +115,30 @@ impl App {
         Ok(())
     }
     
+    /// Suspend the TUI, run `$EDITOR` on `path` until it exits, then resume and tell
+    /// the agent the file may have changed
+    fn edit_file<B: Backend + std::io::Write>(&mut self, terminal: &mut TerminalGuard<B>, path: &std::path::Path) -> Result<()> {
+        let Some(editor) = crate::editor::editor_from_env() else {
+            self.state.session.add_error_message(crate::editor::NO_EDITOR_MESSAGE.to_string());
+            return Ok(());
+        };
+
+        terminal.suspend()?;
+        let status = crate::editor::editor_command(&editor, path).status();
+        terminal.resume()?;
+
+        match status {
+            Ok(status) if status.success() => self.state.session.add_system_message(format!(
+                "The user edited {} in their editor; it may have changed since it was last read.",
+                path.display()
+            )),
+            Ok(status) => self.state.session.add_error_message(format!("{} exited with {}", editor[0], status)),
+            Err(e) => self.state.session.add_error_message(format!("Failed to start {}: {}", editor[0], e)),
+        }
+        self.state.scroll_chat_to_bottom();
+        Ok(())
+    }
+
     /// Draw the user interface
     fn ui(&mut self, f: &mut Frame) {
         let main_chunks = layout::create_main_layout(f.size());
@@ -108,10 +151,16 @@ impl App {
         ToolsComponent::render(&mut self.state, f, top_chunks[1]);
         InputComponent::render(&mut self.state, f, main_chunks[1]);
         StatusComponent::render(&self.state, f, main_chunks[2]);
+        ToastComponent::render(&self.state, f);
 
         // Command palette overlay (render on top)
         if self.state.command_palette_open {
             CommandPaletteComponent::render(&mut self.state, f);
         }
+
+        // Edit approval modal (above everything else)
+        if self.state.pending_approval.is_some() {
+            ApprovalComponent::render(&self.state, f);
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,30 @@
+//! Copying text to the system clipboard for `/copyblock`
+//!
+//! Uses the OSC 52 terminal escape, so it works over SSH and inside the TUI without a
+//! clipboard library. Terminals that don't support it ignore the sequence; tmux needs
+//! `set-clipboard on`.
+
+use base64::Engine;
+use std::io::Write;
+
+/// The OSC 52 sequence that puts `text` on the clipboard
+pub fn osc52_sequence(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", base64::engine::general_purpose::STANDARD.encode(text))
+}
+
+/// Write `text` to the clipboard through the terminal on stdout
+pub fn copy(text: &str) -> std::io::Result<()> {
+    let mut stdout = std::io::stdout();
+    stdout.write_all(osc52_sequence(text).as_bytes())?;
+    stdout.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_osc52_sequence() {
+        assert_eq!(osc52_sequence("fn main() {}\n"), "\x1b]52;c;Zm4gbWFpbigpIHt9Cg==\x07");
+    }
+}
@@ -0,0 +1,86 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use crate::state::AppState;
+
+/// Component for the modal that asks which `fs.apply_patch` ops to apply
+pub struct ApprovalComponent;
+
+impl ApprovalComponent {
+    /// Render the op list with the selected op's diff underneath
+    pub fn render(state: &AppState, f: &mut Frame) {
+        let Some(pending) = state.pending_approval.as_ref() else {
+            return;
+        };
+
+        // Centered, 80% of the screen
+        let area = f.size();
+        let popup_width = area.width * 80 / 100;
+        let popup_height = area.height * 80 / 100;
+        let popup_area = Rect {
+            x: (area.width - popup_width) / 2,
+            y: (area.height - popup_height) / 2,
+            width: popup_width,
+            height: popup_height,
+        };
+
+        f.render_widget(Clear, popup_area);
+
+        let approved_count = pending.approved.iter().filter(|a| **a).count();
+        let mut lines = vec![
+            Line::from(Span::styled(
+                format!("The agent wants to apply {} edit(s); {} selected", pending.approved.len(), approved_count),
+                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+        ];
+
+        for (i, (op, approved)) in pending.request.ops.iter().zip(&pending.approved).enumerate() {
+            let is_selected = i == pending.selected;
+            let style = if is_selected {
+                Style::default().bg(Color::DarkGray).fg(Color::White).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            lines.push(Line::from(vec![
+                Span::styled(if is_selected { "► " } else { "  " }, style),
+                Span::styled(
+                    if *approved { "[x] " } else { "[ ] " },
+                    style.fg(if *approved { Color::Green } else { Color::Red }),
+                ),
+                Span::styled(op.description.clone(), style),
+            ]));
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "↑↓ Select • Space Toggle • Enter Apply selected • a Approve all • r/Esc Reject all",
+            Style::default().fg(Color::DarkGray),
+        )));
+        lines.push(Line::from(""));
+
+        if let Some(op) = pending.request.ops.get(pending.selected) {
+            if op.diff.is_empty() {
+                lines.push(Line::from(Span::styled("(no content changes)", Style::default().fg(Color::DarkGray))));
+            } else {
+                lines.extend(crate::diff::render_diff(&op.diff));
+            }
+        }
+
+        let popup = Paragraph::new(Text::from(lines))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+                    .title(" Approve edits ")
+                    .title_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+            )
+            .wrap(ratatui::widgets::Wrap { trim: false });
+
+        f.render_widget(popup, popup_area);
+    }
+}
@@ -5,31 +5,62 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState},
     Frame,
 };
-use crate::state::AppState;
+use crate::state::{AppState, follow_scroll};
 use serde_json::Value;
 use std::time::SystemTime;
 
 /// Component for rendering the chat panel
 pub struct ChatComponent;
 
+/// Messages older than this show a date instead of an age
+const ABSOLUTE_TIME_AFTER_SECS: u64 = 7 * 86400;
+
 fn format_relative_time(tm: &SystemTime) -> String {
     let now = SystemTime::now();
     if let Ok(dur) = now.duration_since(*tm) {
-        let secs = dur.as_secs();
-        if secs < 60 {
-            "just now".to_string()
-        } else if secs < 3600 {
-            format!("{}m ago", secs / 60)
-        } else if secs < 86400 {
-            format!("{}h ago", secs / 3600)
-        } else {
-            format!("{}d ago", secs / 86400)
-        }
+        format_elapsed(dur.as_secs())
     } else {
         "long ago".to_string()
     }
 }
 
+fn format_elapsed(secs: u64) -> String {
+    if secs < 60 {
+        "just now".to_string()
+    } else if secs < 3600 {
+        format!("{}m ago", secs / 60)
+    } else if secs < 86400 {
+        format!("{}h ago", secs / 3600)
+    } else {
+        format!("{}d ago", secs / 86400)
+    }
+}
+
+/// Age of a message sent at `timestamp_secs`: relative for the last week, then a UTC date
+fn format_message_time(timestamp_secs: u64, now_secs: u64) -> String {
+    let elapsed = now_secs.saturating_sub(timestamp_secs);
+    if elapsed < ABSOLUTE_TIME_AFTER_SECS {
+        format_elapsed(elapsed)
+    } else {
+        let (year, month, day) = civil_date(timestamp_secs / 86400);
+        format!("{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+
+/// Days since the unix epoch to a (year, month, day) calendar date
+fn civil_date(days: u64) -> (u64, u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = era * 400 + yoe + u64::from(month <= 2);
+    (year, month, day)
+}
+
 impl ChatComponent {
     /// Render the chat messages
     pub fn render(state: &mut AppState, f: &mut Frame, area: Rect) {
@@ -38,7 +69,7 @@ impl ChatComponent {
             for (i, chat) in state.available_chats.iter().enumerate() {
                 let prefix = if i == state.selected_chat_index { "> " } else { "  " };
                 let time_str = format_relative_time(&chat.last_modified);
-                let line = format!("{} {} ({} ago)", prefix, chat.title, time_str);
+                let line = format!("{} {} ({})", prefix, chat.title, time_str);
                 lines.push(Line::from(line));
             }
             lines.push(Line::from(" "));
@@ -53,26 +84,53 @@ impl ChatComponent {
             let mut chat_lines = Vec::new();
             let available_width = area.width.saturating_sub(4) as usize; // Account for borders and padding
 
+            // Prose stops at the reading column; code blocks keep the full width
+            let prose_width = state.chat_column.map_or(available_width, |column| column.min(available_width));
+
             // If width is too small, don't wrap to avoid issues
-            let should_wrap = available_width >= 10;
+            let should_wrap = prose_width >= 10;
             
+            let now_secs = SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default();
+
+            // Code blocks are numbered across the conversation, matching `/copyblock`
+            let mut next_code_block = 1;
+
             // NOTE: Include tool messages in the chat render so they are not hidden
             for msg in state.session.messages() {
+                // Recomputed every frame, so ages stay current while the app is open
+                let mut meta = Vec::new();
+                if state.show_timestamps {
+                    meta.push(format_message_time(msg.timestamp_secs, now_secs));
+                }
+                if msg.pinned {
+                    meta.push("📌 pinned".to_string());
+                }
+                if !meta.is_empty() {
+                    chat_lines.push(Line::from(Span::styled(meta.join(" · "), Style::default().fg(Color::DarkGray))));
+                }
+
                 match msg.role {
                     grok_core::MessageRole::User => {
-                        Self::render_user_message(&mut chat_lines, &msg.content, available_width, should_wrap);
+                        Self::render_user_message(&mut chat_lines, &msg.content, prose_width, should_wrap);
                     }
                     grok_core::MessageRole::Agent => {
-                        Self::render_agent_message(&mut chat_lines, &msg.content, available_width);
+                        Self::render_agent_message(&mut chat_lines, &msg.content, prose_width, available_width, state.link_style, &mut next_code_block);
+                    }
+                    grok_core::MessageRole::System if msg.kind == grok_core::MessageKind::FilePreview => {
+                        // File previews keep their line layout and highlight the target line
+                        chat_lines.extend(crate::preview::preview_lines(&msg.content));
                     }
                     grok_core::MessageRole::System => {
-                        Self::render_system_message(&mut chat_lines, &msg.content, available_width, should_wrap);
+                        Self::render_system_message(&mut chat_lines, &msg.content, prose_width, should_wrap);
                     }
                     grok_core::MessageRole::Error => {
-                        Self::render_error_message(&mut chat_lines, &msg.content, available_width, should_wrap);
+                        Self::render_error_message(&mut chat_lines, &msg.content, prose_width, should_wrap);
                     }
                     grok_core::MessageRole::Tool => {
-                        Self::render_tool_message(&mut chat_lines, msg.tool_info.as_ref(), available_width, should_wrap);
+                        Self::render_tool_message(&mut chat_lines, msg.tool_info.as_ref(), prose_width, should_wrap);
                     }
                 }
                 
@@ -80,20 +138,25 @@ impl ChatComponent {
                 chat_lines.push(Line::from(""));
             }
 
+            // Transient status line for a running command; replaced on each chunk
+            if let Some(line) = state.live_output.line() {
+                chat_lines.push(Line::from(Span::styled(
+                    format!("  ⟳ {}", line),
+                    Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
+                )));
+            }
+
             // Calculate scroll limits
             let content_height = chat_lines.len();
             let visible_height = area.height.saturating_sub(2) as usize; // Account for borders
             let max_scroll = content_height.saturating_sub(visible_height);
             
-            // Auto-scroll to bottom if enabled and there's new content
-            let scroll_pos = if state.auto_scroll_chat {
-                max_scroll
-            } else {
-                state.chat_scroll.min(max_scroll)
-            };
+            // Follow new content only if the user was already near the bottom
+            let scroll_pos = follow_scroll(state.chat_scroll, state.chat_max_scroll, max_scroll, state.scroll_stick_lines);
             
             // Update the stored scroll position to prevent phantom scrolling
             state.chat_scroll = scroll_pos;
+            state.chat_max_scroll = max_scroll;
 
             // Slice visible content
             let visible_lines = if content_height > visible_height {
@@ -153,7 +216,14 @@ impl ChatComponent {
         Self::add_wrapped_text(chat_lines, &content, style, available_width, should_wrap);
     }
 
-    fn render_agent_message(chat_lines: &mut Vec<Line>, content: &str, available_width: usize) {
+    fn render_agent_message(
+        chat_lines: &mut Vec<Line>,
+        content: &str,
+        prose_width: usize,
+        available_width: usize,
+        link_style: crate::markdown::LinkStyle,
+        next_code_block: &mut usize,
+    ) {
         // Agent messages - parse markdown
         // Add a subtle indicator that this is an agent response
         chat_lines.push(Line::from(Span::styled(
@@ -161,12 +231,19 @@ impl ChatComponent {
             Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)
         )));
         
-        let markdown_lines = crate::markdown::parse_markdown(content);
-        let wrapped_lines = crate::markdown::wrap_markdown_lines(markdown_lines, available_width);
+        let markdown_lines = crate::markdown::parse_markdown(content, link_style);
+        let mut wrapped_lines = crate::markdown::wrap_markdown_lines_at(markdown_lines, prose_width, available_width);
+        crate::markdown::label_code_blocks(&mut wrapped_lines, next_code_block);
         chat_lines.extend(wrapped_lines);
     }
 
     fn render_system_message(chat_lines: &mut Vec<Line>, content: &str, available_width: usize, should_wrap: bool) {
+        // `/diff` comparisons are colored like patch diffs
+        if crate::diff::is_comparison(content) {
+            chat_lines.extend(crate::diff::comparison_lines(content));
+            return;
+        }
+
         // System messages - simple styling
         let style = Style::default().fg(Color::Yellow);
         Self::add_wrapped_text(chat_lines, content, style, available_width, should_wrap);
@@ -226,17 +303,19 @@ impl ChatComponent {
     }
 
     fn add_wrapped_text(chat_lines: &mut Vec<Line>, content: &str, style: Style, available_width: usize, should_wrap: bool) {
-        if content.len() <= available_width && should_wrap {
-            chat_lines.push(Line::from(Span::styled(content.to_string(), style)));
-        } else if should_wrap {
+        // Reports like /history and /stats put one entry per line, so each line wraps on its own
+        for text in content.split('\n') {
+            if !should_wrap || text.len() <= available_width {
+                chat_lines.push(Line::from(Span::styled(text.to_string(), style)));
+                continue;
+            }
+
             // Wrap text
-            let words: Vec<&str> = content.split_whitespace().collect();
             let mut current_line = String::new();
-
-            for word in words {
+            for word in text.split_whitespace() {
                 if current_line.is_empty() {
                     current_line = word.to_string();
-                } else if current_line.len() + word.len() + 1 <= available_width {
+                } else if current_line.len() + word.len() < available_width {
                     current_line.push(' ');
                     current_line.push_str(word);
                 } else {
@@ -248,9 +327,51 @@ impl ChatComponent {
             if !current_line.is_empty() {
                 chat_lines.push(Line::from(Span::styled(current_line, style)));
             }
-        } else {
-            // Don't wrap - just add as single line
-            chat_lines.push(Line::from(Span::styled(content.to_string(), style)));
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_message_time() {
+        let now = 1_760_000_000;
+        assert_eq!(format_message_time(now - 5, now), "just now");
+        assert_eq!(format_message_time(now - 150, now), "2m ago");
+        assert_eq!(format_message_time(now - 3 * 3600, now), "3h ago");
+        assert_eq!(format_message_time(now - 2 * 86400, now), "2d ago");
+        // 2025-01-01T00:00:00Z, well over a week before `now`
+        assert_eq!(format_message_time(1_735_689_600, now), "2025-01-01");
+    }
+
+    #[test]
+    fn test_system_report_keeps_one_entry_per_line() {
+        let bus = grok_core::EventBus::new();
+        let agent = grok_core::agent::agent_logic::MultiModelAgent::new("test-key".to_string(), "test-model".to_string(), bus.sender())
+            .expect("agent should build");
+        let mut session = grok_core::Session::new(std::sync::Arc::new(agent), bus.sender());
+        session.add_user_message("first question".to_string());
+        session.add_agent_message("an answer long enough that it has to wrap in a narrow chat panel".to_string());
+        let listing = session.history_listing();
+
+        let text = |lines: &[Line]| lines.iter().map(|l| l.spans.iter().map(|s| s.content.as_ref()).collect::<String>()).collect::<Vec<_>>();
+        let mut lines = Vec::new();
+        ChatComponent::render_system_message(&mut lines, &listing, 200, true);
+        let rendered = text(&lines);
+        assert_eq!(rendered.len(), 3);
+        assert_eq!(rendered[0], "History (2 messages):");
+        assert!(rendered[1].trim_start().starts_with("1. [user] first question"));
+        assert!(rendered[2].trim_start().starts_with("2. [assistant]"));
+
+        // A narrow panel wraps within an entry but never joins two entries
+        let mut lines = Vec::new();
+        ChatComponent::render_system_message(&mut lines, &listing, 30, true);
+        let rendered = text(&lines);
+        assert!(rendered.len() > 3);
+        assert!(rendered.iter().all(|line| !line.contains('\n')));
+        assert!(rendered.iter().any(|line| line.trim_start() == "1. [user] first question"));
+        assert!(rendered.iter().any(|line| line.starts_with("2. [assistant]")));
+    }
+}
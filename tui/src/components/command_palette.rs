@@ -5,7 +5,7 @@ use ratatui::{
     widgets::{Block, Borders, Clear, Paragraph},
     Frame,
 };
-use crate::state::{AppState, Command};
+use crate::state::AppState;
 
 /// Component for rendering the command palette overlay
 pub struct CommandPaletteComponent;
@@ -32,7 +32,7 @@ impl CommandPaletteComponent {
         f.render_widget(Clear, popup_area);
 
         // Get filtered commands
-        let filtered_commands = Self::get_filtered_commands(state);
+        let filtered_commands = state.filtered_commands();
 
         // Prepare command list text
         let mut lines = vec![
@@ -71,6 +71,10 @@ impl CommandPaletteComponent {
                         cmd.name.clone(),
                         style.fg(if is_selected { Color::Yellow } else { Color::Green }),
                     ),
+                    Span::styled(
+                        cmd.args_hint.as_ref().map(|hint| format!(" {}", hint)).unwrap_or_default(),
+                        style.fg(Color::DarkGray),
+                    ),
                 ]));
 
                 // Command description
@@ -90,7 +94,7 @@ impl CommandPaletteComponent {
 
         lines.push(Line::from(""));
         lines.push(Line::from(Span::styled(
-            "↑↓ Navigate • Enter Select • Esc Close",
+            "↑↓ Navigate • Space then text adds an argument • Enter Select • Esc Close",
             Style::default().fg(Color::DarkGray),
         )));
 
@@ -107,25 +111,4 @@ impl CommandPaletteComponent {
 
         f.render_widget(popup, popup_area);
     }
-
-    /// Get filtered commands based on current filter
-    fn get_filtered_commands(state: &AppState) -> Vec<&Command> {
-        state
-            .available_commands
-            .iter()
-            .filter(|cmd| {
-                if state.command_palette_filter.is_empty() {
-                    true
-                } else {
-                    cmd.name
-                        .to_lowercase()
-                        .contains(&state.command_palette_filter.to_lowercase())
-                        || cmd
-                            .description
-                            .to_lowercase()
-                            .contains(&state.command_palette_filter.to_lowercase())
-                }
-            })
-            .collect()
-    }
 }
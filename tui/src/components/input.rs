@@ -1,10 +1,44 @@
 use ratatui::{
-    layout::Rect,
+    layout::{Alignment, Rect},
     style::{Color, Style, Modifier},
-    widgets::{Block, Borders, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState},
+    text::Span,
+    widgets::{
+        block::{Position, Title},
+        Block, Borders, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState,
+    },
     Frame,
 };
-use crate::state::AppState;
+use crate::state::{AppState, SubmitMode};
+
+/// Size of the text in the input box, shown under it while typing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputCounter {
+    pub chars: usize,
+    pub lines: usize,
+    /// Rough token estimate at four characters per token
+    pub tokens: usize,
+    /// The estimate has reached the warning threshold
+    pub warn: bool,
+}
+
+impl InputCounter {
+    /// Count `input`, warning once the estimate reaches `warn_tokens` (0 never warns)
+    pub fn new(input: &str, warn_tokens: usize) -> Self {
+        let chars = input.chars().count();
+        let tokens = chars.div_ceil(4);
+        Self {
+            chars,
+            lines: input.split('\n').count(),
+            tokens,
+            warn: warn_tokens > 0 && tokens >= warn_tokens,
+        }
+    }
+
+    pub fn label(&self) -> String {
+        let lines = if self.lines > 1 { format!("{} lines · ", self.lines) } else { String::new() };
+        format!(" {}{} chars · ~{} tokens ", lines, self.chars, self.tokens)
+    }
+}
 
 /// Component for rendering the input panel
 pub struct InputComponent;
@@ -85,11 +119,7 @@ impl InputComponent {
             .collect();
 
         // Adjust cursor position for scrolling
-        let visible_cursor_line = if cursor_line >= scroll_pos {
-            cursor_line - scroll_pos
-        } else {
-            0
-        };
+        let visible_cursor_line = cursor_line.saturating_sub(scroll_pos);
 
         // Create the display text
         let display_text = visible_lines.join("\n");
@@ -101,16 +131,36 @@ impl InputComponent {
         };
 
         let title = if state.focused_panel == 0 {
-            " Input [FOCUSED] (Enter to send, Tab to switch focus) "
+            let send = match state.submit_mode {
+                SubmitMode::Enter => "Enter",
+                SubmitMode::CtrlEnter => "Ctrl+Enter or Alt+Enter",
+                SubmitMode::DoubleEnter => "Enter twice",
+            };
+            format!(" Input [FOCUSED] ({} to send, Tab to switch focus) ", send)
         } else {
-            " Input "
+            " Input ".to_string()
         };
 
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(border_style)
+            .title(title);
+        if !state.input.is_empty() {
+            let counter = InputCounter::new(&state.input, state.input_token_warn);
+            let style = if counter.warn {
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            };
+            block = block.title(
+                Title::from(Span::styled(counter.label(), style))
+                    .position(Position::Bottom)
+                    .alignment(Alignment::Right),
+            );
+        }
+
         let input_widget = Paragraph::new(display_text)
-            .block(Block::default()
-                .borders(Borders::ALL)
-                .border_style(border_style)
-                .title(title))
+            .block(block)
             .wrap(ratatui::widgets::Wrap { trim: false });
 
         f.render_widget(input_widget, area);
@@ -142,3 +192,24 @@ impl InputComponent {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_counter_tracks_input_and_warns_at_threshold() {
+        let counter = InputCounter::new("héllo\nworld", 100);
+        assert_eq!(counter.chars, 11);
+        assert_eq!(counter.lines, 2);
+        assert_eq!(counter.tokens, 3);
+        assert!(!counter.warn);
+        assert_eq!(counter.label(), " 2 lines · 11 chars · ~3 tokens ");
+
+        let long = "a".repeat(400);
+        assert!(!InputCounter::new(&long[..396], 100).warn);
+        assert!(InputCounter::new(&long, 100).warn);
+        // A zero threshold turns the warning off
+        assert!(!InputCounter::new(&long, 0).warn);
+    }
+}
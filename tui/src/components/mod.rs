@@ -5,9 +5,13 @@ pub mod input;
 pub mod tools;
 pub mod status;
 pub mod command_palette;
+pub mod toast;
+pub mod approval;
 
 pub use chat::ChatComponent;
 pub use input::InputComponent;
 pub use tools::ToolsComponent;
 pub use status::StatusComponent;
 pub use command_palette::CommandPaletteComponent;
+pub use toast::ToastComponent;
+pub use approval::ApprovalComponent;
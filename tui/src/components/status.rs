@@ -14,15 +14,20 @@ impl StatusComponent {
     pub fn render(state: &AppState, f: &mut Frame, area: Rect) {
         let focus_indicator = match state.focused_panel {
             0 => "Input focused".to_string(),
-            1 => format!("Chat focused{}", if state.auto_scroll_chat { " [Auto-scroll]" } else { "" }),
-            2 => format!("Tools focused{}", if state.auto_scroll_tools { " [Auto-scroll]" } else { "" }),
+            1 => format!("Chat focused{}", if state.chat_near_bottom() { " [Auto-scroll]" } else { "" }),
+            2 => format!("Tools focused{}", if state.tools_near_bottom() { " [Auto-scroll]" } else { "" }),
             _ => "Unknown".to_string(),
         };
         
+        let cwd = state.session.workspace_root();
+        let read_only = if state.session.read_only() { " | read-only" } else { "" };
+        let approval = if state.session.patch_approval() { " | patch approval: ask" } else { " | patch approval: auto" };
+        let model = state.last_model.as_deref().map(|m| format!(" | model: {}", m)).unwrap_or_default();
+        let running = state.running_tools.current().map(|tool| format!(" | Running: {}", tool)).unwrap_or_default();
         let status_text = if state.processing {
-            format!("● Processing... | {} | 'q' to quit, Tab to switch, '/' for commands, ↑↓/scroll wheel to scroll, End to jump to bottom", focus_indicator)
+            format!("● Processing...{}{}{} | {} | {} | 'q' to quit, Tab to switch, '/' for commands, ↑↓/scroll wheel to scroll, End to jump to bottom", running, read_only, approval, cwd.display(), focus_indicator)
         } else {
-            "Ready - Grok Code CLI | / for commands | Tab to switch".to_string()
+            format!("Ready - Grok Code CLI{}{}{} | {} | / for commands | Tab to switch", model, read_only, approval, cwd.display())
         };
         
         let status = Paragraph::new(status_text)
@@ -0,0 +1,48 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use crate::state::AppState;
+
+/// Component for rendering transient notices in the top-right corner
+pub struct ToastComponent;
+
+impl ToastComponent {
+    /// Render active notices over whatever is underneath
+    pub fn render(state: &AppState, f: &mut Frame) {
+        if state.toasts.is_empty() {
+            return;
+        }
+
+        let area = f.size();
+        let lines: Vec<Line> = state
+            .toasts
+            .messages()
+            .map(|message| Line::from(Span::styled(format!("⚠ {}", message), Style::default().fg(Color::Yellow))))
+            .collect();
+
+        // Wide enough for the longest notice, within half the screen
+        let longest = lines.iter().map(|line| line.width()).max().unwrap_or(0) as u16;
+        let width = (longest + 4).min(area.width / 2).max(10.min(area.width));
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let toast_area = Rect {
+            x: area.x + area.width.saturating_sub(width + 1),
+            y: area.y + 1,
+            width,
+            height,
+        };
+
+        f.render_widget(Clear, toast_area);
+        let toast = Paragraph::new(Text::from(lines))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+            )
+            .wrap(ratatui::widgets::Wrap { trim: true });
+        f.render_widget(toast, toast_area);
+    }
+}
@@ -5,8 +5,9 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState},
     Frame,
 };
+use grok_core::tools::executors::floor_char_boundary;
 use grok_core::ToolStatus;
-use crate::state::AppState;
+use crate::state::{AppState, follow_scroll};
 
 /// Component for rendering the tools panel
 pub struct ToolsComponent;
@@ -48,11 +49,11 @@ impl ToolsComponent {
 
         // Sort tools by timestamp (oldest first, so newest appear at bottom)
         let mut sorted_tools: Vec<_> = tool_messages.iter().collect();
-        sorted_tools.sort_by(|a, b| a.timestamp_secs.cmp(&b.timestamp_secs));
+        sorted_tools.sort_by_key(|m| m.timestamp_secs);
         
         for msg in sorted_tools {
             if let Some(ref tool_info) = msg.tool_info {
-                Self::render_tool(&mut all_lines, tool_info, available_width, should_wrap);
+                Self::render_tool(&mut all_lines, tool_info, available_width, should_wrap, state.tool_preview_bytes);
             }
 
             // Add spacing between tools
@@ -70,15 +71,12 @@ impl ToolsComponent {
             0
         };
         
-        // Auto-scroll to bottom if enabled and there's new content
-        let scroll_pos = if state.auto_scroll_tools {
-            max_scroll
-        } else {
-            state.tools_scroll.min(max_scroll)
-        };
+        // Follow new content only if the user was already near the bottom
+        let scroll_pos = follow_scroll(state.tools_scroll, state.tools_max_scroll, max_scroll, state.scroll_stick_lines);
         
         // Update the stored scroll position to prevent phantom scrolling
         state.tools_scroll = scroll_pos;
+        state.tools_max_scroll = max_scroll;
 
         // Slice visible content
         let visible_lines = if content_height > visible_height {
@@ -115,7 +113,13 @@ impl ToolsComponent {
         }
     }
 
-    fn render_tool(all_lines: &mut Vec<Line>, tool: &grok_core::ToolMessageInfo, available_width: usize, should_wrap: bool) {
+    fn render_tool(
+        all_lines: &mut Vec<Line>,
+        tool: &grok_core::ToolMessageInfo,
+        available_width: usize,
+        should_wrap: bool,
+        preview_bytes: usize,
+    ) {
         // Tool header
         let status_icon = match tool.status {
             ToolStatus::Running => "🔄",
@@ -131,7 +135,10 @@ impl ToolsComponent {
 
         // Create a cleaner header format
         let tool_name = format!("{:?}", tool.tool);
-        let header = Self::format_tool_header(&tool_name, &tool.summary, status_icon);
+        let mut header = Self::format_tool_header(&tool_name, &tool.summary, status_icon);
+        if let Some(bytes) = tool.result_bytes {
+            header.push_str(&format!(" · {}", Self::format_size(bytes)));
+        }
         
         all_lines.push(Line::from(Span::styled(header, Style::default().fg(status_color).add_modifier(Modifier::BOLD))));
         all_lines.push(Line::from("─".repeat(available_width.min(60))));
@@ -139,39 +146,66 @@ impl ToolsComponent {
         // Add tool parameters if available and relevant
         if let Some(ref args) = tool.args {
             Self::render_tool_parameters(all_lines, &tool.tool, args);
+        } else if tool.status == ToolStatus::Running {
+            all_lines.push(Line::from(Span::styled("Parameters pending…", Style::default().fg(Color::DarkGray))));
+            all_lines.push(Line::from(""));
         }
 
         // Add tool content
-        let content = Self::format_tool_content(tool);
+        let content = Self::format_tool_content(tool, preview_bytes);
 
         // Properly wrap content lines
         for line in content.lines() {
             Self::add_wrapped_line(all_lines, line, available_width, should_wrap);
         }
+
+        // Show what a patch changed; diff lines keep their columns, so they aren't wrapped
+        if tool.tool == grok_core::ToolName::FsApplyPatch {
+            if let Some(diff) = tool.result.as_ref().and_then(|r| r.get("diff")).and_then(|d| d.as_str()) {
+                all_lines.push(Line::from(""));
+                all_lines.extend(crate::diff::render_diff(diff));
+            }
+        }
     }
 
     fn format_tool_header(tool_name: &str, summary: &str, status_icon: &str) -> String {
-        if summary.starts_with(&format!("{} file:", tool_name.replace("Fs", "").to_lowercase())) {
-            // For file operations like "Reading file: path", extract just the filename
-            let filename = summary.split(": ").nth(1).unwrap_or(summary);
+        let summary = summary.trim();
+        fn non_empty(value: &str) -> Option<&str> {
+            Some(value.trim()).filter(|v| !v.is_empty())
+        }
+        let after_prefix = |prefix: &str| summary.strip_prefix(prefix).and_then(non_empty);
+
+        if summary.is_empty() {
+            format!("{} {}", status_icon, tool_name)
+        } else if let Some(query) = after_prefix("Searching for:") {
+            format!("{} {} \"{}\"", status_icon, tool_name, query)
+        } else if let Some(command) = after_prefix("Executing:") {
+            format!("{} {} {}", status_icon, tool_name, command)
+        } else if let Some(filename) = summary.split_once("file: ").and_then(|(_, path)| non_empty(path)) {
+            // For file operations like "Reading file: path", show just the filename
             let basename = std::path::Path::new(filename).file_name()
                 .and_then(|f| f.to_str())
                 .unwrap_or(filename);
             format!("{} {} {}", status_icon, tool_name, basename)
-        } else if summary.starts_with("Searching for:") {
-            // For search operations
-            let query = summary.split(": ").nth(1).unwrap_or(summary);
-            format!("{} {} \"{}\"", status_icon, tool_name, query)
-        } else if summary.starts_with("Executing:") {
-            // For shell commands
-            let command = summary.split(": ").nth(1).unwrap_or(summary);
-            format!("{} {} {}", status_icon, tool_name, command)
         } else {
             // Fallback to original summary
             format!("{} {}", status_icon, summary)
         }
     }
 
+    /// Human-readable size of a tool result, e.g. "312 B" or "4.2 KB"
+    fn format_size(bytes: u64) -> String {
+        const KB: f64 = 1024.0;
+        let value = bytes as f64;
+        if value < KB {
+            format!("{} B", bytes)
+        } else if value < KB * KB {
+            format!("{:.1} KB", value / KB)
+        } else {
+            format!("{:.1} MB", value / (KB * KB))
+        }
+    }
+
     fn render_tool_parameters(all_lines: &mut Vec<Line>, tool: &grok_core::ToolName, args: &serde_json::Value) {
         match tool {
             grok_core::ToolName::FsSearch => {
@@ -253,6 +287,8 @@ impl ToolsComponent {
                             grok_core::tools::SimpleEditOp::InsertAfter { .. } => "insert_after",
                             grok_core::tools::SimpleEditOp::DeleteFile { .. } => "delete_file",
                             grok_core::tools::SimpleEditOp::RenameFile { .. } => "rename_file",
+                            grok_core::tools::SimpleEditOp::CreateEmpty { .. } => "create_empty",
+                            grok_core::tools::SimpleEditOp::CreateDir { .. } => "create_dir",
                         }).collect();
                         all_lines.push(Line::from(format!("  Op types: {}", op_types.join(", "))));
                     }
@@ -328,6 +364,16 @@ impl ToolsComponent {
                     all_lines.push(Line::from(""));
                 }
             }
+            grok_core::ToolName::FsTree => {
+                if let Ok(tree_args) = serde_json::from_value::<grok_core::tools::FsTreeArgs>(args.clone()) {
+                    all_lines.push(Line::from(Span::styled("Parameters:", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))));
+                    all_lines.push(Line::from(format!("  Path: {}", tree_args.path.as_deref().unwrap_or("."))));
+                    if let Some(max_depth) = tree_args.max_depth {
+                        all_lines.push(Line::from(format!("  Max depth: {}", max_depth)));
+                    }
+                    all_lines.push(Line::from(""));
+                }
+            }
             grok_core::ToolName::CodeSymbols => {
                 if let Ok(symbols_args) = serde_json::from_value::<grok_core::tools::CodeSymbolsArgs>(args.clone()) {
                     all_lines.push(Line::from(Span::styled("Parameters:", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))));
@@ -341,6 +387,33 @@ impl ToolsComponent {
                     all_lines.push(Line::from(""));
                 }
             }
+            grok_core::ToolName::CodeTest => {
+                if let Ok(test_args) = serde_json::from_value::<grok_core::tools::CodeTestArgs>(args.clone()) {
+                    all_lines.push(Line::from(Span::styled("Parameters:", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))));
+                    all_lines.push(Line::from(format!("  Project: {}", test_args.path.as_deref().unwrap_or("."))));
+                    if let Some(ref filter) = test_args.filter {
+                        all_lines.push(Line::from(format!("  Filter: {}", filter)));
+                    }
+                    all_lines.push(Line::from(""));
+                }
+            }
+            grok_core::ToolName::CodeBuild => {
+                if let Ok(build_args) = serde_json::from_value::<grok_core::tools::CodeBuildArgs>(args.clone()) {
+                    all_lines.push(Line::from(Span::styled("Parameters:", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))));
+                    all_lines.push(Line::from(format!("  Project: {}", build_args.path.as_deref().unwrap_or("."))));
+                    all_lines.push(Line::from(""));
+                }
+            }
+            grok_core::ToolName::HttpFetch => {
+                if let Ok(fetch_args) = serde_json::from_value::<grok_core::tools::HttpFetchArgs>(args.clone()) {
+                    all_lines.push(Line::from(Span::styled("Parameters:", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))));
+                    all_lines.push(Line::from(format!("  URL: {}", fetch_args.url)));
+                    if let Some(max_bytes) = fetch_args.max_bytes {
+                        all_lines.push(Line::from(format!("  Max bytes: {}", max_bytes)));
+                    }
+                    all_lines.push(Line::from(""));
+                }
+            }
             grok_core::ToolName::LargeContextFetch => {
                 if let Ok(context_args) = serde_json::from_value::<grok_core::tools::LargeContextFetchArgs>(args.clone()) {
                     all_lines.push(Line::from(Span::styled("Parameters:", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))));
@@ -349,6 +422,9 @@ impl ToolsComponent {
                     if let Some(max_files) = context_args.max_files {
                         all_lines.push(Line::from(format!("  Max files: {}", max_files)));
                     }
+                    if let Some(max_return_files) = context_args.max_return_files {
+                        all_lines.push(Line::from(format!("  Max returned: {}", max_return_files)));
+                    }
                     if let Some(ref extensions) = context_args.include_extensions {
                         all_lines.push(Line::from(format!("  Extensions: {}", extensions.join(", "))));
                     }
@@ -358,7 +434,7 @@ impl ToolsComponent {
         }
     }
 
-    fn format_tool_content(tool: &grok_core::ToolMessageInfo) -> String {
+    fn format_tool_content(tool: &grok_core::ToolMessageInfo, preview_bytes: usize) -> String {
         match tool.status {
             ToolStatus::Running => {
                 let mut content = String::new();
@@ -392,7 +468,7 @@ impl ToolsComponent {
                 // For both completed and failed tools, show structured result if available
                 if let Some(ref result) = tool.result {
                     if !content.is_empty() { content.push_str("\n\n"); }
-                    content.push_str(&Self::format_tool_result(&tool.tool, result));
+                    content.push_str(&Self::format_tool_result(&tool.tool, result, preview_bytes));
                 }
                 
                 // Add stdout if it exists and is meaningful (for completed tools or if no result)
@@ -417,18 +493,34 @@ impl ToolsComponent {
         }
     }
 
-    fn format_tool_result(tool: &grok_core::ToolName, result: &serde_json::Value) -> String {
+    /// A tool's result as text, cut to `preview_bytes` (0 for no limit)
+    fn format_tool_result(tool: &grok_core::ToolName, result: &serde_json::Value, preview_bytes: usize) -> String {
+        Self::cap_preview(Self::format_result_body(tool, result), preview_bytes)
+    }
+
+    /// Cut `text` to at most `max_bytes` on a char boundary, noting how much is shown
+    fn cap_preview(text: String, max_bytes: usize) -> String {
+        if max_bytes == 0 || text.len() <= max_bytes {
+            return text;
+        }
+        // Back off to a char boundary so multi-byte text isn't split
+        let cut = floor_char_boundary(&text, max_bytes);
+        format!("{}\n\n[Showing first {} of {} bytes]", &text[..cut], cut, text.len())
+    }
+
+    fn format_result_body(tool: &grok_core::ToolName, result: &serde_json::Value) -> String {
         match tool {
             grok_core::ToolName::FsRead => {
                 if let Ok(fs_result) = serde_json::from_value::<serde_json::Value>(result.clone()) {
                     if let Some(contents) = fs_result.get("contents").and_then(|c| c.as_str()) {
-                        // For file reads, show the actual file contents directly but limit display length
-                        const MAX_DISPLAY_LENGTH: usize = 5000; // Limit to 5000 characters for UI display
-                        let mut display_contents = if contents.len() > MAX_DISPLAY_LENGTH {
-                            format!("{}\n\n[Content truncated for display - showing first {} of {} characters]", &contents[..MAX_DISPLAY_LENGTH], MAX_DISPLAY_LENGTH, contents.len())
-                        } else {
-                            contents.to_string()
-                        };
+                        // For file reads, show the actual file contents directly
+                        let mut display_contents = contents.to_string();
+
+                        if fs_result.get("is_empty").and_then(|e| e.as_bool()).unwrap_or(false) {
+                            display_contents.push_str("[Empty file]");
+                        } else if fs_result.get("whitespace_only").and_then(|w| w.as_bool()).unwrap_or(false) {
+                            display_contents.push_str("\n[File contains only whitespace]");
+                        }
 
                         if let Some(truncated) = fs_result.get("truncated").and_then(|t| t.as_bool()) {
                             if truncated {
@@ -506,11 +598,70 @@ impl ToolsComponent {
                         ));
                     }
             
-                    return out;
+                    out
+                } else {
+                    serde_json::to_string_pretty(result).unwrap_or_else(|_| "Invalid JSON".to_string())
+                }
+            }
+            grok_core::ToolName::FsTree => {
+                if let Some(tree) = result.get("tree").and_then(|t| t.as_str()) {
+                    let mut out = tree.to_string();
+                    if result.get("truncated").and_then(|t| t.as_bool()).unwrap_or(false) {
+                        out.push_str("\n\n[Tree truncated at node limit]");
+                    }
+                    out
                 } else {
                     serde_json::to_string_pretty(result).unwrap_or_else(|_| "Invalid JSON".to_string())
                 }
             }
+            grok_core::ToolName::FsWrite => {
+                match serde_json::from_value::<grok_core::tools::FsWriteResult>(result.clone()) {
+                    Ok(write) => {
+                        let mut out = format!("Wrote {} bytes", write.bytes_written);
+                        if let Some(stats) = write.overwrite {
+                            out.push_str(&format!("\n⚠ {}", stats.describe()));
+                        }
+                        out
+                    }
+                    Err(_) => serde_json::to_string_pretty(result).unwrap_or_else(|_| "Invalid JSON".to_string()),
+                }
+            }
+            grok_core::ToolName::FsApplyPatch => {
+                // The diff is rendered separately with colors, so only the summary goes here
+                match serde_json::from_value::<grok_core::tools::FsApplyPatchResult>(result.clone()) {
+                    Ok(patch) => {
+                        let mut out = patch.summary;
+                        for hunk in patch.rejected_hunks.unwrap_or_default() {
+                            out.push_str(&format!("\nRejected: {}", hunk));
+                        }
+                        out
+                    }
+                    Err(_) => serde_json::to_string_pretty(result).unwrap_or_else(|_| "Invalid JSON".to_string()),
+                }
+            }
+            grok_core::ToolName::CodeSymbols => {
+                match serde_json::from_value::<grok_core::tools::CodeSymbolsResult>(result.clone()) {
+                    Ok(symbols) => Self::format_symbols(&symbols),
+                    Err(_) => serde_json::to_string_pretty(result).unwrap_or_else(|_| "Invalid JSON".to_string()),
+                }
+            }
+            grok_core::ToolName::HttpFetch => {
+                match serde_json::from_value::<grok_core::tools::HttpFetchResult>(result.clone()) {
+                    Ok(fetch) => {
+                        let mut out = format!("🌐 {} · HTTP {}", fetch.url, fetch.status);
+                        if let Some(content_type) = &fetch.content_type {
+                            out.push_str(&format!(" · {}", content_type));
+                        }
+                        out.push_str("\n\n");
+                        out.push_str(&fetch.body);
+                        if fetch.truncated {
+                            out.push_str(&format!("\n\n[Body truncated after {} bytes]", fetch.bytes_read));
+                        }
+                        out
+                    }
+                    Err(_) => serde_json::to_string_pretty(result).unwrap_or_else(|_| "Invalid JSON".to_string()),
+                }
+            }
             _ => {
                 // Handle other tool types with their specific result formatting
                 // This is a simplified version - you'd want to implement specific formatting for each tool
@@ -519,6 +670,53 @@ impl ToolsComponent {
         }
     }
 
+    /// Symbols grouped by kind, in the order kinds first appear in the file
+    fn format_symbols(result: &grok_core::tools::CodeSymbolsResult) -> String {
+        let mut out = format!("🧩 {} · {} symbol(s)", result.language, result.symbols.len());
+        if result.symbols.is_empty() {
+            out.push_str("\n\nNo symbols found");
+            return out;
+        }
+
+        let mut groups: Vec<(&str, Vec<&grok_core::tools::CodeSymbol>)> = Vec::new();
+        for symbol in &result.symbols {
+            match groups.iter_mut().find(|(kind, _)| *kind == symbol.symbol_type) {
+                Some((_, members)) => members.push(symbol),
+                None => groups.push((&symbol.symbol_type, vec![symbol])),
+            }
+        }
+
+        for (kind, members) in groups {
+            out.push_str(&format!("\n\n{} ({})", Self::symbol_group_title(kind), members.len()));
+            for symbol in members {
+                let lines = if symbol.line_start == symbol.line_end {
+                    format!("L{}", symbol.line_start)
+                } else {
+                    format!("L{}-{}", symbol.line_start, symbol.line_end)
+                };
+                out.push_str(&format!("\n  {}  {}", symbol.name, lines));
+                if let Some(scope) = &symbol.scope {
+                    out.push_str(&format!(" (in {})", scope));
+                }
+            }
+        }
+        out
+    }
+
+    /// "function" -> "Functions", "class" -> "Classes"
+    fn symbol_group_title(kind: &str) -> String {
+        let mut chars = kind.chars();
+        let title: String = match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => return "Other".to_string(),
+        };
+        if title.ends_with('s') {
+            format!("{}es", title)
+        } else {
+            format!("{}s", title)
+        }
+    }
+
     fn add_wrapped_line(all_lines: &mut Vec<Line>, line: &str, available_width: usize, should_wrap: bool) {
         if line.len() <= available_width && should_wrap {
             all_lines.push(Line::from(line.to_string()));
@@ -530,7 +728,7 @@ impl ToolsComponent {
             for word in words {
                 if current_line.is_empty() {
                     current_line = word.to_string();
-                } else if current_line.len() + word.len() + 1 <= available_width {
+                } else if current_line.len() + word.len() < available_width {
                     current_line.push(' ');
                     current_line.push_str(word);
                 } else {
@@ -574,3 +772,125 @@ impl ToolsComponent {
         result_lines.join("\n")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use grok_core::tools::{CodeSymbol, CodeSymbolsResult};
+    use crate::state::DEFAULT_TOOL_PREVIEW_BYTES;
+
+    fn symbol(name: &str, symbol_type: &str, line_start: u32, line_end: u32, scope: Option<&str>) -> CodeSymbol {
+        CodeSymbol {
+            name: name.to_string(),
+            symbol_type: symbol_type.to_string(),
+            line_start,
+            line_end,
+            scope: scope.map(String::from),
+            visibility: None,
+        }
+    }
+
+    #[test]
+    fn test_code_symbols_result_is_grouped() {
+        let result = CodeSymbolsResult {
+            language: "python".to_string(),
+            symbols: vec![
+                symbol("Parser", "class", 1, 20, None),
+                symbol("parse", "function", 3, 10, Some("Parser")),
+                symbol("main", "function", 22, 22, None),
+            ],
+        };
+        let value = serde_json::to_value(&result).unwrap();
+        let out = ToolsComponent::format_tool_result(&grok_core::ToolName::CodeSymbols, &value, DEFAULT_TOOL_PREVIEW_BYTES);
+
+        assert!(out.starts_with("🧩 python · 3 symbol(s)"));
+        assert!(out.contains("Classes (1)\n  Parser  L1-20"));
+        assert!(out.contains("Functions (2)\n  parse  L3-10 (in Parser)\n  main  L22"));
+        assert!(!out.contains('{'));
+    }
+
+    #[test]
+    fn test_fs_read_display_cut_respects_char_boundaries() {
+        // 4999 ASCII bytes then a 4-byte emoji that straddles the 5000-byte limit
+        let contents = format!("{}🦀🦀", "a".repeat(4999));
+        let value = serde_json::json!({ "contents": contents, "encoding": "utf-8", "truncated": false });
+
+        let out = ToolsComponent::format_tool_result(&grok_core::ToolName::FsRead, &value, DEFAULT_TOOL_PREVIEW_BYTES);
+
+        let (shown, note) = out.split_once("\n\n[Showing first").unwrap();
+        assert_eq!(shown, "a".repeat(4999));
+        assert_eq!(note, " 4999 of 5007 bytes]");
+    }
+
+    #[test]
+    fn test_configured_preview_limit_caps_read_and_search() {
+        let read = serde_json::json!({ "contents": "x".repeat(500), "encoding": "utf-8", "truncated": false });
+        let out = ToolsComponent::format_tool_result(&grok_core::ToolName::FsRead, &read, 100);
+        assert!(out.starts_with(&"x".repeat(100)));
+        assert!(out.ends_with("[Showing first 100 of 500 bytes]"));
+
+        let lines: Vec<_> = (1..=50).map(|ln| serde_json::json!({ "ln": ln, "text": "needle here" })).collect();
+        let search = serde_json::json!({ "matches": [{ "path": "src/lib.rs", "lines": lines }] });
+        let full = ToolsComponent::format_tool_result(&grok_core::ToolName::FsSearch, &search, 0);
+        let out = ToolsComponent::format_tool_result(&grok_core::ToolName::FsSearch, &search, 200);
+        assert_eq!(out, format!("{}\n\n[Showing first 200 of {} bytes]", &full[..200], full.len()));
+
+        // Short results are left alone
+        let out = ToolsComponent::format_tool_result(&grok_core::ToolName::FsRead, &read, 1000);
+        assert_eq!(out, "x".repeat(500));
+    }
+
+    fn running_tool(args: Option<serde_json::Value>) -> grok_core::ToolMessageInfo {
+        grok_core::ToolMessageInfo {
+            id: "t1".to_string(),
+            tool: grok_core::ToolName::FsRead,
+            summary: "Reading file: src/main.rs".to_string(),
+            args,
+            start_time: std::time::SystemTime::now(),
+            status: ToolStatus::Running,
+            stdout: String::new(),
+            stderr: String::new(),
+            result: None,
+            result_bytes: None,
+        }
+    }
+
+    fn rendered(tool: &grok_core::ToolMessageInfo) -> Vec<String> {
+        let mut lines = Vec::new();
+        ToolsComponent::render_tool(&mut lines, tool, 80, true, DEFAULT_TOOL_PREVIEW_BYTES);
+        lines.iter().map(|l| l.spans.iter().map(|s| s.content.as_ref()).collect()).collect()
+    }
+
+    #[test]
+    fn test_tool_without_args_shows_pending_parameters() {
+        let lines = rendered(&running_tool(None));
+        assert_eq!(lines[0], "🔄 FsRead main.rs");
+        assert!(lines.iter().any(|l| l == "Parameters pending…"));
+
+        let lines = rendered(&running_tool(Some(serde_json::json!({ "path": "src/main.rs" }))));
+        assert!(!lines.iter().any(|l| l.contains("pending")));
+    }
+
+    #[test]
+    fn test_tool_header_with_unexpected_summaries() {
+        let header = |summary: &str| ToolsComponent::format_tool_header("ShellExec", summary, "✅");
+        assert_eq!(header(""), "✅ ShellExec");
+        assert_eq!(header("Executing:"), "✅ Executing:");
+        assert_eq!(header("Searching for: file: x"), "✅ ShellExec \"file: x\"");
+        assert_eq!(header("Executing: echo a: b"), "✅ ShellExec echo a: b");
+        assert_eq!(header("Searching for: key: value"), "✅ ShellExec \"key: value\"");
+        assert_eq!(header("Writing to file: /tmp/out/report.md"), "✅ ShellExec report.md");
+        assert_eq!(header("Something else entirely"), "✅ Something else entirely");
+    }
+
+    #[test]
+    fn test_tool_header_shows_result_size() {
+        let mut tool = running_tool(Some(serde_json::json!({ "path": "src/main.rs" })));
+        tool.status = ToolStatus::Completed;
+        tool.result_bytes = Some(4300);
+        assert_eq!(rendered(&tool)[0], "✅ FsRead main.rs · 4.2 KB");
+
+        assert_eq!(ToolsComponent::format_size(312), "312 B");
+        assert_eq!(ToolsComponent::format_size(3 * 1024 * 1024), "3.0 MB");
+    }
+}
@@ -0,0 +1,239 @@
+//! Styled rendering of unified diffs, such as the one `fs.apply_patch` returns or the
+//! file comparison built by `/diff <pathA> <pathB>`
+//!
+//! Additions are green and deletions red. When a run of removed lines is replaced by the
+//! same number of added lines, each pair is compared word by word and only the words that
+//! changed are highlighted.
+
+use grok_core::tools::executors::{diff_files, FsExecutor};
+use grok_core::tools::FsReadResult;
+use grok_core::EventBus;
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+use serde_json::json;
+use std::path::Path;
+
+const ADDED: Style = Style::new().fg(Color::Green);
+const REMOVED: Style = Style::new().fg(Color::Red);
+const CONTEXT: Style = Style::new().fg(Color::DarkGray);
+const HUNK: Style = Style::new().fg(Color::Cyan);
+const FILE_HEADER: Style = Style::new().add_modifier(Modifier::BOLD);
+
+/// Header prefix that marks a system message as a `/diff` comparison
+const COMPARISON_HEADER: &str = "Diff: ";
+
+/// Read both files (relative to `root`) in full through the fs.read executor and diff them
+pub async fn compare_files(old_path: &str, new_path: &str, root: &Path) -> Result<String, String> {
+    // The comparison isn't a tool call, so its progress events go nowhere
+    let bus = EventBus::new();
+    let executor = FsExecutor::new(bus.sender(), usize::MAX).with_root(Some(root.to_path_buf()));
+
+    let mut contents = Vec::with_capacity(2);
+    for path in [old_path, new_path] {
+        let value = executor
+            .execute_read_with_result("diff-files".to_string(), json!({ "path": path, "max_bytes": 0 }))
+            .await
+            .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+        let result: FsReadResult = serde_json::from_value(value)
+            .map_err(|e| format!("Unexpected fs.read result: {}", e))?;
+        // A partial read would show the missing tail as deleted
+        if result.truncated {
+            return Err(format!("Failed to read {}: file was only partly read", path));
+        }
+        contents.push(result.contents);
+    }
+
+    Ok(build_comparison(old_path, new_path, &contents[0], &contents[1]))
+}
+
+/// A header naming both files followed by their unified diff
+pub fn build_comparison(old_path: &str, new_path: &str, old: &str, new: &str) -> String {
+    let diff = diff_files(old_path, new_path, old, new);
+    if diff.is_empty() {
+        format!("{}{} → {}: files are identical", COMPARISON_HEADER, old_path, new_path)
+    } else {
+        format!("{}{} → {}\n{}", COMPARISON_HEADER, old_path, new_path, diff.trim_end())
+    }
+}
+
+/// Whether a system message holds a comparison built by [`build_comparison`]
+pub fn is_comparison(content: &str) -> bool {
+    content.starts_with(COMPARISON_HEADER)
+}
+
+/// Style a comparison for the chat panel: a highlighted header over the colored diff
+pub fn comparison_lines(content: &str) -> Vec<Line<'static>> {
+    let (header, diff) = content.split_once('\n').unwrap_or((content, ""));
+    let mut lines = vec![Line::from(Span::styled(
+        header.to_string(),
+        Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+    ))];
+    lines.extend(render_diff(diff));
+    lines
+}
+
+/// Lines of a unified diff, colored by kind
+pub fn render_diff(diff: &str) -> Vec<Line<'static>> {
+    let lines: Vec<&str> = diff.lines().collect();
+    let mut out = Vec::with_capacity(lines.len());
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        if line.starts_with("---") || line.starts_with("+++") {
+            out.push(Line::from(Span::styled(line.to_string(), FILE_HEADER)));
+            i += 1;
+        } else if line.starts_with("@@") {
+            out.push(Line::from(Span::styled(line.to_string(), HUNK)));
+            i += 1;
+        } else if line.starts_with('-') || line.starts_with('+') {
+            let removed = run_length(&lines[i..], '-');
+            let added = run_length(&lines[i + removed..], '+');
+            let (old, new) = (&lines[i..i + removed], &lines[i + removed..i + removed + added]);
+            if removed == added {
+                let (old_lines, new_lines): (Vec<_>, Vec<_>) = old
+                    .iter()
+                    .zip(new)
+                    .map(|(o, n)| word_diff(&o[1..], &n[1..]))
+                    .unzip();
+                out.extend(old_lines);
+                out.extend(new_lines);
+            } else {
+                out.extend(old.iter().map(|l| Line::from(Span::styled(l.to_string(), REMOVED))));
+                out.extend(new.iter().map(|l| Line::from(Span::styled(l.to_string(), ADDED))));
+            }
+            // A lone `+` run (no deletions) still advances
+            i += (removed + added).max(1);
+        } else {
+            out.push(Line::from(Span::styled(line.to_string(), CONTEXT)));
+            i += 1;
+        }
+    }
+    out
+}
+
+/// Number of leading lines starting with `marker` (file headers excluded)
+fn run_length(lines: &[&str], marker: char) -> usize {
+    let header = if marker == '-' { "---" } else { "+++" };
+    lines
+        .iter()
+        .take_while(|l| l.starts_with(marker) && !l.starts_with(header))
+        .count()
+}
+
+/// A removed and an added line with the words between their shared prefix and
+/// suffix highlighted
+fn word_diff(old: &str, new: &str) -> (Line<'static>, Line<'static>) {
+    let old_words = split_words(old);
+    let new_words = split_words(new);
+    let prefix = old_words.iter().zip(&new_words).take_while(|(a, b)| a == b).count();
+    let suffix = old_words[prefix..]
+        .iter()
+        .rev()
+        .zip(new_words[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let styled = |marker: &str, words: &[&str], base: Style| {
+        let changed = &words[prefix..words.len() - suffix];
+        let mut spans = vec![Span::styled(format!("{}{}", marker, words[..prefix].concat()), base)];
+        if !changed.is_empty() {
+            spans.push(Span::styled(changed.concat(), base.add_modifier(Modifier::REVERSED)));
+        }
+        spans.push(Span::styled(words[words.len() - suffix..].concat(), base));
+        Line::from(spans)
+    };
+    (styled("-", &old_words, REMOVED), styled("+", &new_words, ADDED))
+}
+
+/// Words and the whitespace between them, as separate pieces
+fn split_words(text: &str) -> Vec<&str> {
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    let mut in_space = None;
+    for (idx, c) in text.char_indices() {
+        let space = c.is_whitespace();
+        if in_space.is_some_and(|s| s != space) {
+            pieces.push(&text[start..idx]);
+            start = idx;
+        }
+        in_space = Some(space);
+    }
+    if start < text.len() {
+        pieces.push(&text[start..]);
+    }
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "--- a/main.rs\n+++ b/main.rs\n@@ -1,3 +1,3 @@\n fn main() {\n-    let x = 1;\n+    let x = 2;\n }\n@@ -10,1 +10,2 @@\n+// added\n ok\n";
+
+    fn text(line: &Line) -> String {
+        line.spans.iter().map(|s| s.content.as_ref()).collect()
+    }
+
+    #[test]
+    fn test_render_diff_colors_additions_and_deletions() {
+        let lines = render_diff(DIFF);
+        let texts: Vec<String> = lines.iter().map(text).collect();
+        assert_eq!(texts[3], " fn main() {");
+        assert_eq!(texts[4], "-    let x = 1;");
+        assert_eq!(texts[5], "+    let x = 2;");
+        assert_eq!(texts[8], "+// added");
+
+        assert_eq!(lines[2].spans[0].style.fg, Some(Color::Cyan));
+        assert_eq!(lines[3].spans[0].style.fg, Some(Color::DarkGray));
+        assert!(lines[4].spans.iter().all(|s| s.style.fg == Some(Color::Red)));
+        assert!(lines[5].spans.iter().all(|s| s.style.fg == Some(Color::Green)));
+        assert_eq!(lines[8].spans[0].style.fg, Some(Color::Green));
+
+        // Only the changed word is highlighted within the paired lines
+        let highlighted: Vec<&str> = lines[5]
+            .spans
+            .iter()
+            .filter(|s| s.style.add_modifier.contains(Modifier::REVERSED))
+            .map(|s| s.content.as_ref())
+            .collect();
+        assert_eq!(highlighted, vec!["2;"]);
+    }
+
+    #[tokio::test]
+    async fn test_compare_files_shows_changed_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("old.txt"), "alpha\nbeta\ngamma\n").unwrap();
+        std::fs::write(dir.path().join("new.txt"), "alpha\nBETA\ngamma\ndelta\n").unwrap();
+
+        let comparison = compare_files("old.txt", "new.txt", dir.path()).await.unwrap();
+        assert!(is_comparison(&comparison));
+        let texts: Vec<String> = comparison_lines(&comparison).iter().map(text).collect();
+        assert_eq!(texts[0], "Diff: old.txt → new.txt");
+        assert_eq!(&texts[1..3], ["--- old.txt", "+++ new.txt"]);
+        assert!(texts.contains(&"-beta".to_string()));
+        assert!(texts.contains(&"+BETA".to_string()));
+        assert!(texts.contains(&"+delta".to_string()));
+        assert!(texts.contains(&" alpha".to_string()));
+
+        std::fs::write(dir.path().join("copy.txt"), "alpha\nbeta\ngamma\n").unwrap();
+        let identical = compare_files("old.txt", "copy.txt", dir.path()).await.unwrap();
+        assert_eq!(identical, "Diff: old.txt → copy.txt: files are identical");
+
+        let missing = compare_files("old.txt", "nope.txt", dir.path()).await.unwrap_err();
+        assert!(missing.starts_with("Failed to read nope.txt"));
+    }
+
+    #[tokio::test]
+    async fn test_compare_files_reads_past_the_default_cut() {
+        let dir = tempfile::tempdir().unwrap();
+        let body = "line\n".repeat(300_000);
+        std::fs::write(dir.path().join("old.txt"), format!("{}old tail\n", body)).unwrap();
+        std::fs::write(dir.path().join("new.txt"), format!("{}new tail\n", body)).unwrap();
+
+        let comparison = compare_files("old.txt", "new.txt", dir.path()).await.unwrap();
+        assert!(comparison.contains("-old tail"));
+        assert!(comparison.contains("+new tail"));
+    }
+}
@@ -0,0 +1,54 @@
+//! Handing a file to the user's `$EDITOR` for `/edit`
+
+use std::path::Path;
+use std::process::Command;
+
+/// Message shown when `/edit` is used without an editor configured
+pub const NO_EDITOR_MESSAGE: &str = "No editor configured: set $EDITOR (for example `export EDITOR=vim`) and restart.";
+
+/// The editor command from `$EDITOR` (falling back to `$VISUAL`), split into program
+/// and arguments so values like `code --wait` work
+pub fn editor_from_env() -> Option<Vec<String>> {
+    editor_from(|name| std::env::var(name).ok())
+}
+
+/// [`editor_from_env`] with the environment lookup supplied by the caller
+pub fn editor_from(lookup: impl Fn(&str) -> Option<String>) -> Option<Vec<String>> {
+    ["EDITOR", "VISUAL"].iter().find_map(|name| {
+        let words: Vec<String> = lookup(name)?.split_whitespace().map(str::to_string).collect();
+        (!words.is_empty()).then_some(words)
+    })
+}
+
+/// Command that opens `path` in `editor`, ready to spawn
+pub fn editor_command(editor: &[String], path: &Path) -> Command {
+    let mut command = Command::new(&editor[0]);
+    command.args(&editor[1..]).arg(path);
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsStr;
+
+    #[test]
+    fn test_editor_resolved_from_env_and_command_built() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| vars.iter().find(|(k, _)| *k == name).map(|(_, v)| v.to_string())
+        };
+
+        assert_eq!(editor_from(env(&[])), None);
+        assert_eq!(editor_from(env(&[("EDITOR", "  ")])), None);
+        assert_eq!(editor_from(env(&[("VISUAL", "nano")])), Some(vec!["nano".to_string()]));
+
+        let editor = editor_from(env(&[("EDITOR", "code --wait"), ("VISUAL", "nano")])).unwrap();
+        assert_eq!(editor, vec!["code".to_string(), "--wait".to_string()]);
+
+        // Building the command doesn't spawn anything
+        let command = editor_command(&editor, Path::new("/work/src/main.rs"));
+        assert_eq!(command.get_program(), OsStr::new("code"));
+        let args: Vec<&OsStr> = command.get_args().collect();
+        assert_eq!(args, vec![OsStr::new("--wait"), OsStr::new("/work/src/main.rs")]);
+    }
+}
@@ -1,6 +1,6 @@
-use grok_core::AppEvent;
+use grok_core::{AgentError, AppEvent};
 use tracing::{debug, error};
-use crate::state::AppState;
+use crate::state::{AppState, PendingApproval};
 
 /// Handles application events from the session
 pub struct EventHandler;
@@ -9,20 +9,32 @@ impl EventHandler {
     /// Handle application events
     pub async fn handle_event(state: &mut AppState, event: AppEvent) {
         debug!("Handling app event: {:?}", event);
+        if let Some(log) = state.event_log.as_mut() {
+            log.record(&event);
+        }
+        state.live_output.observe(&event);
+        state.running_tools.observe(&event);
+        state.recent_files.observe(&event);
         match event {
             AppEvent::UserInput(_) => {
                 // User input is handled directly in submit_input
             }
             AppEvent::AgentResponse(response) => {
                 // Append agent response and mark as done
+                if response.metadata.model.is_some() {
+                    state.last_model = response.metadata.model.clone();
+                }
                 state.session.add_agent_message(response.content);
                 state.processing = false;
-                // Re-enable auto-scroll for new content
-                state.auto_scroll_chat = true;
                 debug!("Received agent response");
             }
             AppEvent::AgentError(error) => {
                 state.session.add_error_message(format!("{}", error));
+                if matches!(error, AgentError::ContextTooLarge(_)) {
+                    state.session.add_system_message(
+                        "The conversation is too long for the model. Use /clear to start a fresh context.".to_string(),
+                    );
+                }
                 state.processing = false;
                 error!("Agent error: {}", error);
             }
@@ -32,10 +44,7 @@ impl EventHandler {
             AppEvent::Clear => {
                 state.session.clear();
                 // Reset UI state to fresh start
-                state.chat_scroll = 0;
-                state.tools_scroll = 0;
-                state.auto_scroll_chat = true;
-                state.auto_scroll_tools = true;
+                state.reset_scroll();
                 state.current_token_usage = None;
             }
             AppEvent::ShowAgentInfo => {
@@ -67,9 +76,6 @@ impl EventHandler {
                 debug!("Tool {} started: {}", id, summary);
                 
                 state.session.handle_tool_begin(id, tool, summary, args);
-                // Re-enable auto-scroll for new tools and chat
-                state.auto_scroll_tools = true;
-                state.auto_scroll_chat = true;
             }
             AppEvent::ToolProgress { id, message } => {
                 debug!("Tool {} progress: {}", id, message);
@@ -87,9 +93,14 @@ impl EventHandler {
                 debug!("Tool {} result: {:?}", id, payload);
                 state.session.handle_tool_result(id, payload);
             }
-            AppEvent::ToolEnd { id, ok, duration_ms } => {
-                debug!("Tool {} ended: ok={}, duration={}ms", id, ok, duration_ms);
-                state.session.handle_tool_end(id, ok, duration_ms);
+            AppEvent::ToolEnd { id, ok, duration_ms, result_bytes } => {
+                debug!("Tool {} ended: ok={}, duration={}ms, result_bytes={:?}", id, ok, duration_ms, result_bytes);
+                state.session.handle_tool_end(id, ok, duration_ms, result_bytes);
+            }
+            AppEvent::ApprovalRequest(request) => {
+                debug!("Tool {} awaiting approval of {} op(s)", request.id, request.ops.len());
+                state.close_command_palette();
+                state.pending_approval = Some(PendingApproval::new(request));
             }
 
 
@@ -98,9 +109,14 @@ impl EventHandler {
                 error!("Error: {}", message);
                 state.session.add_error_message(format!("Error: {}", message));
             }
+            AppEvent::Notice(message) => {
+                debug!("Notice: {}", message);
+                state.toasts.push(message, std::time::Instant::now());
+            }
             AppEvent::TokenCount(usage) => {
                 debug!("Token usage: {}/{} tokens", usage.input_tokens, usage.output_tokens);
-                // Update current token usage for the /context command
+                // Update current token usage for the /context command and the /stats totals
+                state.session.record_token_usage(&usage);
                 state.current_token_usage = Some(usage);
             }
             AppEvent::Background(message) => {
@@ -109,3 +125,53 @@ impl EventHandler {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use grok_core::agent::agent_logic::MultiModelAgent;
+    use grok_core::agent::{AgentResponse, ResponseMetadata};
+    use grok_core::{EventBus, Session};
+    use std::time::{Duration, Instant};
+
+    fn test_state(chats_dir: &std::path::Path) -> AppState {
+        let bus = EventBus::new();
+        let agent = MultiModelAgent::new("test-key".to_string(), "test-model".to_string(), bus.sender())
+            .expect("agent should build");
+        let session = Session::new(std::sync::Arc::new(agent), bus.sender());
+        let (_sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+        AppState::new(session, receiver, chats_dir.to_path_buf())
+    }
+
+    #[tokio::test]
+    async fn test_notice_goes_to_toast_not_chat() {
+        let chats_dir = tempfile::tempdir().unwrap();
+        let mut state = test_state(chats_dir.path());
+
+        let notice = "Failed to connect to openrouter, trying next provider...".to_string();
+        EventHandler::handle_event(&mut state, AppEvent::Notice(notice.clone())).await;
+
+        assert!(state.session.messages().is_empty());
+        assert_eq!(state.toasts.messages().collect::<Vec<_>>(), vec![notice.as_str()]);
+
+        state.toasts.expire(Instant::now() + crate::state::TOAST_DURATION + Duration::from_millis(1));
+        assert!(state.toasts.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_agent_response_records_reported_model() {
+        let chats_dir = tempfile::tempdir().unwrap();
+        let mut state = test_state(chats_dir.path());
+        state.processing = true;
+
+        let response = AgentResponse {
+            content: "done".to_string(),
+            metadata: ResponseMetadata::new().with_model(Some("x-ai/grok-4".to_string())),
+        };
+        EventHandler::handle_event(&mut state, AppEvent::AgentResponse(response)).await;
+
+        assert_eq!(state.last_model.as_deref(), Some("x-ai/grok-4"));
+        assert!(!state.processing);
+        assert_eq!(state.session.messages()[0].content, "done");
+    }
+}
@@ -1,5 +1,5 @@
 use crossterm::event::{Event, KeyCode, KeyEventKind, MouseEvent, MouseEventKind};
-use crate::state::AppState;
+use crate::state::{AppState, SubmitMode};
 
 /// Handles input events for the application
 pub struct InputHandler;
@@ -18,6 +18,22 @@ impl InputHandler {
         }
     }
 
+    /// Keys for the `fs.apply_patch` approval modal
+    fn handle_approval_key(state: &mut AppState, key_code: KeyCode) {
+        let Some(pending) = state.pending_approval.as_mut() else {
+            return;
+        };
+        match key_code {
+            KeyCode::Up | KeyCode::Char('k') => pending.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => pending.select_next(),
+            KeyCode::Char(' ') => pending.toggle_selected(),
+            KeyCode::Char('a') => state.resolve_approval(Some(true)),
+            KeyCode::Char('r') | KeyCode::Esc => state.resolve_approval(Some(false)),
+            KeyCode::Enter => state.resolve_approval(None),
+            _ => {}
+        }
+    }
+
     async fn handle_key_event(
         state: &mut AppState,
         key_code: KeyCode,
@@ -25,13 +41,27 @@ impl InputHandler {
     ) {
         use crossterm::event::KeyModifiers;
 
+        // The approval modal takes every key until it is answered
+        if state.pending_approval.is_some() && !(key_code == KeyCode::Char('c') && modifiers.contains(KeyModifiers::CONTROL)) {
+            Self::handle_approval_key(state, key_code);
+            return;
+        }
+
+        // Keys that act outside the palette close it first, so focus and input stay in sync
+        if state.command_palette_open && Self::closes_palette(key_code) {
+            state.close_command_palette();
+        }
+
+        // Only an Enter directly after the newline-inserting one completes a double Enter
+        let enter_pending = std::mem::take(&mut state.enter_pending);
+
         match key_code {
             KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
                 state.should_quit = true;
             }
             KeyCode::Tab => {
                 // Switch between panels (chat input, chat history, tools)
-                state.focused_panel = (state.focused_panel + 1) % 3;
+                state.focus_next_panel();
             }
             KeyCode::Up => {
                 Self::handle_up_key(state);
@@ -49,9 +79,21 @@ impl InputHandler {
                 if state.command_palette_open {
                     Self::execute_selected_command(state).await;
                 } else {
-                    Self::submit_input(state).await;
+                    // Alt+Enter stands in for Ctrl+Enter where the terminal can't tell them apart
+                    let send_chord = modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+                    Self::handle_enter(state, send_chord, enter_pending).await;
                 }
             }
+            // Terminals without the kitty keyboard protocol send Ctrl+Enter as a bare
+            // line feed, which arrives as Ctrl+J
+            KeyCode::Char('j')
+                if modifiers.contains(KeyModifiers::CONTROL)
+                    && state.focused_panel == 0
+                    && state.submit_mode == SubmitMode::CtrlEnter
+                    && !state.command_palette_open =>
+            {
+                Self::submit_input(state).await;
+            }
             KeyCode::Char('/') if state.focused_panel == 0 && state.input.is_empty() && !state.command_palette_open => {
                 // Open command palette when typing '/' at the beginning of empty input
                 state.command_palette_open = true;
@@ -68,12 +110,15 @@ impl InputHandler {
             KeyCode::Backspace if state.focused_panel == 0 => {
                 if state.command_palette_open {
                     if !state.command_palette_filter.is_empty() {
+                        let name_filter = state.palette_query().0.to_string();
                         state.command_palette_filter.pop();
-                        // Reset selection when filter changes
-                        state.command_palette_selected = 0;
+                        // Editing the argument keeps the selected command
+                        if state.palette_query().0 != name_filter {
+                            state.command_palette_selected = 0;
+                        }
                     } else {
                         // Close command palette if filter is empty and backspace is pressed
-                        state.command_palette_open = false;
+                        state.close_command_palette();
                     }
                 } else {
                     Self::delete_char(state);
@@ -93,10 +138,7 @@ impl InputHandler {
             }
             KeyCode::Esc => {
                 if state.command_palette_open {
-                    // Close command palette
-                    state.command_palette_open = false;
-                    state.command_palette_filter.clear();
-                    state.command_palette_selected = 0;
+                    state.close_command_palette();
                 } else {
                     state.input.clear();
                     state.focused_panel = 0; // Return focus to chat
@@ -111,11 +153,11 @@ impl InputHandler {
                     }
                     1 => {
                         // Chat history
-                        state.auto_scroll_chat = true;
+                        state.scroll_chat_to_bottom();
                     }
                     2 => {
                         // Tools
-                        state.auto_scroll_tools = true;
+                        state.scroll_tools_to_bottom();
                     }
                     _ => {}
                 }
@@ -124,32 +166,38 @@ impl InputHandler {
         }
     }
 
+    /// Panel switching, paging and cursor movement have no meaning inside the palette;
+    /// Up/Down, Enter, Esc, Backspace and typing are handled by it
+    fn closes_palette(key_code: KeyCode) -> bool {
+        matches!(
+            key_code,
+            KeyCode::Tab
+                | KeyCode::BackTab
+                | KeyCode::PageUp
+                | KeyCode::PageDown
+                | KeyCode::Left
+                | KeyCode::Right
+                | KeyCode::Home
+                | KeyCode::End
+        )
+    }
+
     fn handle_mouse_event(state: &mut AppState, mouse_event: MouseEvent) {
         match mouse_event.kind {
             MouseEventKind::ScrollUp => {
                 // Scroll up in focused panel
                 match state.focused_panel {
-                    0 => {
-                        // Input area - scroll up
-                        if state.input_scroll > 0 {
-                            state.input_scroll = state.input_scroll.saturating_sub(1);
-                        }
+                    // Input area - scroll up
+                    0 if state.input_scroll > 0 => {
+                        state.input_scroll = state.input_scroll.saturating_sub(1);
                     }
-                    1 => {
-                        // Chat history
-                        if state.chat_scroll > 0 {
-                            state.chat_scroll = state.chat_scroll.saturating_sub(3); // Scroll 3 lines at a time
-                            // Disable auto-scroll when user manually scrolls
-                            state.auto_scroll_chat = false;
-                        }
+                    // Chat history
+                    1 if state.chat_scroll > 0 => {
+                        state.chat_scroll = state.chat_scroll.saturating_sub(3); // Scroll 3 lines at a time
                     }
-                    2 => {
-                        // Tools
-                        if state.tools_scroll > 0 {
-                            state.tools_scroll = state.tools_scroll.saturating_sub(3); // Scroll 3 lines at a time
-                            // Disable auto-scroll when user manually scrolls
-                            state.auto_scroll_tools = false;
-                        }
+                    // Tools
+                    2 if state.tools_scroll > 0 => {
+                        state.tools_scroll = state.tools_scroll.saturating_sub(3); // Scroll 3 lines at a time
                     }
                     _ => {}
                 }
@@ -164,14 +212,10 @@ impl InputHandler {
                     1 => {
                         // Chat history
                         state.chat_scroll = state.chat_scroll.saturating_add(3); // Scroll 3 lines at a time
-                        // Disable auto-scroll when user manually scrolls
-                        state.auto_scroll_chat = false;
                     }
                     2 => {
                         // Tools
                         state.tools_scroll = state.tools_scroll.saturating_add(3); // Scroll 3 lines at a time
-                        // Disable auto-scroll when user manually scrolls
-                        state.auto_scroll_tools = false;
                     }
                     _ => {}
                 }
@@ -193,21 +237,13 @@ impl InputHandler {
                     // Input area - move cursor up in multi-line input
                     Self::move_cursor_up(state);
                 }
-                1 => {
-                    // Chat history
-                    if state.chat_scroll > 0 {
-                        state.chat_scroll = state.chat_scroll.saturating_sub(1);
-                        // Disable auto-scroll when user manually scrolls
-                        state.auto_scroll_chat = false;
-                    }
+                // Chat history
+                1 if state.chat_scroll > 0 => {
+                    state.chat_scroll = state.chat_scroll.saturating_sub(1);
                 }
-                2 => {
-                    // Tools
-                    if state.tools_scroll > 0 {
-                        state.tools_scroll = state.tools_scroll.saturating_sub(1);
-                        // Disable auto-scroll when user manually scrolls
-                        state.auto_scroll_tools = false;
-                    }
+                // Tools
+                2 if state.tools_scroll > 0 => {
+                    state.tools_scroll = state.tools_scroll.saturating_sub(1);
                 }
                 _ => {}
             }
@@ -217,7 +253,7 @@ impl InputHandler {
     fn handle_down_key(state: &mut AppState) {
         if state.command_palette_open && state.focused_panel == 0 {
             // Navigate command palette
-            let filtered_commands = Self::get_filtered_commands(state);
+            let filtered_commands = state.filtered_commands();
             if state.command_palette_selected < filtered_commands.len().saturating_sub(1) {
                 state.command_palette_selected += 1;
             }
@@ -231,14 +267,10 @@ impl InputHandler {
                 1 => {
                     // Chat history
                     state.chat_scroll = state.chat_scroll.saturating_add(1);
-                    // Disable auto-scroll when user manually scrolls
-                    state.auto_scroll_chat = false;
                 }
                 2 => {
                     // Tools
                     state.tools_scroll = state.tools_scroll.saturating_add(1);
-                    // Disable auto-scroll when user manually scrolls
-                    state.auto_scroll_tools = false;
                 }
                 _ => {}
             }
@@ -248,11 +280,9 @@ impl InputHandler {
     fn handle_page_up(state: &mut AppState) {
         // Page up in focused panel
         match state.focused_panel {
-            0 => {
-                // Input area - scroll up
-                if state.input_scroll > 0 {
-                    state.input_scroll = state.input_scroll.saturating_sub(5);
-                }
+            // Input area - scroll up
+            0 if state.input_scroll > 0 => {
+                state.input_scroll = state.input_scroll.saturating_sub(5);
             }
             1 => {
                 // Chat history
@@ -261,8 +291,6 @@ impl InputHandler {
                 } else {
                     state.chat_scroll = 0;
                 }
-                // Disable auto-scroll when user manually scrolls
-                state.auto_scroll_chat = false;
             }
             2 => {
                 // Tools
@@ -271,8 +299,6 @@ impl InputHandler {
                 } else {
                     state.tools_scroll = 0;
                 }
-                // Disable auto-scroll when user manually scrolls
-                state.auto_scroll_tools = false;
             }
             _ => {}
         }
@@ -288,14 +314,10 @@ impl InputHandler {
             1 => {
                 // Chat history
                 state.chat_scroll = state.chat_scroll.saturating_add(10);
-                // Disable auto-scroll when user manually scrolls
-                state.auto_scroll_chat = false;
             }
             2 => {
                 // Tools
                 state.tools_scroll = state.tools_scroll.saturating_add(10);
-                // Disable auto-scroll when user manually scrolls
-                state.auto_scroll_tools = false;
             }
             _ => {}
         }
@@ -323,14 +345,20 @@ impl InputHandler {
             "/clear" => {
                 state.session.clear();
                 // Reset UI state to fresh start
-                state.chat_scroll = 0;
-                state.tools_scroll = 0;
-                state.auto_scroll_chat = true;
-                state.auto_scroll_tools = true;
+                state.reset_scroll();
                 state.current_token_usage = None;
                 state.processing = false;
                 return;
             }
+            "/reload" => {
+                match state.session.reload_prompt() {
+                    Ok(sources) => state.session.add_system_message(format!("Reloaded system prompt from {}.", sources)),
+                    Err(e) => state.session.add_error_message(e),
+                }
+                state.scroll_chat_to_bottom();
+                state.processing = false;
+                return;
+            }
             "/info" => {
                 let info = state.session.agent_info();
                 state.session.add_system_message(format!(
@@ -352,6 +380,29 @@ impl InputHandler {
                 state.processing = false;
                 return;
             }
+            "/stats" => {
+                let report = state.session.stats(std::time::SystemTime::now()).describe();
+                state.session.add_system_message(report);
+                state.scroll_chat_to_bottom();
+                state.processing = false;
+                return;
+            }
+            "/env" => {
+                let root = state.session.workspace_root();
+                let report = match grok_core::Config::load(&root) {
+                    Ok(config) => config.describe_env(&root),
+                    Err(e) => format!("{}\n(project config ignored: {})", grok_core::Config::default().describe_env(&root), e),
+                };
+                state.session.add_system_message(report);
+                state.processing = false;
+                return;
+            }
+            "/notools" => {
+                state.session.set_next_tool_choice(grok_core::ToolChoice::None);
+                state.session.add_system_message("Tools disabled for the next message.".to_string());
+                state.processing = false;
+                return;
+            }
             "/save" => {
                 match state.session.save() {
                     Ok(_) => state.session.add_system_message("Chat history saved to ~/.grok_code/chat_history.json.".to_string()),
@@ -365,7 +416,7 @@ impl InputHandler {
                     Ok(_) => {
                         state.session.add_system_message("Chat history loaded from default location.".to_string());
                         state.chat_scroll = 0;
-                        state.auto_scroll_chat = true;
+                        state.chat_max_scroll = 0;
                     }
                     Err(e) => state.session.add_error_message(format!("Failed to load history: {}", e)),
                 }
@@ -375,8 +426,249 @@ impl InputHandler {
             _ => {}
         }
 
-        // Re-enable auto-scroll for new conversation
-        state.auto_scroll_chat = true;
+        if let Some(arg) = input.strip_prefix("/open").filter(|rest| rest.is_empty() || rest.starts_with(' ')) {
+            match crate::preview::parse_location(arg) {
+                Some((path, line)) => match crate::preview::load_preview(&path, line, &state.session.workspace_root()).await {
+                    Ok(preview) => state.session.add_preview_message(preview),
+                    Err(e) => state.session.add_error_message(format!("Failed to open {}: {}", path, e)),
+                },
+                None => state.session.add_error_message("Usage: /open <path>:<line>".to_string()),
+            }
+            state.scroll_chat_to_bottom();
+            state.processing = false;
+            return;
+        }
+
+        if let Some(arg) = input.strip_prefix("/recent").filter(|rest| rest.is_empty() || rest.starts_with(' ')) {
+            let paths = state.recent_files.paths();
+            match arg.trim() {
+                _ if paths.is_empty() => state.session.add_system_message("No files read or written yet.".to_string()),
+                "" => {
+                    let listing: Vec<String> = paths.iter().enumerate().map(|(i, p)| format!("{:>3}. {}", i + 1, p)).collect();
+                    state.session.add_system_message(format!("Recent files (newest first):\n{}", listing.join("\n")));
+                }
+                n => match n.parse::<usize>().ok().and_then(|n| n.checked_sub(1)).and_then(|i| paths.get(i)) {
+                    Some(path) => {
+                        // Ready to be referenced in the next message or command
+                        state.input = format!("{} ", path);
+                        state.input_cursor = state.input.len();
+                    }
+                    None => state.session.add_error_message(format!("Usage: /recent [N] with N from 1 to {}", paths.len())),
+                },
+            }
+            state.scroll_chat_to_bottom();
+            state.processing = false;
+            return;
+        }
+
+        if let Some(arg) = input.strip_prefix("/diff").filter(|rest| rest.is_empty() || rest.starts_with(' ')) {
+            match arg.split_whitespace().collect::<Vec<_>>().as_slice() {
+                [old_path, new_path] => {
+                    match crate::diff::compare_files(old_path, new_path, &state.session.workspace_root()).await {
+                        Ok(comparison) => state.session.add_system_message(comparison),
+                        Err(e) => state.session.add_error_message(e),
+                    }
+                }
+                _ => state.session.add_error_message("Usage: /diff <pathA> <pathB>".to_string()),
+            }
+            state.scroll_chat_to_bottom();
+            state.processing = false;
+            return;
+        }
+
+        if let Some(arg) = input.strip_prefix("/attach").filter(|rest| rest.is_empty() || rest.starts_with(' ')) {
+            let path = arg.trim();
+            if path.is_empty() {
+                state.session.add_error_message("Usage: /attach <image path>".to_string());
+            } else {
+                match state.session.attach_image(path) {
+                    Ok(image) => {
+                        let message = format!("Attached {} ({}); it will be sent with your next message.", image.name, image.mime_type);
+                        state.session.add_system_message(message);
+                    }
+                    Err(e) => state.session.add_error_message(e),
+                }
+            }
+            state.scroll_chat_to_bottom();
+            state.processing = false;
+            return;
+        }
+
+        if let Some(arg) = input.strip_prefix("/history").filter(|rest| rest.is_empty() || rest.starts_with(' ')) {
+            let parts: Vec<&str> = arg.split_whitespace().collect();
+            match parts.as_slice() {
+                [] => {
+                    let listing = state.session.history_listing();
+                    state.session.add_system_message(listing);
+                }
+                ["clear-last", n] if n.parse::<usize>().is_ok() => {
+                    let removed = state.session.remove_last_messages(n.parse().unwrap_or_default());
+                    state.session.add_system_message(format!("Removed the last {} message(s) from history.", removed));
+                }
+                _ => state.session.add_error_message("Usage: /history or /history clear-last <N>".to_string()),
+            }
+            state.scroll_chat_to_bottom();
+            state.processing = false;
+            return;
+        }
+
+        if let Some(arg) = input.strip_prefix("/set").filter(|rest| rest.is_empty() || rest.starts_with(' ')) {
+            match arg.split_whitespace().collect::<Vec<_>>().as_slice() {
+                ["width", value] => match crate::state::parse_chat_column(value) {
+                    Ok(column) => {
+                        state.chat_column = column;
+                        let message = match column {
+                            Some(column) => format!("Chat text now wraps at {} columns.", column),
+                            None => "Chat text now uses the full panel width.".to_string(),
+                        };
+                        state.session.add_system_message(message);
+                    }
+                    Err(e) => state.session.add_error_message(e),
+                },
+                ["timestamps", value @ ("on" | "off")] => {
+                    state.show_timestamps = *value == "on";
+                    state.session.add_system_message(format!("Message timestamps {}.", value));
+                }
+                ["links", value] => match crate::markdown::LinkStyle::parse(value) {
+                    Ok(link_style) => {
+                        state.link_style = link_style;
+                        state.session.add_system_message(format!("Links now shown as {}.", value.to_ascii_lowercase()));
+                    }
+                    Err(e) => state.session.add_error_message(e),
+                },
+                _ => state.session.add_error_message("Usage: /set width <columns>|off, /set timestamps on|off or /set links inline|text".to_string()),
+            }
+            state.scroll_chat_to_bottom();
+            state.processing = false;
+            return;
+        }
+
+        for (command, pinned) in [("/pin", true), ("/unpin", false)] {
+            let Some(arg) = input.strip_prefix(command).filter(|rest| rest.is_empty() || rest.starts_with(' ')) else {
+                continue;
+            };
+            let arg = arg.trim();
+            let position = match arg {
+                "" => Ok(None),
+                n => n.parse::<usize>().map(Some).map_err(|_| format!("Usage: {} [N]", command)),
+            };
+            match position.and_then(|position| state.session.set_pinned(position, pinned)) {
+                Ok(n) if pinned => state.session.add_system_message(format!("Pinned message {}; /clear will keep it.", n)),
+                Ok(n) => state.session.add_system_message(format!("Unpinned message {}.", n)),
+                Err(e) => state.session.add_error_message(e),
+            }
+            state.scroll_chat_to_bottom();
+            state.processing = false;
+            return;
+        }
+
+        if let Some(arg) = input.strip_prefix("/readonly").filter(|rest| rest.is_empty() || rest.starts_with(' ')) {
+            let read_only = match arg.trim().to_ascii_lowercase().as_str() {
+                "" => Some(!state.session.read_only()),
+                "on" => Some(true),
+                "off" => Some(false),
+                _ => None,
+            };
+            match read_only {
+                Some(read_only) => {
+                    state.session.set_read_only(read_only);
+                    state.session.add_system_message(if read_only {
+                        "Read-only mode on: tools that write files or run commands are blocked.".to_string()
+                    } else {
+                        "Read-only mode off: all tools are available.".to_string()
+                    });
+                }
+                None => state.session.add_error_message("Usage: /readonly [on|off]".to_string()),
+            }
+            state.scroll_chat_to_bottom();
+            state.processing = false;
+            return;
+        }
+
+        if let Some(arg) = input.strip_prefix("/approve").filter(|rest| rest.is_empty() || rest.starts_with(' ')) {
+            match arg.trim().to_ascii_lowercase().as_str() {
+                "" => state.session.add_system_message(format!(
+                    "Patch approval: {}",
+                    if state.session.patch_approval() { "ask" } else { "auto" }
+                )),
+                "auto" => {
+                    state.session.set_patch_approval(false);
+                    state.session.add_system_message("Patch approval set to auto: fs.apply_patch edits are applied without asking.".to_string());
+                }
+                "ask" => {
+                    state.session.set_patch_approval(true);
+                    state.session.add_system_message("Patch approval set to ask: fs.apply_patch edits wait for your approval.".to_string());
+                }
+                _ => state.session.add_error_message("Usage: /approve [auto|ask]".to_string()),
+            }
+            state.scroll_chat_to_bottom();
+            state.processing = false;
+            return;
+        }
+
+        if let Some(arg) = input.strip_prefix("/copyblock").filter(|rest| rest.is_empty() || rest.starts_with(' ')) {
+            let blocks = state.code_blocks();
+            let number = match arg.trim() {
+                "" => Ok(blocks.len()),
+                n => n.parse::<usize>().map_err(|_| "Usage: /copyblock [N]".to_string()),
+            };
+            match number {
+                Ok(n) if n >= 1 && n <= blocks.len() => state.pending_copy = Some((n, blocks[n - 1].clone())),
+                Ok(_) if blocks.is_empty() => state.session.add_error_message("There are no code blocks in the chat.".to_string()),
+                Ok(n) => state.session.add_error_message(format!("No code block {}; the chat has {}.", n, blocks.len())),
+                Err(e) => state.session.add_error_message(e),
+            }
+            state.scroll_chat_to_bottom();
+            state.processing = false;
+            return;
+        }
+
+        if let Some(arg) = input.strip_prefix("/edit").filter(|rest| rest.is_empty() || rest.starts_with(' ')) {
+            let path = arg.trim();
+            if path.is_empty() {
+                state.session.add_error_message("Usage: /edit <path>".to_string());
+            } else if crate::editor::editor_from_env().is_none() {
+                state.session.add_error_message(crate::editor::NO_EDITOR_MESSAGE.to_string());
+            } else {
+                state.pending_edit = Some(state.session.workspace_root().join(path));
+            }
+            state.scroll_chat_to_bottom();
+            state.processing = false;
+            return;
+        }
+
+        if let Some(arg) = input.strip_prefix("/export").filter(|rest| rest.is_empty() || rest.starts_with(' ')) {
+            let path = arg.trim();
+            if path.is_empty() {
+                state.session.add_error_message("Usage: /export <path>".to_string());
+            } else {
+                match state.session.export_markdown_to(path) {
+                    Ok(written) => state.session.add_system_message(format!("Conversation exported to {}", written.display())),
+                    Err(e) => state.session.add_error_message(e),
+                }
+            }
+            state.scroll_chat_to_bottom();
+            state.processing = false;
+            return;
+        }
+
+        if let Some(arg) = input.strip_prefix("/cd").filter(|rest| rest.is_empty() || rest.starts_with(' ')) {
+            let arg = arg.trim();
+            if arg.is_empty() {
+                state.session.add_system_message(format!("Working directory: {}", state.session.workspace_root().display()));
+            } else {
+                match state.session.set_workspace_root(arg) {
+                    Ok(root) => state.session.add_system_message(format!("Working directory: {}", root.display())),
+                    Err(e) => state.session.add_error_message(e),
+                }
+            }
+            state.scroll_chat_to_bottom();
+            state.processing = false;
+            return;
+        }
+
+        // Sending a message always jumps back to the latest output
+        state.scroll_chat_to_bottom();
 
         // Process with session (this adds the user message immediately and
         // spawns a background task for the agent response)
@@ -393,6 +685,27 @@ impl InputHandler {
         }
     }
 
+    /// Submit or insert a newline on Enter, according to the submit mode; `send_chord`
+    /// is set when Enter came with Ctrl or Alt
+    async fn handle_enter(state: &mut AppState, send_chord: bool, enter_pending: bool) {
+        match state.submit_mode {
+            SubmitMode::Enter => Self::submit_input(state).await,
+            SubmitMode::CtrlEnter if send_chord => Self::submit_input(state).await,
+            SubmitMode::CtrlEnter => Self::insert_char(state, '\n'),
+            SubmitMode::DoubleEnter if enter_pending => {
+                // Drop the newline the first Enter inserted before sending
+                if state.input[..state.input_cursor].ends_with('\n') {
+                    Self::delete_char(state);
+                }
+                Self::submit_input(state).await;
+            }
+            SubmitMode::DoubleEnter => {
+                Self::insert_char(state, '\n');
+                state.enter_pending = true;
+            }
+        }
+    }
+
     /// Delete character before cursor
     fn delete_char(state: &mut AppState) {
         if state.input_cursor > 0 {
@@ -498,30 +811,13 @@ impl InputHandler {
         }
     }
 
-    /// Get filtered commands based on current filter
-    fn get_filtered_commands(state: &AppState) -> Vec<&crate::state::Command> {
-        state
-            .available_commands
-            .iter()
-            .filter(|cmd| {
-                if state.command_palette_filter.is_empty() {
-                    true
-                } else {
-                    cmd.name
-                        .to_lowercase()
-                        .contains(&state.command_palette_filter.to_lowercase())
-                        || cmd
-                            .description
-                            .to_lowercase()
-                            .contains(&state.command_palette_filter.to_lowercase())
-                }
-            })
-            .collect()
-    }
-
     /// Handle character input for command palette filtering
     fn handle_command_palette_char(state: &mut AppState, c: char) {
-        if c.is_alphanumeric() || c == '/' || c == ' ' || c == '-' || c == '_' {
+        // Past the first space the text is an argument (paths, `file:line`), so take anything printable
+        let in_argument = state.command_palette_filter.contains(' ');
+        if in_argument && !c.is_control() {
+            state.command_palette_filter.push(c);
+        } else if c.is_alphanumeric() || c == '/' || c == ' ' || c == '-' || c == '_' {
             state.command_palette_filter.push(c);
             // Reset selection when filter changes
             state.command_palette_selected = 0;
@@ -530,18 +826,314 @@ impl InputHandler {
 
     /// Execute the currently selected command
     async fn execute_selected_command(state: &mut AppState) {
-        let filtered_commands = Self::get_filtered_commands(state);
+        let filtered_commands = state.filtered_commands();
         if let Some(cmd) = filtered_commands.get(state.command_palette_selected) {
-            let command_text = cmd.name.clone();
+            let (name, arg) = (cmd.name.clone(), state.palette_query().1.map(str::to_string));
+            let needs_arg = cmd.requires_args() && arg.is_none();
 
-            // Close the command palette
-            state.command_palette_open = false;
-            state.command_palette_filter.clear();
-            state.command_palette_selected = 0;
+            state.close_command_palette();
+
+            if needs_arg {
+                // Leave the command in the input for the argument to be typed
+                state.input = format!("{} ", name);
+                state.input_cursor = state.input.len();
+                return;
+            }
 
             // Execute the command
-            state.input = command_text;
+            state.input = match arg {
+                Some(arg) => format!("{} {}", name, arg),
+                None => name,
+            };
             Self::submit_input(state).await;
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+    use grok_core::agent::agent_logic::MultiModelAgent;
+    use grok_core::{EventBus, Session};
+
+    fn test_state(chats_dir: &std::path::Path) -> AppState {
+        let bus = EventBus::new();
+        let agent = MultiModelAgent::new("test-key".to_string(), "test-model".to_string(), bus.sender())
+            .expect("agent should build");
+        let session = Session::new(std::sync::Arc::new(agent), bus.sender());
+        let (_sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+        AppState::new(session, receiver, chats_dir.to_path_buf())
+    }
+
+    async fn press(state: &mut AppState, key_code: KeyCode) {
+        InputHandler::handle_key_event(state, key_code, KeyModifiers::NONE).await;
+    }
+
+    #[tokio::test]
+    async fn test_approval_modal_sends_toggled_ops() {
+        let chats_dir = tempfile::tempdir().unwrap();
+        let mut state = test_state(chats_dir.path());
+        let op = |description: &str| grok_core::PatchOpPreview { description: description.to_string(), diff: String::new() };
+        let (request, reply) = grok_core::PatchApprovalRequest::new("t1".to_string(), vec![op("set_file a"), op("set_file b")]);
+        state.pending_approval = Some(crate::state::PendingApproval::new(request));
+
+        // Keys go to the modal, not the input
+        press(&mut state, KeyCode::Down).await;
+        press(&mut state, KeyCode::Char(' ')).await;
+        assert!(state.input.is_empty());
+        press(&mut state, KeyCode::Enter).await;
+
+        assert!(state.pending_approval.is_none());
+        assert_eq!(reply.await.unwrap(), vec![true, false]);
+    }
+
+    #[tokio::test]
+    async fn test_tab_closes_command_palette_before_switching() {
+        let chats_dir = tempfile::tempdir().unwrap();
+        let mut state = test_state(chats_dir.path());
+
+        press(&mut state, KeyCode::Char('/')).await;
+        press(&mut state, KeyCode::Char('c')).await;
+        assert!(state.command_palette_open);
+
+        press(&mut state, KeyCode::Tab).await;
+        assert!(!state.command_palette_open);
+        assert!(state.command_palette_filter.is_empty());
+        assert_eq!(state.focused_panel, 1);
+
+        // Typing after returning to the input goes to the input, not a stale filter
+        state.focused_panel = 0;
+        press(&mut state, KeyCode::Char('x')).await;
+        assert_eq!(state.input, "x");
+    }
+
+    #[tokio::test]
+    async fn test_tab_skips_tools_panel_without_tools() {
+        let chats_dir = tempfile::tempdir().unwrap();
+        let mut state = test_state(chats_dir.path());
+        state.focused_panel = 0;
+
+        let mut visited = Vec::new();
+        for _ in 0..4 {
+            press(&mut state, KeyCode::Tab).await;
+            visited.push(state.focused_panel);
+        }
+        assert_eq!(visited, vec![1, 0, 1, 0]);
+
+        // Once a tool has run, Tab reaches the tools panel too
+        state.session.handle_tool_begin("t1".to_string(), grok_core::ToolName::FsRead, "Reading".to_string(), None);
+        press(&mut state, KeyCode::Tab).await;
+        press(&mut state, KeyCode::Tab).await;
+        assert_eq!(state.focused_panel, 2);
+        press(&mut state, KeyCode::Tab).await;
+        assert_eq!(state.focused_panel, 0);
+    }
+
+    #[test]
+    fn test_focus_order_parse() {
+        use crate::state::FocusOrder;
+
+        let order = FocusOrder::parse("chat, input").unwrap();
+        assert_eq!(order.next(1, |_| true), 0);
+        assert_eq!(order.next(0, |_| true), 1);
+        // A panel outside the order moves to its start
+        assert_eq!(order.next(2, |_| true), 1);
+
+        assert!(FocusOrder::parse("input,sidebar").is_err());
+        assert!(FocusOrder::parse("chat,tools").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_arrow_keys_with_command_palette_open() {
+        let chats_dir = tempfile::tempdir().unwrap();
+        let mut state = test_state(chats_dir.path());
+
+        press(&mut state, KeyCode::Char('/')).await;
+        press(&mut state, KeyCode::Down).await;
+        press(&mut state, KeyCode::Down).await;
+        press(&mut state, KeyCode::Up).await;
+        // Up and Down move the selection and keep the palette open
+        assert!(state.command_palette_open);
+        assert_eq!(state.command_palette_selected, 1);
+
+        // Left and Right edit the input, so they close the palette
+        press(&mut state, KeyCode::Left).await;
+        assert!(!state.command_palette_open);
+        assert_eq!(state.command_palette_selected, 0);
+        assert_eq!(state.focused_panel, 0);
+    }
+
+    async fn type_text(state: &mut AppState, text: &str) {
+        for c in text.chars() {
+            press(state, KeyCode::Char(c)).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_palette_passes_argument_to_command() {
+        let chats_dir = tempfile::tempdir().unwrap();
+        let mut state = test_state(chats_dir.path());
+        state.session.add_user_message("first".to_string());
+        state.session.add_user_message("second".to_string());
+
+        press(&mut state, KeyCode::Char('/')).await;
+        type_text(&mut state, "history clear-last 1").await;
+        assert_eq!(state.filtered_commands()[0].name, "/history");
+        press(&mut state, KeyCode::Enter).await;
+
+        // `/history clear-last 1` ran: one message removed, then the confirmation added
+        let messages = state.session.messages();
+        assert_eq!(messages[0].content, "first");
+        assert_eq!(messages[1].content, "Removed the last 1 message(s) from history.");
+        assert!(!state.command_palette_open);
+    }
+
+    #[tokio::test]
+    async fn test_palette_prefills_command_that_needs_an_argument() {
+        let chats_dir = tempfile::tempdir().unwrap();
+        let mut state = test_state(chats_dir.path());
+
+        press(&mut state, KeyCode::Char('/')).await;
+        type_text(&mut state, "open").await;
+        press(&mut state, KeyCode::Enter).await;
+
+        assert!(!state.command_palette_open);
+        assert_eq!(state.input, "/open ");
+        assert_eq!(state.input_cursor, state.input.len());
+        assert!(state.session.messages().is_empty());
+    }
+
+    /// State in `mode` with a harmless command typed into the input
+    fn submit_mode_state(chats_dir: &std::path::Path, mode: SubmitMode) -> AppState {
+        let mut state = test_state(chats_dir);
+        state.submit_mode = mode;
+        state.input = "/history".to_string();
+        state.input_cursor = state.input.len();
+        state
+    }
+
+    #[tokio::test]
+    async fn test_enter_submits_by_default() {
+        let chats_dir = tempfile::tempdir().unwrap();
+        let mut state = submit_mode_state(chats_dir.path(), SubmitMode::default());
+
+        press(&mut state, KeyCode::Enter).await;
+        assert!(state.input.is_empty());
+        assert!(!state.session.messages().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_ctrl_enter_submit_mode() {
+        let chats_dir = tempfile::tempdir().unwrap();
+        let mut state = submit_mode_state(chats_dir.path(), SubmitMode::CtrlEnter);
+
+        press(&mut state, KeyCode::Enter).await;
+        assert_eq!(state.input, "/history\n");
+        assert!(state.session.messages().is_empty());
+
+        // The newline is sent with the message; trimming drops it
+        InputHandler::handle_key_event(&mut state, KeyCode::Enter, KeyModifiers::CONTROL).await;
+        assert!(state.input.is_empty());
+        assert!(!state.session.messages().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_ctrl_enter_mode_fallback_keys_submit() {
+        let chats_dir = tempfile::tempdir().unwrap();
+        // Legacy terminals report Ctrl+Enter as Ctrl+J; Alt+Enter works everywhere
+        for (key_code, modifiers) in [(KeyCode::Char('j'), KeyModifiers::CONTROL), (KeyCode::Enter, KeyModifiers::ALT)] {
+            let mut state = submit_mode_state(chats_dir.path(), SubmitMode::CtrlEnter);
+            InputHandler::handle_key_event(&mut state, key_code, modifiers).await;
+            assert!(state.input.is_empty(), "{:?} {:?} should send", key_code, modifiers);
+            assert!(!state.session.messages().is_empty());
+        }
+
+        // Outside Ctrl+Enter mode Ctrl+J is just a key, not a send
+        let mut state = submit_mode_state(chats_dir.path(), SubmitMode::Enter);
+        InputHandler::handle_key_event(&mut state, KeyCode::Char('j'), KeyModifiers::CONTROL).await;
+        assert!(state.session.messages().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_double_enter_submit_mode() {
+        let chats_dir = tempfile::tempdir().unwrap();
+        let mut state = submit_mode_state(chats_dir.path(), SubmitMode::DoubleEnter);
+
+        // Enter, a key, then Enter again inserts two newlines without sending
+        press(&mut state, KeyCode::Enter).await;
+        press(&mut state, KeyCode::Char('x')).await;
+        press(&mut state, KeyCode::Enter).await;
+        assert_eq!(state.input, "/history\nx\n");
+        assert!(state.session.messages().is_empty());
+
+        // Two Enters in a row send, without the newline the first one added
+        let mut state = submit_mode_state(chats_dir.path(), SubmitMode::DoubleEnter);
+        press(&mut state, KeyCode::Enter).await;
+        assert_eq!(state.input, "/history\n");
+        press(&mut state, KeyCode::Enter).await;
+        assert!(state.input.is_empty());
+        assert!(!state.session.messages().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_approve_command_switches_patch_approval() {
+        let chats_dir = tempfile::tempdir().unwrap();
+        let mut state = test_state(chats_dir.path());
+        state.session.set_patch_approval(true);
+
+        for (command, expected) in [("/approve auto", false), ("/approve bogus", false), ("/approve ASK", true)] {
+            state.input = command.to_string();
+            state.input_cursor = state.input.len();
+            press(&mut state, KeyCode::Enter).await;
+            assert_eq!(state.session.patch_approval(), expected, "after {}", command);
+        }
+        let last = state.session.messages().last().unwrap();
+        assert!(last.content.starts_with("Patch approval set to ask"));
+    }
+
+    #[tokio::test]
+    async fn test_open_adds_a_preview_message() {
+        let chats_dir = tempfile::tempdir().unwrap();
+        let workspace = tempfile::tempdir().unwrap();
+        std::fs::write(workspace.path().join("notes.txt"), "alpha\nbeta\n").unwrap();
+        let mut state = test_state(chats_dir.path());
+        state.session.set_workspace_root(&workspace.path().to_string_lossy()).unwrap();
+
+        state.input = "/open notes.txt:2".to_string();
+        state.input_cursor = state.input.len();
+        press(&mut state, KeyCode::Enter).await;
+
+        let last = state.session.messages().last().unwrap();
+        assert_eq!(last.kind, grok_core::MessageKind::FilePreview);
+        assert!(last.content.contains("notes.txt:2"));
+    }
+
+    #[tokio::test]
+    async fn test_copyblock_picks_numbered_block() {
+        let chats_dir = tempfile::tempdir().unwrap();
+        let mut state = test_state(chats_dir.path());
+        state.session.add_agent_message("```\nfirst\n```".to_string());
+        state.session.add_user_message("```\nnot mine\n```".to_string());
+        state.session.add_agent_message("Two:\n\n```rust\nsecond\n```\n\n```\nthird\n```".to_string());
+
+        for (command, expected) in [
+            ("/copyblock 2", Some((2, "second\n".to_string()))),
+            ("/copyblock", Some((3, "third\n".to_string()))),
+            ("/copyblock 4", None),
+        ] {
+            state.input = command.to_string();
+            state.input_cursor = state.input.len();
+            press(&mut state, KeyCode::Enter).await;
+            assert_eq!(state.pending_copy.take(), expected, "{}", command);
+        }
+    }
+
+    #[test]
+    fn test_submit_mode_parse() {
+        assert_eq!(SubmitMode::parse("enter"), Ok(SubmitMode::Enter));
+        assert_eq!(SubmitMode::parse(" Ctrl-Enter "), Ok(SubmitMode::CtrlEnter));
+        assert_eq!(SubmitMode::parse("double_enter"), Ok(SubmitMode::DoubleEnter));
+        assert!(SubmitMode::parse("shift-enter").is_err());
+    }
+}
@@ -1,10 +1,14 @@
 //! TUI library for Grok Code, providing the terminal user interface with app structure, components, and event handling.
 
 pub mod app;
+pub mod clipboard;
 pub mod components;
+pub mod diff;
+pub mod editor;
 pub mod events;
 pub mod handlers;
 pub mod markdown;
+pub mod preview;
 pub mod state;
 pub mod utils;
 
@@ -1,26 +1,17 @@
 use anyhow::Result;
-use grok_core::{AgentFactory, EventBus, Session};
+use grok_core::session::resolve_dir;
+use grok_core::{AgentFactory, Config, EventBus, Session};
 use std::env;
-use std::io::{self, Write};
+use std::io::{self, IsTerminal};
 use tracing::info;
 
-mod app;
-mod components;
-mod events;
-mod handlers;
-pub mod markdown;
-mod state;
-mod utils;
-
-use app::App;
+use grok_tui::utils::api_key::{self, prompt_for_key, Prompted};
+use grok_tui::App;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize tracing - only log to stderr and filter out less important messages
-    tracing_subscriber::fmt()
-        .with_max_level(tracing::Level::WARN)
-        .with_writer(std::io::stderr)
-        .init();
+    // Initialize tracing - stderr only, WARN unless GROK_LOG / RUST_LOG says otherwise
+    grok_tui::utils::logging::init();
     info!("Starting Grok Code TUI");
     
     // Create event bus for communication
@@ -31,27 +22,55 @@ async fn main() -> Result<()> {
     let _ = dotenvy::dotenv();
 
     // Check for OpenRouter API key and prompt if missing
-    if env::var("OPENROUTER_API_KEY").is_err() {
-        println!("OpenRouter API key not found in environment.");
-        println!("Get one from: https://openrouter.ai/keys");
-        print!("Enter your API key: ");
-        io::stdout().flush()?;
-        
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
-        let key = input.trim().to_string();
-        
-        if key.is_empty() {
-            eprintln!("Error: API key cannot be empty.");
-            std::process::exit(1);
+    if env::var("OPENROUTER_API_KEY").map_or(true, |key| key.trim().is_empty()) {
+        let interactive = io::stdin().is_terminal();
+        match prompt_for_key(interactive, &mut io::stdin().lock(), &mut io::stdout())? {
+            Prompted::Key(key) => {
+                env::set_var("OPENROUTER_API_KEY", key);
+                println!("API key set. Proceeding...");
+            }
+            Prompted::Empty => {
+                eprintln!("Error: API key cannot be empty.");
+                std::process::exit(1);
+            }
+            Prompted::NotInteractive => {
+                eprintln!("{}", api_key::NOT_INTERACTIVE_MESSAGE);
+                std::process::exit(api_key::NOT_INTERACTIVE_EXIT_CODE);
+            }
         }
-        
-        env::set_var("OPENROUTER_API_KEY", key);
-        println!("API key set. Proceeding...");
     }
 
+    // Optional --cwd points tools (and the project config lookup) at another directory
+    let workspace_root = match grok_tui::utils::args::cwd_flag(env::args().skip(1)) {
+        Ok(Some(path)) => match resolve_dir(&env::current_dir()?, &path) {
+            Ok(root) => Some(root),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        },
+        Ok(None) => None,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let project_dir = match &workspace_root {
+        Some(root) => root.clone(),
+        None => env::current_dir()?,
+    };
+
+    // Project settings from .grok/config.toml; env vars still take precedence
+    let config = match Config::load(&project_dir) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Warning: ignoring project config: {}", e);
+            Config::default()
+        }
+    };
+
     // Create OpenRouter agent (now with key guaranteed to be set)
-    let agent = match AgentFactory::create_openrouter_from_env(event_sender.clone()) {
+    let agent = match AgentFactory::create_openrouter_with_config(event_sender.clone(), &config) {
         Ok(agent) => agent,
         Err(e) => {
             eprintln!("Error creating agent: {}. Please check your API key.", e);
@@ -61,6 +80,9 @@ async fn main() -> Result<()> {
     
     // Create session
     let mut session = Session::new(agent, event_sender.clone());
+    if let Some(root) = workspace_root {
+        session = session.with_workspace_root(root);
+    }
     
     // Create and run the TUI application
     let mut app = App::new(session, event_bus.into_receiver());
@@ -1,25 +1,126 @@
-use pulldown_cmark::{Event, Parser, Tag, TagEnd};
+use pulldown_cmark::{Event, HeadingLevel, Parser, Tag, TagEnd};
 use ratatui::{
     style::{Color, Modifier, Style},
     text::{Line, Span},
 };
 
+/// Style of the lines inside a fenced code block
+const CODE_BLOCK_STYLE: Style = Style::new().fg(Color::Green).bg(Color::Black);
+
+/// Borders drawn above and below a fenced code block
+const CODE_BLOCK_TOP: &str = "┌─ Code Block ─────────────────────────────";
+const CODE_BLOCK_BOTTOM: &str = "└─────────────────────────────────────────";
+
+/// Horizontal rules span the wrap width; this is their length before wrapping
+const RULE_WIDTH: usize = 80;
+const RULE_STYLE: Style = Style::new().fg(Color::DarkGray);
+
+/// A horizontal rule `width` columns long
+fn rule_line(width: usize) -> Line<'static> {
+    Line::from(Span::styled("─".repeat(width), RULE_STYLE))
+}
+
+/// Whether `line` is a horizontal rule, which wrapping resizes rather than splits
+fn is_rule_line(line: &Line) -> bool {
+    match line.spans.as_slice() {
+        [span] => span.style == RULE_STYLE && !span.content.is_empty() && span.content.chars().all(|c| c == '─'),
+        _ => false,
+    }
+}
+
+/// Headings get less emphasis the deeper they are, since a terminal can't change text size
+fn heading_style(level: HeadingLevel) -> Style {
+    match level {
+        HeadingLevel::H1 => Style::new().fg(Color::Yellow).add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+        HeadingLevel::H2 => Style::new().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        HeadingLevel::H3 => Style::new().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+        HeadingLevel::H4 => Style::new().fg(Color::Cyan),
+        HeadingLevel::H5 => Style::new().fg(Color::Blue).add_modifier(Modifier::ITALIC),
+        HeadingLevel::H6 => Style::new().fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
+    }
+}
+
+/// Style of the text inside a `>` blockquote, and of its bars
+const QUOTE_STYLE: Style = Style::new().fg(Color::DarkGray).add_modifier(Modifier::ITALIC);
+const QUOTE_BAR_STYLE: Style = Style::new().fg(Color::DarkGray);
+
+/// Start an empty line inside `depth` blockquotes with one bar per level
+fn start_quoted_line(current_line: &mut Vec<Span<'static>>, depth: usize) {
+    if depth > 0 && current_line.is_empty() {
+        current_line.push(Span::styled("│ ".repeat(depth), QUOTE_BAR_STYLE));
+    }
+}
+
+/// The bars alone, separating paragraphs inside a blockquote
+fn quote_gap_line(depth: usize) -> Line<'static> {
+    Line::from(Span::styled("│ ".repeat(depth).trim_end().to_string(), QUOTE_BAR_STYLE))
+}
+
+/// Style of a link's text
+const LINK_STYLE: Style = Style::new().fg(Color::Blue).add_modifier(Modifier::UNDERLINED);
+
+/// How `[text](url)` links are shown, from `GROK_LINK_STYLE` or `/set links`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LinkStyle {
+    /// The link text followed by its URL, as `text (url)`
+    #[default]
+    Inline,
+    /// Only the link text
+    Text,
+}
+
+impl LinkStyle {
+    /// Parse `inline` or `text`
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "inline" => Ok(Self::Inline),
+            "text" => Ok(Self::Text),
+            other => Err(format!("Unknown link style '{}'; use inline or text", other)),
+        }
+    }
+
+    /// The style named by `GROK_LINK_STYLE`, or inline when unset or invalid
+    pub fn from_env() -> Self {
+        std::env::var("GROK_LINK_STYLE")
+            .ok()
+            .and_then(|v| Self::parse(&v).ok())
+            .unwrap_or_default()
+    }
+}
+
 /// Converts markdown text to styled ratatui Lines
-pub fn parse_markdown(text: &str) -> Vec<Line<'static>> {
+pub fn parse_markdown(text: &str, link_style: LinkStyle) -> Vec<Line<'static>> {
     let parser = Parser::new(text);
     let mut lines = Vec::new();
     let mut current_line: Vec<Span> = Vec::new();
     let mut style_stack = Vec::new();
     let mut in_code_block = false;
     let mut code_block_lines: Vec<String> = Vec::new();
-    let mut list_depth: usize = 0;
+    // One entry per open list: the next number for ordered lists, None for bullets
+    let mut lists: Vec<Option<u64>> = Vec::new();
+    // The current line holds only a list marker, so the item's first paragraph joins it
+    let mut at_item_start = false;
+    // Open blockquotes, and whether a paragraph inside them ended so the next needs a gap
+    let mut quote_depth: usize = 0;
+    let mut quote_gap = false;
+    // URL of the link being read, and its text so far
+    let mut link: Option<(String, String)> = None;
+    // Inside a `---` underlined (setext) heading, which is shown as text then a rule
+    let mut dash_heading = false;
     
-    for event in parser {
+    for (event, range) in parser.into_offset_iter() {
         match event {
             Event::Start(tag) => {
                 match tag {
-                    Tag::Heading { .. } => {
-                        style_stack.push(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+                    Tag::Heading { level, .. } => {
+                        if !current_line.is_empty() {
+                            lines.push(Line::from(current_line.clone()));
+                            current_line.clear();
+                        }
+                        // Text with `---` under it is usually prose followed by a rule rather than
+                        // a heading; `#` and `===` headings are unambiguous
+                        dash_heading = level == HeadingLevel::H2 && !text[range].trim_start().starts_with('#');
+                        style_stack.push(if dash_heading { Style::default() } else { heading_style(level) });
                     }
                     Tag::Emphasis => {
                         style_stack.push(Style::default().add_modifier(Modifier::ITALIC));
@@ -36,12 +137,12 @@ pub fn parse_markdown(text: &str) -> Vec<Line<'static>> {
                         }
                         // Add a separator line before code block
                         lines.push(Line::from(Span::styled(
-                            "┌─ Code Block ─────────────────────────────",
+                            CODE_BLOCK_TOP,
                             Style::default().fg(Color::DarkGray)
                         )));
                     }
-                    Tag::List(_) => {
-                        list_depth += 1;
+                    Tag::List(start) => {
+                        lists.push(start);
                     }
                     Tag::Item => {
                         // End current line for list item
@@ -49,31 +150,86 @@ pub fn parse_markdown(text: &str) -> Vec<Line<'static>> {
                             lines.push(Line::from(current_line.clone()));
                             current_line.clear();
                         }
-                        // Add list marker
-                        let indent = "  ".repeat(list_depth.saturating_sub(1));
+                        // Add list marker, indented by nesting level and numbered per list
+                        let indent = "  ".repeat(lists.len().saturating_sub(1));
+                        let marker = match lists.last_mut() {
+                            Some(Some(number)) => {
+                                *number += 1;
+                                format!("{}. ", *number - 1)
+                            }
+                            _ => "• ".to_string(),
+                        };
+                        start_quoted_line(&mut current_line, quote_depth);
                         current_line.push(Span::styled(
-                            format!("{}• ", indent),
+                            format!("{}{}", indent, marker),
                             Style::default().fg(Color::Cyan)
                         ));
+                        at_item_start = true;
                     }
+                    // Start a new paragraph
                     Tag::Paragraph => {
-                        // Start a new paragraph
-                        if !current_line.is_empty() {
+                        if !current_line.is_empty() && !at_item_start {
                             lines.push(Line::from(current_line.clone()));
                             current_line.clear();
                         }
+                        if quote_gap {
+                            lines.push(quote_gap_line(quote_depth));
+                            quote_gap = false;
+                        }
+                    }
+                    Tag::Link { dest_url, .. } => {
+                        style_stack.push(LINK_STYLE);
+                        link = Some((dest_url.to_string(), String::new()));
                     }
                     Tag::BlockQuote(_) => {
-                        style_stack.push(Style::default().fg(Color::DarkGray));
-                        current_line.push(Span::styled("│ ", Style::default().fg(Color::DarkGray)));
+                        if !current_line.is_empty() {
+                            lines.push(Line::from(current_line.clone()));
+                            current_line.clear();
+                        }
+                        if quote_gap {
+                            lines.push(quote_gap_line(quote_depth));
+                            quote_gap = false;
+                        }
+                        quote_depth += 1;
+                        style_stack.push(QUOTE_STYLE);
                     }
                     _ => {}
                 }
             }
             Event::End(tag) => {
                 match tag {
-                    TagEnd::Heading(_) | TagEnd::Emphasis | TagEnd::Strong | TagEnd::BlockQuote => {
+                    TagEnd::Heading(_) => {
+                        style_stack.pop();
+                        if !current_line.is_empty() {
+                            lines.push(Line::from(current_line.clone()));
+                            current_line.clear();
+                        }
+                        if dash_heading {
+                            lines.push(rule_line(RULE_WIDTH));
+                            dash_heading = false;
+                        }
+                        lines.push(Line::from(""));
+                    }
+                    TagEnd::Emphasis | TagEnd::Strong => {
+                        style_stack.pop();
+                    }
+                    TagEnd::BlockQuote => {
+                        style_stack.pop();
+                        quote_depth = quote_depth.saturating_sub(1);
+                        // Space after the outermost quote, like after a paragraph
+                        if quote_depth == 0 {
+                            quote_gap = false;
+                            lines.push(Line::from(""));
+                        }
+                    }
+                    TagEnd::Link => {
                         style_stack.pop();
+                        // Autolinks (`<https://...>`) already show their URL as the text
+                        if let Some((url, text)) = link.take() {
+                            if link_style == LinkStyle::Inline && text != url {
+                                current_line.push(Span::styled(format!(" ({})", url), Style::default().fg(Color::DarkGray)));
+                            }
+                        }
                     }
                     TagEnd::CodeBlock => {
                         in_code_block = false;
@@ -81,27 +237,25 @@ pub fn parse_markdown(text: &str) -> Vec<Line<'static>> {
                         for code_line in &code_block_lines {
                             lines.push(Line::from(Span::styled(
                                 format!("│ {}", code_line),
-                                Style::default().fg(Color::Green).bg(Color::Black)
+                                CODE_BLOCK_STYLE
                             )));
                         }
                         code_block_lines.clear();
                         // Add closing border
                         lines.push(Line::from(Span::styled(
-                            "└─────────────────────────────────────────",
+                            CODE_BLOCK_BOTTOM,
                             Style::default().fg(Color::DarkGray)
                         )));
                         // Add a blank line after code block
                         lines.push(Line::from(""));
                     }
                     TagEnd::List(_) => {
-                        list_depth = list_depth.saturating_sub(1);
+                        lists.pop();
                     }
-                    TagEnd::Item => {
-                        // End the list item line
-                        if !current_line.is_empty() {
-                            lines.push(Line::from(current_line.clone()));
-                            current_line.clear();
-                        }
+                    // End the list item line
+                    TagEnd::Item if !current_line.is_empty() => {
+                        lines.push(Line::from(current_line.clone()));
+                        current_line.clear();
                     }
                     TagEnd::Paragraph => {
                         // End paragraph and add spacing
@@ -109,7 +263,11 @@ pub fn parse_markdown(text: &str) -> Vec<Line<'static>> {
                             lines.push(Line::from(current_line.clone()));
                             current_line.clear();
                         }
-                        lines.push(Line::from(""));
+                        if quote_depth > 0 {
+                            quote_gap = true;
+                        } else {
+                            lines.push(Line::from(""));
+                        }
                     }
                     _ => {}
                 }
@@ -119,7 +277,11 @@ pub fn parse_markdown(text: &str) -> Vec<Line<'static>> {
                     // Collect code block text
                     code_block_lines.extend(text.lines().map(|line| line.to_string()));
                 } else {
+                    at_item_start = false;
                     let current_style = style_stack.last().copied().unwrap_or_default();
+                    if let Some((_, link_text)) = link.as_mut() {
+                        link_text.push_str(&text);
+                    }
                     
                     // Handle line breaks in text
                     let text_lines: Vec<&str> = text.lines().collect();
@@ -130,6 +292,7 @@ pub fn parse_markdown(text: &str) -> Vec<Line<'static>> {
                             current_line.clear();
                         }
                         if !line.is_empty() {
+                            start_quoted_line(&mut current_line, quote_depth);
                             current_line.push(Span::styled(line.to_string(), current_style));
                         }
                     }
@@ -137,6 +300,8 @@ pub fn parse_markdown(text: &str) -> Vec<Line<'static>> {
             }
             Event::Code(text) => {
                 // Inline code
+                at_item_start = false;
+                start_quoted_line(&mut current_line, quote_depth);
                 current_line.push(Span::styled(
                     text.to_string(),
                     Style::default().fg(Color::Green).bg(Color::Black)
@@ -155,10 +320,7 @@ pub fn parse_markdown(text: &str) -> Vec<Line<'static>> {
                     lines.push(Line::from(current_line.clone()));
                     current_line.clear();
                 }
-                lines.push(Line::from(Span::styled(
-                    "─".repeat(80),
-                    Style::default().fg(Color::DarkGray)
-                )));
+                lines.push(rule_line(RULE_WIDTH));
                 lines.push(Line::from(""));
             }
             _ => {}
@@ -171,7 +333,7 @@ pub fn parse_markdown(text: &str) -> Vec<Line<'static>> {
     }
     
     // Remove trailing empty lines but keep at least one if the original had content
-    while lines.len() > 1 && lines.last().map_or(false, |line| {
+    while lines.len() > 1 && lines.last().is_some_and(|line| {
         line.spans.is_empty() || (line.spans.len() == 1 && line.spans[0].content.trim().is_empty())
     }) {
         lines.pop();
@@ -180,6 +342,61 @@ pub fn parse_markdown(text: &str) -> Vec<Line<'static>> {
     lines
 }
 
+/// Number the code block borders in `lines`, counting on from `next`, so `/copyblock` can name them
+pub fn label_code_blocks(lines: &mut [Line<'static>], next: &mut usize) {
+    for line in lines.iter_mut().filter(|line| matches!(line.spans.as_slice(), [span] if span.content == CODE_BLOCK_TOP)) {
+        let label = format!("┌─ Code Block {} ", next);
+        let fill = CODE_BLOCK_TOP.chars().count().saturating_sub(label.chars().count());
+        line.spans[0].content = format!("{}{}", label, "─".repeat(fill)).into();
+        *next += 1;
+    }
+}
+
+/// The raw source of each code block in `text`, in the order they're rendered
+pub fn code_blocks(text: &str) -> Vec<String> {
+    let mut blocks = Vec::new();
+    let mut current: Option<String> = None;
+    for event in Parser::new(text) {
+        match event {
+            Event::Start(Tag::CodeBlock(_)) => current = Some(String::new()),
+            Event::Text(text) => {
+                if let Some(block) = current.as_mut() {
+                    block.push_str(&text);
+                }
+            }
+            Event::End(TagEnd::CodeBlock) => blocks.extend(current.take()),
+            _ => {}
+        }
+    }
+    blocks
+}
+
+/// Whether `line` is part of a fenced code block, including its borders
+fn is_code_block_line(line: &Line) -> bool {
+    match line.spans.as_slice() {
+        [span] => {
+            (span.style == CODE_BLOCK_STYLE && span.content.starts_with("│ "))
+                || span.content == CODE_BLOCK_TOP
+                || span.content == CODE_BLOCK_BOTTOM
+        }
+        _ => false,
+    }
+}
+
+/// Wrap prose at `prose_width` while code blocks keep the panel's `full_width`
+pub fn wrap_markdown_lines_at(lines: Vec<Line<'static>>, prose_width: usize, full_width: usize) -> Vec<Line<'static>> {
+    if prose_width >= full_width {
+        return wrap_markdown_lines(lines, full_width);
+    }
+    lines
+        .into_iter()
+        .flat_map(|line| {
+            let width = if is_code_block_line(&line) { full_width } else { prose_width };
+            wrap_markdown_lines(vec![line], width)
+        })
+        .collect()
+}
+
 /// Wraps markdown lines to fit within a given width
 pub fn wrap_markdown_lines(lines: Vec<Line<'static>>, width: usize) -> Vec<Line<'static>> {
     // If width is 0, don't wrap to avoid infinite loops
@@ -194,6 +411,10 @@ pub fn wrap_markdown_lines(lines: Vec<Line<'static>>, width: usize) -> Vec<Line<
             wrapped_lines.push(line);
             continue;
         }
+        if is_rule_line(&line) {
+            wrapped_lines.push(rule_line(width));
+            continue;
+        }
         
         let total_content_len: usize = line.spans.iter()
             .map(|span| span.content.len())
@@ -270,7 +491,7 @@ mod tests {
     #[test]
     fn test_basic_markdown_parsing() {
         let markdown = "# Hello World\n\nThis is **bold** text and *italic* text.\n\n```rust\nfn main() {\n    println!(\"Hello\");\n}\n```\n\n- Item 1\n- Item 2";
-        let lines = parse_markdown(markdown);
+        let lines = parse_markdown(markdown, LinkStyle::default());
         
         // Should have multiple lines with different styles
         assert!(!lines.is_empty());
@@ -295,10 +516,29 @@ mod tests {
         assert!(wrapped.len() > 1); // Should be wrapped into multiple lines
     }
 
+    #[test]
+    fn test_reading_column_wraps_prose_but_not_code() {
+        let code_line = "let result = compute_something(first_argument, second_argument, third_argument);";
+        let markdown = format!(
+            "This paragraph is long enough that it has to wrap once the reading column is set to forty.\n\n```rust\n{}\n```",
+            code_line
+        );
+        let wrapped = wrap_markdown_lines_at(parse_markdown(&markdown, LinkStyle::default()), 40, 120);
+        let rendered: Vec<String> = wrapped
+            .iter()
+            .map(|line| line.spans.iter().map(|span| span.content.as_ref()).collect())
+            .collect();
+
+        let prose: Vec<&String> = rendered.iter().take_while(|line| !line.is_empty()).collect();
+        assert!(prose.len() > 1);
+        assert!(prose.iter().all(|line| line.len() <= 40));
+        assert!(rendered.contains(&format!("│ {}", code_line)));
+    }
+
     #[test]
     fn test_code_block_rendering() {
         let markdown = "Here's some code:\n\n```rust\nfn hello() {\n    println!(\"Hello, world!\");\n}\n```\n\nThat was code.";
-        let lines = parse_markdown(markdown);
+        let lines = parse_markdown(markdown, LinkStyle::default());
         
         // Should contain code block borders and content
         let content: String = lines.iter()
@@ -311,4 +551,117 @@ mod tests {
         assert!(content.contains("hello()"));
         assert!(content.contains("│")); // Code block should have borders
     }
+
+    #[test]
+    fn test_link_shows_text_and_url() {
+        let rendered = |link_style| -> String {
+            parse_markdown("See [the docs](https://docs.rs/ratatui) for more.", link_style)
+                .iter()
+                .flat_map(|line| &line.spans)
+                .map(|span| span.content.as_ref())
+                .collect()
+        };
+
+        assert_eq!(rendered(LinkStyle::Inline), "See the docs (https://docs.rs/ratatui) for more.");
+        assert_eq!(rendered(LinkStyle::Text), "See the docs for more.");
+
+        let lines = parse_markdown("[the docs](https://docs.rs/ratatui)", LinkStyle::Inline);
+        assert_eq!(lines[0].spans[0].style, LINK_STYLE);
+
+        // An autolink isn't followed by a second copy of its URL
+        let autolink = parse_markdown("<https://docs.rs>", LinkStyle::Inline);
+        assert_eq!(autolink[0].spans.len(), 1);
+    }
+
+    #[test]
+    fn test_nested_ordered_lists() {
+        let markdown = "1. First\n   1. Sub one\n   2. Sub two\n2. Second\n   - Bullet\n3. Third\n\nBetween lists\n\n5. New\n\n6. Loose";
+        let rendered: Vec<String> = parse_markdown(markdown, LinkStyle::default())
+            .iter()
+            .map(|line| line.spans.iter().map(|span| span.content.as_ref()).collect())
+            .filter(|line: &String| !line.is_empty())
+            .collect();
+
+        assert_eq!(rendered, vec![
+            "1. First",
+            "  1. Sub one",
+            "  2. Sub two",
+            "2. Second",
+            "  • Bullet",
+            "3. Third",
+            "Between lists",
+            "5. New",
+            "6. Loose",
+        ]);
+    }
+
+    #[test]
+    fn test_blockquote_rendering() {
+        let markdown = "> First line\n> continues here\n>\n> Second paragraph\n>> Nested\n\nAfter";
+        let lines = parse_markdown(markdown, LinkStyle::default());
+        let rendered: Vec<String> = lines
+            .iter()
+            .map(|line| line.spans.iter().map(|span| span.content.as_ref()).collect())
+            .collect();
+
+        assert_eq!(rendered, vec![
+            "│ First line continues here",
+            "│",
+            "│ Second paragraph",
+            "│",
+            "│ │ Nested",
+            "",
+            "After",
+        ]);
+        assert_eq!(lines[0].spans[0].style, QUOTE_BAR_STYLE);
+        assert_eq!(lines[0].spans[1].style, QUOTE_STYLE);
+        assert_eq!(lines[6].spans[0].style, Style::default());
+    }
+
+    #[test]
+    fn test_heading_levels_and_rules() {
+        let levels = [
+            HeadingLevel::H1, HeadingLevel::H2, HeadingLevel::H3,
+            HeadingLevel::H4, HeadingLevel::H5, HeadingLevel::H6,
+        ];
+        for (i, level) in levels.into_iter().enumerate() {
+            let markdown = format!("{} Title\n\nBody", "#".repeat(i + 1));
+            let lines = parse_markdown(&markdown, LinkStyle::default());
+            assert_eq!(lines[0].spans[0].content, "Title");
+            assert_eq!(lines[0].spans[0].style, heading_style(level));
+            assert_eq!(lines[1].width(), 0);
+            assert_eq!(lines[2].spans[0].style, Style::default());
+        }
+        assert_eq!(parse_markdown("Title\n===", LinkStyle::default())[0].spans[0].style, heading_style(HeadingLevel::H1));
+
+        // A rule fills the wrap width
+        let lines = wrap_markdown_lines(parse_markdown("Above\n\n---\n\nBelow", LinkStyle::default()), 30);
+        assert_eq!(lines[2].spans[0].content, "─".repeat(30));
+
+        // `---` right under text is the text followed by a rule, not a heading
+        let lines = parse_markdown("Some text\n---\nMore", LinkStyle::default());
+        assert_eq!(lines[0].spans[0].style, Style::default());
+        assert!(is_rule_line(&lines[1]));
+        assert_eq!(lines[3].spans[0].content, "More");
+    }
+
+    #[test]
+    fn test_code_blocks_are_indexed_in_order() {
+        let markdown = "Intro\n\n```rust\nfn one() {}\n```\n\nThen:\n\n```\ntwo\nlines\n```\n\n    indented three\n";
+        assert_eq!(code_blocks(markdown), vec!["fn one() {}\n", "two\nlines\n", "indented three\n"]);
+
+        // Labels continue from the previous message's count
+        let mut next = 4;
+        let mut lines = parse_markdown(markdown, LinkStyle::default());
+        label_code_blocks(&mut lines, &mut next);
+        let tops: Vec<String> = lines
+            .iter()
+            .filter(|line| line.spans.len() == 1 && line.spans[0].content.starts_with("┌─"))
+            .map(|line| line.spans[0].content.to_string())
+            .collect();
+        assert_eq!(next, 7);
+        assert!(tops[0].starts_with("┌─ Code Block 4 ─"));
+        assert!(tops[2].starts_with("┌─ Code Block 6 ─"));
+        assert_eq!(tops[0].chars().count(), CODE_BLOCK_TOP.chars().count());
+    }
 }
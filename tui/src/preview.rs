@@ -0,0 +1,137 @@
+//! File previews for the `/open <path>:<line>` command
+
+use grok_core::tools::executors::FsExecutor;
+use grok_core::tools::FsReadResult;
+use grok_core::EventBus;
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+use serde_json::json;
+use std::path::Path;
+
+/// Lines shown on each side of the target line
+pub const PREVIEW_RADIUS: usize = 8;
+
+/// Header prefix of every preview
+const PREVIEW_HEADER: &str = "Preview: ";
+
+/// Gutter marker for the requested line
+const TARGET_MARKER: &str = "▶";
+
+/// Split `path:line` into its parts. A missing or invalid line number means line 1.
+pub fn parse_location(arg: &str) -> Option<(String, usize)> {
+    let arg = arg.trim();
+    if arg.is_empty() {
+        return None;
+    }
+    match arg.rsplit_once(':') {
+        Some((path, line)) if !path.is_empty() => match line.parse::<usize>() {
+            Ok(line) => Some((path.to_string(), line.max(1))),
+            Err(_) => Some((arg.to_string(), 1)),
+        },
+        _ => Some((arg.to_string(), 1)),
+    }
+}
+
+/// Read `path` (relative to `root`) through the fs.read executor and build a preview around `line`
+pub async fn load_preview(path: &str, line: usize, root: &Path) -> Result<String, String> {
+    // The preview isn't a tool call, so its progress events go nowhere
+    let bus = EventBus::new();
+    let executor = FsExecutor::new(bus.sender(), usize::MAX).with_root(Some(root.to_path_buf()));
+
+    let value = executor
+        .execute_read_with_result("open-preview".to_string(), json!({ "path": path }))
+        .await?;
+    let result: FsReadResult = serde_json::from_value(value)
+        .map_err(|e| format!("Unexpected fs.read result: {}", e))?;
+
+    Ok(build_preview(path, &result.contents, line, PREVIEW_RADIUS))
+}
+
+/// Render a window of `contents` centered on `line` (1-based). Lines past the end of
+/// the file are clamped to the last line and noted in the header.
+pub fn build_preview(path: &str, contents: &str, line: usize, radius: usize) -> String {
+    let lines: Vec<&str> = contents.lines().collect();
+    if lines.is_empty() {
+        return format!("{}{} is empty", PREVIEW_HEADER, path);
+    }
+
+    let total = lines.len();
+    let target = line.clamp(1, total);
+    let start = target.saturating_sub(radius).max(1);
+    let end = (target + radius).min(total);
+    let width = end.to_string().len();
+
+    let mut out = format!("{}{}:{} (lines {}-{} of {})", PREVIEW_HEADER, path, target, start, end, total);
+    if target != line {
+        out.push_str(&format!(" — line {} is past the end of the file", line));
+    }
+
+    for number in start..=end {
+        let marker = if number == target { TARGET_MARKER } else { " " };
+        out.push_str(&format!("\n{} {:>width$} │ {}", marker, number, lines[number - 1], width = width));
+    }
+    out
+}
+
+/// Style a preview for the chat panel, highlighting the target line
+pub fn preview_lines(content: &str) -> Vec<Line<'static>> {
+    content
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let style = if i == 0 {
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+            } else if line.starts_with(TARGET_MARKER) {
+                Style::default().fg(Color::Black).bg(Color::Yellow).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::Gray)
+            };
+            Line::from(Span::styled(line.to_string(), style))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbered(n: usize) -> String {
+        (1..=n).map(|i| format!("line {}", i)).collect::<Vec<_>>().join("\n")
+    }
+
+    #[test]
+    fn test_parse_location() {
+        assert_eq!(parse_location("src/main.rs:42"), Some(("src/main.rs".to_string(), 42)));
+        assert_eq!(parse_location("src/main.rs"), Some(("src/main.rs".to_string(), 1)));
+        assert_eq!(parse_location("src/main.rs:0"), Some(("src/main.rs".to_string(), 1)));
+        assert_eq!(parse_location("  "), None);
+    }
+
+    #[test]
+    fn test_preview_centers_and_highlights_target() {
+        let preview = build_preview("file.rs", &numbered(100), 50, 3);
+        let lines: Vec<&str> = preview.lines().collect();
+
+        assert_eq!(lines[0], "Preview: file.rs:50 (lines 47-53 of 100)");
+        assert_eq!(lines.len(), 8);
+        // Target sits in the middle of the window with the marker
+        assert_eq!(lines[4], "▶ 50 │ line 50");
+        assert_eq!(lines.iter().filter(|l| l.starts_with(TARGET_MARKER)).count(), 1);
+
+        let styled = preview_lines(&preview);
+        assert_eq!(styled[4].spans[0].style.bg, Some(Color::Yellow));
+        assert_eq!(styled[3].spans[0].style.bg, None);
+    }
+
+    #[test]
+    fn test_preview_clamps_out_of_range_line() {
+        let preview = build_preview("file.rs", &numbered(5), 99, 3);
+        assert!(preview.contains("file.rs:5 (lines 2-5 of 5)"));
+        assert!(preview.contains("line 99 is past the end of the file"));
+        assert!(preview.lines().last().unwrap().starts_with("▶ 5 │"));
+
+        assert_eq!(build_preview("empty.rs", "", 3, 3), "Preview: empty.rs is empty");
+    }
+}
@@ -1,5 +1,5 @@
-use grok_core::{AppEvent, Session, TokenUsage, ChatMessage, MessageRole};
-use std::time::Instant;
+use grok_core::{AppEvent, PatchApprovalRequest, Session, TokenUsage, ChatMessage, MessageRole, ToolName};
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 use std::path::PathBuf;
 use std::fs;
@@ -14,11 +14,350 @@ pub struct ChatInfo {
     pub last_modified: SystemTime,
 }
 
+/// Default for `GROK_SCROLL_STICK_LINES`
+const DEFAULT_SCROLL_STICK_LINES: usize = 3;
+
+/// Whether `scroll` is within `threshold` lines of `max_scroll`
+pub fn is_near_bottom(scroll: usize, max_scroll: usize, threshold: usize) -> bool {
+    max_scroll.saturating_sub(scroll) <= threshold
+}
+
+/// Scroll offset to render after content changes from `prev_max` to `new_max`.
+/// A view near the bottom follows new content; otherwise the offset is only clamped.
+pub fn follow_scroll(scroll: usize, prev_max: usize, new_max: usize, threshold: usize) -> usize {
+    if new_max != prev_max && is_near_bottom(scroll, prev_max, threshold) {
+        new_max
+    } else {
+        scroll.min(new_max)
+    }
+}
+
+/// Narrowest reading column accepted from `GROK_CHAT_WIDTH` or `/set width`
+pub const MIN_CHAT_COLUMN: usize = 20;
+
+/// Parse a reading column; `off` or `0` means use the full chat width
+pub fn parse_chat_column(value: &str) -> Result<Option<usize>, String> {
+    let value = value.trim();
+    if matches!(value.to_ascii_lowercase().as_str(), "off" | "0") {
+        return Ok(None);
+    }
+    match value.parse::<usize>() {
+        Ok(column) if column >= MIN_CHAT_COLUMN => Ok(Some(column)),
+        _ => Err(format!("Chat width must be a number of columns (at least {}) or 'off'", MIN_CHAT_COLUMN)),
+    }
+}
+
+/// Which key press in the input box sends the message, from `GROK_SUBMIT_KEY`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SubmitMode {
+    /// Enter sends
+    #[default]
+    Enter,
+    /// Ctrl+Enter sends; Enter inserts a newline. Alt+Enter and Ctrl+J also send, for
+    /// terminals that report Ctrl+Enter as a plain Enter or a line feed
+    CtrlEnter,
+    /// Enter inserts a newline; a second Enter straight after sends
+    DoubleEnter,
+}
+
+impl SubmitMode {
+    /// Parse `enter`, `ctrl-enter` or `double-enter`
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_ascii_lowercase().replace('_', "-").as_str() {
+            "enter" => Ok(Self::Enter),
+            "ctrl-enter" | "ctrl+enter" => Ok(Self::CtrlEnter),
+            "double-enter" => Ok(Self::DoubleEnter),
+            other => Err(format!("Unknown submit key '{}'; use enter, ctrl-enter or double-enter", other)),
+        }
+    }
+
+    /// The mode named by `GROK_SUBMIT_KEY`, or Enter when unset or invalid
+    pub fn from_env() -> Self {
+        std::env::var("GROK_SUBMIT_KEY")
+            .ok()
+            .and_then(|v| Self::parse(&v).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// Panel indices in `AppState::focused_panel`
+pub const INPUT_PANEL: usize = 0;
+pub const CHAT_PANEL: usize = 1;
+pub const TOOLS_PANEL: usize = 2;
+
+/// Order Tab moves focus through the panels, from `GROK_FOCUS_ORDER`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FocusOrder(Vec<usize>);
+
+impl Default for FocusOrder {
+    fn default() -> Self {
+        Self(vec![INPUT_PANEL, CHAT_PANEL, TOOLS_PANEL])
+    }
+}
+
+impl FocusOrder {
+    /// Parse a comma-separated list of `input`, `chat` and `tools`; panels left out are never focused by Tab
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let mut panels = Vec::new();
+        for name in value.split(',').map(|n| n.trim().to_ascii_lowercase()).filter(|n| !n.is_empty()) {
+            let panel = match name.as_str() {
+                "input" => INPUT_PANEL,
+                "chat" => CHAT_PANEL,
+                "tools" => TOOLS_PANEL,
+                other => return Err(format!("Unknown panel '{}'; use input, chat or tools", other)),
+            };
+            if !panels.contains(&panel) {
+                panels.push(panel);
+            }
+        }
+        if !panels.contains(&INPUT_PANEL) {
+            return Err("The focus order must include input".to_string());
+        }
+        Ok(Self(panels))
+    }
+
+    /// The order named by `GROK_FOCUS_ORDER`, or input, chat, tools when unset or invalid
+    pub fn from_env() -> Self {
+        std::env::var("GROK_FOCUS_ORDER")
+            .ok()
+            .and_then(|v| Self::parse(&v).ok())
+            .unwrap_or_default()
+    }
+
+    /// The panel after `current` among those `focusable` accepts, wrapping around
+    pub fn next(&self, current: usize, focusable: impl Fn(usize) -> bool) -> usize {
+        let panels: Vec<usize> = self.0.iter().copied().filter(|p| focusable(*p)).collect();
+        match panels.iter().position(|p| *p == current) {
+            Some(i) => panels[(i + 1) % panels.len()],
+            None => panels.first().copied().unwrap_or(INPUT_PANEL),
+        }
+    }
+}
+
+/// Default for `GROK_TOOL_PREVIEW_BYTES`
+pub const DEFAULT_TOOL_PREVIEW_BYTES: usize = 5000;
+
+/// Default for `GROK_INPUT_WARN_TOKENS`
+const DEFAULT_INPUT_WARN_TOKENS: usize = 8000;
+
+/// Longest tail of command output mirrored into the chat
+const LIVE_OUTPUT_WIDTH: usize = 120;
+
+/// The latest line of output from a running command, shown in the chat as a
+/// status line that is replaced on each chunk and removed when the tool ends
+#[derive(Debug, Clone, Default)]
+pub struct LiveOutput {
+    enabled: bool,
+    current: Option<(String, String)>,
+}
+
+impl LiveOutput {
+    pub fn new(enabled: bool) -> Self {
+        Self { enabled, current: None }
+    }
+
+    /// Enabled unless `GROK_SHELL_LIVE_OUTPUT` is `0`, `false` or `off`
+    pub fn from_env() -> Self {
+        let disabled = std::env::var("GROK_SHELL_LIVE_OUTPUT")
+            .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "0" | "false" | "off"))
+            .unwrap_or(false);
+        Self::new(!disabled)
+    }
+
+    /// Track output and completion events for the tool being mirrored
+    pub fn observe(&mut self, event: &AppEvent) {
+        match event {
+            AppEvent::ToolStdout { id, chunk } | AppEvent::ToolStderr { id, chunk } if self.enabled => {
+                if let Some(line) = chunk.lines().map(str::trim).rfind(|l| !l.is_empty()) {
+                    self.current = Some((id.clone(), tail(line, LIVE_OUTPUT_WIDTH)));
+                }
+            }
+            AppEvent::ToolEnd { id, .. } if self.current.as_ref().is_some_and(|(current, _)| current == id) => {
+                self.current = None;
+            }
+            _ => {}
+        }
+    }
+
+    /// The line to show, if a command is producing output
+    pub fn line(&self) -> Option<&str> {
+        self.current.as_ref().map(|(_, line)| line.as_str())
+    }
+}
+
+/// Tools that have begun and not yet ended, for the status bar's "Running:" label
+#[derive(Debug, Clone, Default)]
+pub struct RunningTools {
+    /// Tool call id and label, oldest first
+    running: Vec<(String, String)>,
+}
+
+impl RunningTools {
+    /// Track `ToolBegin` and `ToolEnd`
+    pub fn observe(&mut self, event: &AppEvent) {
+        match event {
+            AppEvent::ToolBegin { id, tool, summary, .. } => {
+                // "Executing: cargo test" becomes "shell.exec (cargo test)"
+                let label = match summary.split_once(": ").map(|(_, detail)| detail.trim()) {
+                    Some(detail) if !detail.is_empty() => format!("{} ({})", tool.wire_name(), detail),
+                    _ => tool.wire_name().to_string(),
+                };
+                self.running.push((id.clone(), label));
+            }
+            AppEvent::ToolEnd { id, .. } => {
+                self.running.retain(|(running, _)| running != id);
+            }
+            _ => {}
+        }
+    }
+
+    /// The most recently started tool still running
+    pub fn current(&self) -> Option<&str> {
+        self.running.last().map(|(_, label)| label.as_str())
+    }
+}
+
+/// Most paths kept by [`RecentFiles`]
+pub const MAX_RECENT_FILES: usize = 10;
+
+/// Files the agent read or wrote this session, for `/recent`
+#[derive(Debug, Clone, Default)]
+pub struct RecentFiles {
+    /// Most recent first, without duplicates
+    paths: Vec<String>,
+}
+
+impl RecentFiles {
+    /// Track the `path` argument of file reads and writes as they begin
+    pub fn observe(&mut self, event: &AppEvent) {
+        let AppEvent::ToolBegin { tool, args: Some(args), .. } = event else {
+            return;
+        };
+        let touches_file = matches!(
+            tool,
+            ToolName::FsRead
+                | ToolName::FsWrite
+                | ToolName::FsSetFile
+                | ToolName::FsReplaceOnce
+                | ToolName::FsInsertBefore
+                | ToolName::FsInsertAfter
+        );
+        if let Some(path) = args.get("path").and_then(|p| p.as_str()).filter(|_| touches_file) {
+            self.paths.retain(|p| p != path);
+            self.paths.insert(0, path.to_string());
+            self.paths.truncate(MAX_RECENT_FILES);
+        }
+    }
+
+    pub fn paths(&self) -> &[String] {
+        &self.paths
+    }
+}
+
+/// Keep the last `width` characters, marking the cut with an ellipsis
+fn tail(line: &str, width: usize) -> String {
+    let count = line.chars().count();
+    if count <= width {
+        return line.to_string();
+    }
+    let kept: String = line.chars().skip(count - width + 1).collect();
+    format!("…{}", kept)
+}
+
+/// How long a notice stays on screen
+pub const TOAST_DURATION: Duration = Duration::from_secs(5);
+
+/// Most notices shown at once; older ones are dropped first
+const MAX_TOASTS: usize = 3;
+
+/// Short-lived notices (provider fallback, retries) shown over the UI instead of in the chat
+#[derive(Debug, Clone)]
+pub struct Toasts {
+    duration: Duration,
+    items: Vec<(String, Instant)>,
+}
+
+impl Default for Toasts {
+    fn default() -> Self {
+        Self::new(TOAST_DURATION)
+    }
+}
+
+impl Toasts {
+    pub fn new(duration: Duration) -> Self {
+        Self { duration, items: Vec::new() }
+    }
+
+    /// Show `message` from `now` until the duration passes
+    pub fn push(&mut self, message: String, now: Instant) {
+        self.items.push((message, now + self.duration));
+        if self.items.len() > MAX_TOASTS {
+            self.items.remove(0);
+        }
+    }
+
+    /// Drop notices whose time is up
+    pub fn expire(&mut self, now: Instant) {
+        self.items.retain(|(_, expires_at)| *expires_at > now);
+    }
+
+    /// Notices to draw, oldest first
+    pub fn messages(&self) -> impl Iterator<Item = &str> {
+        self.items.iter().map(|(message, _)| message.as_str())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+/// An `fs.apply_patch` call waiting for the user to pick which ops to apply
+#[derive(Debug)]
+pub struct PendingApproval {
+    pub request: PatchApprovalRequest,
+    /// One flag per op; every op starts approved
+    pub approved: Vec<bool>,
+    /// Op whose diff is shown
+    pub selected: usize,
+}
+
+impl PendingApproval {
+    pub fn new(request: PatchApprovalRequest) -> Self {
+        let approved = vec![true; request.ops.len()];
+        Self { request, approved, selected: 0 }
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.approved.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn toggle_selected(&mut self) {
+        if let Some(flag) = self.approved.get_mut(self.selected) {
+            *flag = !*flag;
+        }
+    }
+}
+
 /// Command for the command palette
 #[derive(Debug, Clone)]
 pub struct Command {
     pub name: String,
     pub description: String,
+    /// Argument syntax shown in the palette; `<...>` is required, `[...]` optional
+    pub args_hint: Option<String>,
+}
+
+impl Command {
+    /// Whether the command can't run without an argument
+    pub fn requires_args(&self) -> bool {
+        self.args_hint.as_deref().is_some_and(|hint| hint.starts_with('<'))
+    }
 }
 
 pub fn scan_chats(dir: &PathBuf) -> Result<Vec<ChatInfo>> {
@@ -27,7 +366,7 @@ pub fn scan_chats(dir: &PathBuf) -> Result<Vec<ChatInfo>> {
     if let Ok(entries) = fs::read_dir(dir) {
         for entry in entries.flatten() {
             let path = entry.path();
-            if path.is_file() && path.extension().map_or(false, |e| e == "json") {
+            if path.is_file() && path.extension().is_some_and(|e| e == "json") {
                 if let Ok(contents) = fs::read_to_string(&path) {
                     if let Ok(messages) = serde_json::from_str::<Vec<ChatMessage>>(&contents) {
                         if !messages.is_empty() {
@@ -55,7 +394,7 @@ pub fn scan_chats(dir: &PathBuf) -> Result<Vec<ChatInfo>> {
             }
         }
     }
-    chats.sort_by(|a, b| b.last_modified.cmp(&a.last_modified));
+    chats.sort_by_key(|c| std::cmp::Reverse(c.last_modified));
     Ok(chats)
 }
 
@@ -70,7 +409,7 @@ pub fn sanitize_filename(name: &str) -> String {
 }
 
 pub fn save_chat(session: &Session, path: &PathBuf) -> Result<()> {
-    let contents = serde_json::to_string_pretty(&session.messages())?;
+    let contents = serde_json::to_string_pretty(&session.persisted_messages())?;
     fs::write(path, contents)?;
     Ok(())
 }
@@ -113,11 +452,60 @@ pub struct AppState {
     /// Currently focused panel (0 = chat input, 1 = chat history, 2 = tools)
     pub focused_panel: usize,
 
-    /// Whether to auto-scroll chat to bottom on new messages
-    pub auto_scroll_chat: bool,
+    /// Largest chat scroll offset as of the last render
+    pub chat_max_scroll: usize,
+
+    /// Largest tools scroll offset as of the last render
+    pub tools_max_scroll: usize,
+
+    /// Keep following new content while within this many lines of the bottom
+    pub scroll_stick_lines: usize,
+
+    /// Longest tool result shown in the tools panel, in bytes; 0 shows results in full
+    pub tool_preview_bytes: usize,
+
+    /// Tail of the running command's output mirrored into the chat
+    pub live_output: LiveOutput,
+
+    /// Tools in flight, shown in the status bar
+    pub running_tools: RunningTools,
+
+    /// Files recently read or written by tools, listed by `/recent`
+    pub recent_files: RecentFiles,
+
+    /// Every event appended to `GROK_EVENT_LOG`, when set
+    pub event_log: Option<grok_core::event_log::EventLog>,
+
+    /// Transient notices drawn over the top of the screen
+    pub toasts: Toasts,
+
+    /// Column prose wraps at in the chat; `None` uses the full panel width
+    pub chat_column: Option<usize>,
+
+    /// Whether each chat message shows when it was sent
+    pub show_timestamps: bool,
 
-    /// Whether to auto-scroll tools to bottom on new tools/updates
-    pub auto_scroll_tools: bool,
+    /// How markdown links in agent messages are shown
+    pub link_style: crate::markdown::LinkStyle,
+
+    /// File `/edit` asked to open; the app loop suspends the TUI to run the editor
+    pub pending_edit: Option<PathBuf>,
+
+    /// Code block `/copyblock` picked, as its number and source; the app loop copies it
+    pub pending_copy: Option<(usize, String)>,
+
+    /// Key press that sends the input
+    pub submit_mode: SubmitMode,
+
+    /// Panels Tab cycles through
+    pub focus_order: FocusOrder,
+
+    /// The last key was an Enter that inserted a newline, so another Enter sends
+    /// under [`SubmitMode::DoubleEnter`]
+    pub enter_pending: bool,
+
+    /// Estimated input size in tokens at which the input counter turns red; 0 disables it
+    pub input_token_warn: usize,
 
     /// Whether cursor is visible (for blinking effect)
     pub cursor_visible: bool,
@@ -125,6 +513,9 @@ pub struct AppState {
     /// Last time cursor blinked
     pub last_cursor_blink: Instant,
 
+    /// `fs.apply_patch` ops awaiting the user's approval; the modal is shown while set
+    pub pending_approval: Option<PendingApproval>,
+
     /// Command palette state
     pub command_palette_open: bool,
 
@@ -140,6 +531,9 @@ pub struct AppState {
     /// Current token usage total
     pub current_token_usage: Option<TokenUsage>,
 
+    /// Model the provider reported for the latest response
+    pub last_model: Option<String>,
+
     /// Directory for chat files
     pub chats_dir: PathBuf,
 
@@ -166,30 +560,127 @@ impl AppState {
             Command {
                 name: "/context".to_string(),
                 description: "Show current token usage information".to_string(),
+                args_hint: None,
+            },
+            Command {
+                name: "/stats".to_string(),
+                description: "Summarize this session: messages, tool calls, tokens, bytes and duration".to_string(),
+                args_hint: None,
             },
             Command {
                 name: "/quit".to_string(),
                 description: "Exit the application".to_string(),
+                args_hint: None,
             },
             Command {
                 name: "/clear".to_string(),
                 description: "Clear conversation history and start new chat".to_string(),
+                args_hint: None,
+            },
+            Command {
+                name: "/approve".to_string(),
+                description: "Show or set whether fs.apply_patch waits for approval".to_string(),
+                args_hint: Some("[auto|ask]".to_string()),
+            },
+            Command {
+                name: "/readonly".to_string(),
+                description: "Toggle blocking of tools that write files or run commands".to_string(),
+                args_hint: Some("[on|off]".to_string()),
+            },
+            Command {
+                name: "/reload".to_string(),
+                description: "Re-read .grok/system_prompt.md and AGENTS.md".to_string(),
+                args_hint: None,
             },
             Command {
                 name: "/info".to_string(),
                 description: "Show agent information".to_string(),
+                args_hint: None,
+            },
+            Command {
+                name: "/env".to_string(),
+                description: "Show the active configuration (API keys shown only as set/unset)".to_string(),
+                args_hint: None,
+            },
+            Command {
+                name: "/notools".to_string(),
+                description: "Answer the next message without using tools".to_string(),
+                args_hint: None,
             },
             Command {
                 name: "/new".to_string(),
                 description: "Start a new chat".to_string(),
+                args_hint: None,
             },
             Command {
                 name: "/save".to_string(),
                 description: "Save current chat with a title based on first message".to_string(),
+                args_hint: None,
             },
             Command {
                 name: "/load".to_string(),
                 description: "Load a specific chat (use chat list)".to_string(),
+                args_hint: None,
+            },
+            Command {
+                name: "/open".to_string(),
+                description: "Preview a file around a line (/open path:line)".to_string(),
+                args_hint: Some("<path>:<line>".to_string()),
+            },
+            Command {
+                name: "/recent".to_string(),
+                description: "List files tools recently read or wrote (/recent N puts path N in the input)".to_string(),
+                args_hint: Some("[N]".to_string()),
+            },
+            Command {
+                name: "/diff".to_string(),
+                description: "Show a unified diff between two files (/diff pathA pathB)".to_string(),
+                args_hint: Some("<pathA> <pathB>".to_string()),
+            },
+            Command {
+                name: "/attach".to_string(),
+                description: "Attach an image to the next message (vision models only)".to_string(),
+                args_hint: Some("<image path>".to_string()),
+            },
+            Command {
+                name: "/history".to_string(),
+                description: "List messages in the conversation (/history clear-last N to drop some)".to_string(),
+                args_hint: Some("[clear-last N]".to_string()),
+            },
+            Command {
+                name: "/set".to_string(),
+                description: "Change a display setting (/set width N|off, /set timestamps on|off, /set links inline|text)".to_string(),
+                args_hint: Some("<width N|off | timestamps on|off | links inline|text>".to_string()),
+            },
+            Command {
+                name: "/copyblock".to_string(),
+                description: "Copy a code block from the chat (the latest by default) to the clipboard".to_string(),
+                args_hint: Some("[N]".to_string()),
+            },
+            Command {
+                name: "/edit".to_string(),
+                description: "Open a file in $EDITOR, then return to the chat (/edit path)".to_string(),
+                args_hint: Some("<path>".to_string()),
+            },
+            Command {
+                name: "/pin".to_string(),
+                description: "Keep a message through /clear (/pin N, numbered as in /history; default: latest)".to_string(),
+                args_hint: Some("[N]".to_string()),
+            },
+            Command {
+                name: "/unpin".to_string(),
+                description: "Stop keeping a pinned message (/unpin N; default: latest pinned)".to_string(),
+                args_hint: Some("[N]".to_string()),
+            },
+            Command {
+                name: "/export".to_string(),
+                description: "Write the conversation to a Markdown file (/export path)".to_string(),
+                args_hint: Some("<path>".to_string()),
+            },
+            Command {
+                name: "/cd".to_string(),
+                description: "Change the directory tools work in (/cd path)".to_string(),
+                args_hint: Some("[path]".to_string()),
             },
         ];
 
@@ -207,15 +698,44 @@ impl AppState {
             tools_scroll: 0,
             input_scroll: 0,
             focused_panel: if show_chat_list { 1 } else { 0 },
-            auto_scroll_chat: true,
-            auto_scroll_tools: true,
+            chat_max_scroll: 0,
+            tools_max_scroll: 0,
+            scroll_stick_lines: std::env::var("GROK_SCROLL_STICK_LINES")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(DEFAULT_SCROLL_STICK_LINES),
+            tool_preview_bytes: std::env::var("GROK_TOOL_PREVIEW_BYTES")
+                .ok()
+                .and_then(|s| s.trim().parse().ok())
+                .unwrap_or(DEFAULT_TOOL_PREVIEW_BYTES),
+            live_output: LiveOutput::from_env(),
+            running_tools: RunningTools::default(),
+            recent_files: RecentFiles::default(),
+            toasts: Toasts::default(),
+            event_log: grok_core::event_log::EventLog::from_env(),
+            chat_column: std::env::var("GROK_CHAT_WIDTH")
+                .ok()
+                .and_then(|v| parse_chat_column(&v).ok().flatten()),
+            show_timestamps: false,
+            link_style: crate::markdown::LinkStyle::from_env(),
+            pending_edit: None,
+            pending_copy: None,
+            submit_mode: SubmitMode::from_env(),
+            focus_order: FocusOrder::from_env(),
+            enter_pending: false,
+            input_token_warn: std::env::var("GROK_INPUT_WARN_TOKENS")
+                .ok()
+                .and_then(|s| s.trim().parse().ok())
+                .unwrap_or(DEFAULT_INPUT_WARN_TOKENS),
             cursor_visible: true,
             last_cursor_blink: Instant::now(),
+            pending_approval: None,
             command_palette_open: false,
             command_palette_selected: 0,
             command_palette_filter: String::new(),
             available_commands,
             current_token_usage: None,
+            last_model: None,
             chats_dir,
             available_chats,
             show_chat_list,
@@ -225,6 +745,94 @@ impl AppState {
         }
     }
 
+    /// Whether the chat view is close enough to the bottom to follow new content
+    pub fn chat_near_bottom(&self) -> bool {
+        is_near_bottom(self.chat_scroll, self.chat_max_scroll, self.scroll_stick_lines)
+    }
+
+    /// Whether the tools view is close enough to the bottom to follow new content
+    pub fn tools_near_bottom(&self) -> bool {
+        is_near_bottom(self.tools_scroll, self.tools_max_scroll, self.scroll_stick_lines)
+    }
+
+    /// Split the palette filter into the command-name filter and any argument typed after a space
+    pub fn palette_query(&self) -> (&str, Option<&str>) {
+        match self.command_palette_filter.split_once(' ') {
+            Some((name, arg)) => (name, Some(arg.trim()).filter(|arg| !arg.is_empty())),
+            None => (self.command_palette_filter.as_str(), None),
+        }
+    }
+
+    /// Commands whose name or description matches the palette filter, name matches first
+    pub fn filtered_commands(&self) -> Vec<&Command> {
+        let filter = self.palette_query().0.to_lowercase();
+        let (mut by_name, by_description): (Vec<&Command>, Vec<&Command>) = self
+            .available_commands
+            .iter()
+            .filter(|cmd| {
+                filter.is_empty()
+                    || cmd.name.to_lowercase().contains(&filter)
+                    || cmd.description.to_lowercase().contains(&filter)
+            })
+            .partition(|cmd| cmd.name.to_lowercase().contains(&filter));
+        by_name.extend(by_description);
+        by_name
+    }
+
+    /// Source of each code block in agent messages, in the order the chat numbers them from 1
+    pub fn code_blocks(&self) -> Vec<String> {
+        self.session
+            .messages()
+            .iter()
+            .filter(|msg| msg.role == grok_core::MessageRole::Agent)
+            .flat_map(|msg| crate::markdown::code_blocks(&msg.content))
+            .collect()
+    }
+
+    /// Close the command palette and forget its filter and selection
+    pub fn close_command_palette(&mut self) {
+        self.command_palette_open = false;
+        self.command_palette_filter.clear();
+        self.command_palette_selected = 0;
+    }
+
+    /// Answer the pending approval: `Some(all)` approves or rejects every op,
+    /// `None` sends the per-op choices
+    pub fn resolve_approval(&mut self, all: Option<bool>) {
+        if let Some(pending) = self.pending_approval.take() {
+            let approved = match all {
+                Some(all) => vec![all; pending.approved.len()],
+                None => pending.approved,
+            };
+            pending.request.respond(approved);
+        }
+    }
+
+    /// Move focus to the next panel in `focus_order`; the tools panel is skipped
+    /// while there are no tool calls to show
+    pub fn focus_next_panel(&mut self) {
+        let has_tools = !self.session.tool_messages().is_empty();
+        self.focused_panel = self.focus_order.next(self.focused_panel, |p| p != TOOLS_PANEL || has_tools);
+    }
+
+    /// Jump the chat view to the bottom; clamped on the next render
+    pub fn scroll_chat_to_bottom(&mut self) {
+        self.chat_scroll = usize::MAX;
+    }
+
+    /// Jump the tools view to the bottom; clamped on the next render
+    pub fn scroll_tools_to_bottom(&mut self) {
+        self.tools_scroll = usize::MAX;
+    }
+
+    /// Reset both scrollable panels to an empty, bottom-pinned state
+    pub fn reset_scroll(&mut self) {
+        self.chat_scroll = 0;
+        self.tools_scroll = 0;
+        self.chat_max_scroll = 0;
+        self.tools_max_scroll = 0;
+    }
+
     /// Update cursor blinking state
     pub fn update_cursor_blink(&mut self) {
         let now = Instant::now();
@@ -233,4 +841,132 @@ impl AppState {
             self.last_cursor_blink = now;
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_follow_scroll_sticks_when_near_bottom() {
+        // At the bottom
+        assert_eq!(follow_scroll(10, 10, 15, 3), 15);
+        // Scrolled up, but within the threshold
+        assert_eq!(follow_scroll(8, 10, 15, 3), 15);
+        // Fresh view with no prior content
+        assert_eq!(follow_scroll(0, 0, 4, 0), 4);
+    }
+
+    #[test]
+    fn test_follow_scroll_stays_put_when_scrolled_up() {
+        assert_eq!(follow_scroll(2, 10, 15, 3), 2);
+        // Without new content a near-bottom view isn't yanked back down
+        assert_eq!(follow_scroll(8, 10, 10, 3), 8);
+        // Offsets past the end are clamped
+        assert_eq!(follow_scroll(usize::MAX, 10, 10, 3), 10);
+        assert_eq!(follow_scroll(12, 12, 5, 3), 5);
+    }
+
+    #[test]
+    fn test_live_output_updates_in_place_and_clears_on_end() {
+        let mut live = LiveOutput::new(true);
+        live.observe(&AppEvent::ToolStdout { id: "t1".to_string(), chunk: "Compiling foo\n".to_string() });
+        assert_eq!(live.line(), Some("Compiling foo"));
+
+        // Each chunk replaces the line with its last non-empty line
+        live.observe(&AppEvent::ToolStderr { id: "t1".to_string(), chunk: "warning: x\nCompiling bar\n\n".to_string() });
+        assert_eq!(live.line(), Some("Compiling bar"));
+
+        // Another tool finishing leaves the line alone
+        live.observe(&AppEvent::ToolEnd { id: "t2".to_string(), ok: true, duration_ms: 1, result_bytes: None });
+        assert_eq!(live.line(), Some("Compiling bar"));
+
+        live.observe(&AppEvent::ToolEnd { id: "t1".to_string(), ok: true, duration_ms: 5, result_bytes: None });
+        assert_eq!(live.line(), None);
+    }
+
+    #[test]
+    fn test_running_tools_tracks_latest_and_clears() {
+        let begin = |id: &str, tool: grok_core::ToolName, summary: &str| AppEvent::ToolBegin {
+            id: id.to_string(),
+            tool,
+            summary: summary.to_string(),
+            args: None,
+        };
+        let end = |id: &str| AppEvent::ToolEnd { id: id.to_string(), ok: true, duration_ms: 1, result_bytes: None };
+
+        let mut running = RunningTools::default();
+        running.observe(&begin("t1", grok_core::ToolName::ShellExec, "Executing: cargo test"));
+        assert_eq!(running.current(), Some("shell.exec (cargo test)"));
+
+        running.observe(&begin("t2", grok_core::ToolName::FsApplyPatch, "Applying patch"));
+        assert_eq!(running.current(), Some("fs.apply_patch"));
+
+        // The earlier tool is shown again once the later one ends
+        running.observe(&end("t2"));
+        assert_eq!(running.current(), Some("shell.exec (cargo test)"));
+
+        running.observe(&end("t1"));
+        assert_eq!(running.current(), None);
+    }
+
+    #[test]
+    fn test_recent_files_newest_first_without_duplicates() {
+        let begin = |tool: grok_core::ToolName, path: &str| AppEvent::ToolBegin {
+            id: "t".to_string(),
+            tool,
+            summary: String::new(),
+            args: Some(serde_json::json!({ "path": path })),
+        };
+
+        let mut recent = RecentFiles::default();
+        recent.observe(&begin(grok_core::ToolName::FsRead, "src/a.rs"));
+        recent.observe(&begin(grok_core::ToolName::FsRead, "src/b.rs"));
+        recent.observe(&begin(grok_core::ToolName::FsWrite, "src/c.rs"));
+        // Reading a file again moves it to the front
+        recent.observe(&begin(grok_core::ToolName::FsRead, "src/a.rs"));
+        // Directory listings aren't files
+        recent.observe(&begin(grok_core::ToolName::FsTree, "src"));
+        assert_eq!(recent.paths(), ["src/a.rs", "src/c.rs", "src/b.rs"]);
+
+        for i in 0..20 {
+            recent.observe(&begin(grok_core::ToolName::FsRead, &format!("f{}.rs", i)));
+        }
+        assert_eq!(recent.paths().len(), MAX_RECENT_FILES);
+        assert_eq!(recent.paths()[0], "f19.rs");
+    }
+
+    #[test]
+    fn test_live_output_trims_and_respects_disable() {
+        let mut live = LiveOutput::new(true);
+        live.observe(&AppEvent::ToolStdout { id: "t1".to_string(), chunk: "x".repeat(500) });
+        let line = live.line().unwrap();
+        assert_eq!(line.chars().count(), LIVE_OUTPUT_WIDTH);
+        assert!(line.starts_with('…'));
+
+        let mut disabled = LiveOutput::new(false);
+        disabled.observe(&AppEvent::ToolStdout { id: "t1".to_string(), chunk: "output".to_string() });
+        assert_eq!(disabled.line(), None);
+    }
+
+    #[test]
+    fn test_is_near_bottom() {
+        assert!(is_near_bottom(7, 10, 3));
+        assert!(!is_near_bottom(6, 10, 3));
+        assert!(is_near_bottom(20, 10, 0));
+    }
+
+    #[test]
+    fn test_toasts_expire_after_duration() {
+        let start = Instant::now();
+        let mut toasts = Toasts::new(Duration::from_secs(5));
+        toasts.push("openrouter returned 502, trying next provider...".to_string(), start);
+        assert_eq!(toasts.messages().collect::<Vec<_>>(), vec!["openrouter returned 502, trying next provider..."]);
+
+        toasts.expire(start + Duration::from_secs(4));
+        assert!(!toasts.is_empty());
+
+        toasts.expire(start + Duration::from_secs(5));
+        assert!(toasts.is_empty());
+    }
+}
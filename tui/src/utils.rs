@@ -1,36 +1,388 @@
-/// Utility functions for the TUI application
+//! Utility functions for the TUI application
 
 /// Terminal management utilities
 pub mod terminal {
     use anyhow::Result;
     use crossterm::{
         execute,
-        terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-        event::{DisableMouseCapture, EnableMouseCapture},
+        terminal::{disable_raw_mode, enable_raw_mode, supports_keyboard_enhancement, EnterAlternateScreen, LeaveAlternateScreen},
+        event::{
+            DisableMouseCapture, EnableMouseCapture, KeyboardEnhancementFlags, PopKeyboardEnhancementFlags,
+            PushKeyboardEnhancementFlags,
+        },
     };
-    use ratatui::{backend::CrosstermBackend, Terminal};
-    use std::io;
-
-    /// Setup terminal for TUI mode
-    pub fn setup() -> Result<Terminal<CrosstermBackend<io::Stdout>>> {
-        enable_raw_mode()?;
-        let mut stdout = io::stdout();
-        execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-        let backend = CrosstermBackend::new(stdout);
-        let terminal = Terminal::new(backend)?;
-        Ok(terminal)
-    }
-
-    /// Restore terminal to normal mode
-    pub fn restore<B: ratatui::backend::Backend + std::io::Write>(terminal: &mut Terminal<B>) -> Result<()> {
-        disable_raw_mode()?;
-        execute!(
-            terminal.backend_mut(),
-            LeaveAlternateScreen,
-            DisableMouseCapture
-        )?;
-        terminal.show_cursor()?;
-        Ok(())
+    use ratatui::{
+        backend::{Backend, CrosstermBackend},
+        layout::Rect,
+        Terminal, TerminalOptions, Viewport,
+    };
+    use std::io::{self, Write};
+    use std::ops::{Deref, DerefMut};
+
+    /// Turns the terminal's raw mode on or off; tests swap in a fake since they have no tty
+    type RawMode = fn(bool) -> io::Result<()>;
+
+    fn crossterm_raw_mode(enable: bool) -> io::Result<()> {
+        if enable {
+            enable_raw_mode()
+        } else {
+            disable_raw_mode()
+        }
+    }
+
+    /// The TUI's terminal, in raw mode on the alternate screen with mouse capture for as
+    /// long as the guard lives. Dropping it restores the terminal, so normal exits, errors
+    /// and unwinding panics all clean up, and teardown runs at most once.
+    pub struct TerminalGuard<B: Backend + Write> {
+        terminal: Terminal<B>,
+        raw_mode: RawMode,
+        /// Whether the terminal takes the kitty keyboard protocol, which is what lets it
+        /// report modifiers on Enter (Ctrl+Enter) instead of sending a plain Enter
+        keyboard_enhancement: bool,
+        active: bool,
+    }
+
+    impl TerminalGuard<CrosstermBackend<io::Stdout>> {
+        /// Put stdout into TUI mode
+        pub fn new() -> Result<Self> {
+            let keyboard_enhancement = supports_keyboard_enhancement().unwrap_or(false);
+            Self::enter(CrosstermBackend::new(io::stdout()), None, crossterm_raw_mode, keyboard_enhancement)
+        }
+    }
+
+    impl<B: Backend + Write> TerminalGuard<B> {
+        /// `viewport` fixes the drawing area instead of asking the backend for its size
+        fn enter(backend: B, viewport: Option<Rect>, raw_mode: RawMode, keyboard_enhancement: bool) -> Result<Self> {
+            let terminal = match viewport {
+                Some(area) => Terminal::with_options(backend, TerminalOptions { viewport: Viewport::Fixed(area) })?,
+                None => Terminal::new(backend)?,
+            };
+            raw_mode(true)?;
+            let mut guard = Self { terminal, raw_mode, keyboard_enhancement, active: true };
+            guard.setup()?;
+            Ok(guard)
+        }
+
+        /// Switch screens and input modes; raw mode is already on. On failure the guard
+        /// is dropped by the caller, which restores whatever was set.
+        fn setup(&mut self) -> io::Result<()> {
+            execute!(self.terminal.backend_mut(), EnterAlternateScreen, EnableMouseCapture)?;
+            if self.keyboard_enhancement {
+                execute!(
+                    self.terminal.backend_mut(),
+                    PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES)
+                )?;
+            }
+            Ok(())
+        }
+
+        /// Hand the terminal back temporarily, e.g. while an external editor runs
+        pub fn suspend(&mut self) -> Result<()> {
+            self.teardown()?;
+            Ok(())
+        }
+
+        /// Return to TUI mode after [`TerminalGuard::suspend`]
+        pub fn resume(&mut self) -> Result<()> {
+            if self.active {
+                return Ok(());
+            }
+            (self.raw_mode)(true)?;
+            self.active = true;
+            self.setup()?;
+            // Whatever ran meanwhile drew over the screen, so repaint everything
+            self.terminal.clear()?;
+            Ok(())
+        }
+
+        fn teardown(&mut self) -> io::Result<()> {
+            if !self.active {
+                return Ok(());
+            }
+            self.active = false;
+            (self.raw_mode)(false)?;
+            if self.keyboard_enhancement {
+                execute!(self.terminal.backend_mut(), PopKeyboardEnhancementFlags)?;
+            }
+            execute!(self.terminal.backend_mut(), LeaveAlternateScreen, DisableMouseCapture)?;
+            self.terminal.show_cursor()
+        }
+    }
+
+    impl<B: Backend + Write> Deref for TerminalGuard<B> {
+        type Target = Terminal<B>;
+
+        fn deref(&self) -> &Terminal<B> {
+            &self.terminal
+        }
+    }
+
+    impl<B: Backend + Write> DerefMut for TerminalGuard<B> {
+        fn deref_mut(&mut self) -> &mut Terminal<B> {
+            &mut self.terminal
+        }
+    }
+
+    impl<B: Backend + Write> Drop for TerminalGuard<B> {
+        fn drop(&mut self) {
+            if let Err(e) = self.teardown() {
+                tracing::warn!("Failed to restore the terminal: {}", e);
+            }
+        }
+    }
+
+    /// Best-effort terminal restore straight to stdout, for when the guard can't run.
+    /// Errors are ignored, and calling it on an already restored terminal is harmless.
+    pub fn reset() {
+        let _ = disable_raw_mode();
+        let _ = execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture, crossterm::cursor::Show);
+    }
+
+    /// Restore the terminal before a panic message is printed, so the message is readable
+    /// and the shell isn't left in raw mode. Installed once; later calls do nothing.
+    pub fn install_panic_hook() {
+        static INSTALL: std::sync::Once = std::sync::Once::new();
+        INSTALL.call_once(|| chain_panic_hook(reset));
+    }
+
+    /// Run `cleanup` ahead of the previously installed panic hook, for panics on the
+    /// calling thread only: background tasks that panic on other threads are caught by
+    /// the runtime and mustn't tear down a TUI that's still running
+    pub fn chain_panic_hook(cleanup: impl Fn() + Send + Sync + 'static) {
+        let ui_thread = std::thread::current().id();
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            if std::thread::current().id() == ui_thread {
+                cleanup();
+            }
+            previous(info);
+        }));
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        static RAW_MODE_ON: AtomicBool = AtomicBool::new(false);
+        static RAW_MODE_DISABLES: AtomicUsize = AtomicUsize::new(0);
+
+        fn fake_raw_mode(enable: bool) -> io::Result<()> {
+            RAW_MODE_ON.store(enable, Ordering::SeqCst);
+            if !enable {
+                RAW_MODE_DISABLES.fetch_add(1, Ordering::SeqCst);
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn test_terminal_guard_drop_disables_raw_mode_once() {
+            let backend = CrosstermBackend::new(Vec::<u8>::new());
+            let mut guard = TerminalGuard::enter(backend, Some(Rect::new(0, 0, 20, 5)), fake_raw_mode, false).unwrap();
+            assert!(RAW_MODE_ON.load(Ordering::SeqCst));
+
+            guard.suspend().unwrap();
+            assert!(!RAW_MODE_ON.load(Ordering::SeqCst));
+            guard.resume().unwrap();
+            assert!(RAW_MODE_ON.load(Ordering::SeqCst));
+            assert_eq!(RAW_MODE_DISABLES.load(Ordering::SeqCst), 1);
+
+            drop(guard);
+            assert!(!RAW_MODE_ON.load(Ordering::SeqCst));
+            assert_eq!(RAW_MODE_DISABLES.load(Ordering::SeqCst), 2);
+        }
+
+        /// Writer whose output stays readable after the backend owning it is dropped
+        #[derive(Clone, Default)]
+        struct SharedOutput(Arc<std::sync::Mutex<Vec<u8>>>);
+
+        impl Write for SharedOutput {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.0.lock().unwrap().write(buf)
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        #[test]
+        fn test_terminal_guard_pushes_and_pops_keyboard_enhancement() {
+            fn noop_raw_mode(_: bool) -> io::Result<()> {
+                Ok(())
+            }
+            const PUSH: &str = "\x1b[>1u";
+            const POP: &str = "\x1b[<1u";
+            let written = |output: &SharedOutput| String::from_utf8_lossy(&output.0.lock().unwrap()).to_string();
+
+            let output = SharedOutput::default();
+            let backend = CrosstermBackend::new(output.clone());
+            let guard = TerminalGuard::enter(backend, Some(Rect::new(0, 0, 20, 5)), noop_raw_mode, true).unwrap();
+            assert!(written(&output).contains(PUSH));
+            assert!(!written(&output).contains(POP));
+            drop(guard);
+            assert!(written(&output).contains(POP));
+
+            // Terminals without the protocol get neither sequence
+            let output = SharedOutput::default();
+            let backend = CrosstermBackend::new(output.clone());
+            drop(TerminalGuard::enter(backend, Some(Rect::new(0, 0, 20, 5)), noop_raw_mode, false).unwrap());
+            assert!(!written(&output).contains(PUSH) && !written(&output).contains(POP));
+        }
+
+        #[test]
+        fn test_panic_hook_runs_cleanup_for_this_thread_only() {
+            let cleanups = Arc::new(AtomicUsize::new(0));
+            let counter = cleanups.clone();
+            chain_panic_hook(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+            });
+
+            // A panic elsewhere (like a background task) leaves the terminal alone
+            let _ = std::thread::spawn(|| panic!("background panic")).join();
+            assert_eq!(cleanups.load(Ordering::SeqCst), 0);
+
+            let result = std::panic::catch_unwind(|| panic!("simulated render panic"));
+            assert!(result.is_err());
+            assert_eq!(cleanups.load(Ordering::SeqCst), 1);
+        }
+    }
+}
+
+/// Command-line flag parsing
+pub mod args {
+    /// Value of `--cwd <path>` / `--cwd=<path>`, if given
+    pub fn cwd_flag(args: impl IntoIterator<Item = String>) -> Result<Option<String>, String> {
+        let mut args = args.into_iter();
+        let mut cwd = None;
+        while let Some(arg) = args.next() {
+            if let Some(value) = arg.strip_prefix("--cwd=") {
+                cwd = Some(value.to_string());
+            } else if arg == "--cwd" {
+                cwd = Some(args.next().ok_or_else(|| "--cwd requires a path".to_string())?);
+            }
+        }
+        Ok(cwd)
+    }
+}
+
+/// Asking for the OpenRouter API key when it isn't in the environment
+pub mod api_key {
+    use std::io::{self, BufRead, Write};
+
+    /// Exit code when the key is missing and there's no terminal to prompt on
+    pub const NOT_INTERACTIVE_EXIT_CODE: i32 = 2;
+
+    /// Shown instead of prompting when stdin is piped or closed (CI, scripts)
+    pub const NOT_INTERACTIVE_MESSAGE: &str = "Error: OPENROUTER_API_KEY is not set, and stdin is not a terminal to prompt for it.\n\
+        Set OPENROUTER_API_KEY in the environment or in a .env file (see Setup in README.md).\n\
+        Get a key from https://openrouter.ai/keys";
+
+    /// What [`prompt_for_key`] got
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum Prompted {
+        Key(String),
+        /// The user entered nothing (or closed stdin)
+        Empty,
+        /// No prompt was shown because input isn't a terminal
+        NotInteractive,
+    }
+
+    /// Prompt on `output` and read a key from `input`; `interactive` says whether
+    /// `input` is a terminal, since a piped stdin would just hit EOF
+    pub fn prompt_for_key(interactive: bool, input: &mut impl BufRead, output: &mut impl Write) -> io::Result<Prompted> {
+        if !interactive {
+            return Ok(Prompted::NotInteractive);
+        }
+        writeln!(output, "OpenRouter API key not found in environment.")?;
+        writeln!(output, "Get one from: https://openrouter.ai/keys")?;
+        write!(output, "Enter your API key: ")?;
+        output.flush()?;
+
+        let mut line = String::new();
+        input.read_line(&mut line)?;
+        Ok(match line.trim() {
+            "" => Prompted::Empty,
+            key => Prompted::Key(key.to_string()),
+        })
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_prompt_for_key() {
+            let mut output = Vec::new();
+            let key = prompt_for_key(true, &mut "sk-or-123\n".as_bytes(), &mut output).unwrap();
+            assert_eq!(key, Prompted::Key("sk-or-123".to_string()));
+            assert!(String::from_utf8(output).unwrap().ends_with("Enter your API key: "));
+
+            assert_eq!(prompt_for_key(true, &mut "".as_bytes(), &mut Vec::new()).unwrap(), Prompted::Empty);
+
+            // Piped stdin: nothing is printed or read
+            let mut output = Vec::new();
+            let mut input = "sk-or-123\n".as_bytes();
+            assert_eq!(prompt_for_key(false, &mut input, &mut output).unwrap(), Prompted::NotInteractive);
+            assert!(output.is_empty());
+            assert_eq!(input.len(), 10);
+        }
+    }
+}
+
+/// Tracing setup shared by the binaries
+pub mod logging {
+    use tracing::Level;
+    use tracing_subscriber::filter::{LevelFilter, Targets};
+    use tracing_subscriber::prelude::*;
+
+    /// Directives from `GROK_LOG`, then `RUST_LOG`, in `RUST_LOG` syntax
+    /// (`debug`, `grok_core=trace,warn`). Unset or unparsable means WARN.
+    pub fn filter_from_env() -> Targets {
+        let directives = std::env::var("GROK_LOG").or_else(|_| std::env::var("RUST_LOG")).ok();
+        filter_from(directives.as_deref())
+    }
+
+    pub fn filter_from(directives: Option<&str>) -> Targets {
+        directives
+            .map(str::trim)
+            .filter(|d| !d.is_empty())
+            .and_then(|d| d.parse().ok())
+            .unwrap_or_else(|| Targets::new().with_default(LevelFilter::from_level(Level::WARN)))
+    }
+
+    /// Log to stderr, filtered by [`filter_from_env`], so output never lands in the TUI
+    pub fn init() {
+        tracing_subscriber::fmt()
+            .with_writer(std::io::stderr)
+            .finish()
+            .with(filter_from_env())
+            .init();
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_filter_from_directives() {
+            let default = filter_from(None);
+            assert!(default.would_enable("grok_core", &Level::WARN));
+            assert!(!default.would_enable("grok_core", &Level::INFO));
+
+            let debug = filter_from(Some("debug"));
+            assert!(debug.would_enable("grok_tui", &Level::DEBUG));
+            assert!(!debug.would_enable("grok_tui", &Level::TRACE));
+
+            let targeted = filter_from(Some("grok_core=trace,error"));
+            assert!(targeted.would_enable("grok_core::agent", &Level::TRACE));
+            assert!(!targeted.would_enable("reqwest", &Level::WARN));
+
+            // Garbage falls back to the default rather than silencing everything
+            let invalid = filter_from(Some("=verbose="));
+            assert!(invalid.would_enable("grok_core", &Level::WARN));
+        }
     }
 }
 